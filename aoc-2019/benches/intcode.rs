@@ -0,0 +1,76 @@
+use aoc_2019::intcode::Stack;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use itertools::Itertools;
+
+/// A program shaped like a day 2 input: `1,noun,verb,3`, then a chain of additions and
+/// multiplications by constants which ends by writing its result to cell 0
+fn program() -> String {
+    let instructions = 40;
+    let constants = 4 + 4 * instructions + 1;
+
+    let mut memory = vec![1, 0, 0, 3];
+    for k in 0..instructions {
+        let address = 4 + 4 * k;
+        let opcode = if k % 3 == 2 { 2 } else { 1 };
+        let target = if k + 1 == instructions {
+            0
+        } else {
+            address + 3
+        };
+        memory.extend([opcode, constants + k % 5, address - 1, target]);
+    }
+    memory.push(99);
+    memory.extend((0..5).map(|k| k + 1));
+
+    memory.iter().join(",")
+}
+
+/// The answer for a noun and verb partway through the sweep
+fn target(program: &Stack) -> i64 {
+    let mut stack = program.clone();
+    stack.input(77, 33).unwrap();
+    stack.run_to_halt().unwrap();
+    stack.output().unwrap()
+}
+
+/// Tries every noun and verb, producing a fresh machine for each with `machine`
+fn sweep(target: i64, mut machine: impl FnMut() -> Stack) -> Option<i64> {
+    for noun in 0..=99 {
+        for verb in 0..=99 {
+            let mut stack = machine();
+            stack.input(noun, verb).unwrap();
+            stack.run_to_halt().unwrap();
+
+            if stack.output().unwrap() == target {
+                return Some(100 * noun + verb);
+            }
+        }
+    }
+
+    None
+}
+
+fn day02_sweep(c: &mut Criterion) {
+    let source = program();
+    let parsed: Stack = source.parse().unwrap();
+    let mut predecoded = parsed.clone();
+    predecoded.predecode();
+    let target = target(&parsed);
+
+    let mut group = c.benchmark_group("day02 sweep");
+
+    group.bench_function("reparse", |b| {
+        b.iter(|| sweep(target, || black_box(&source).parse().unwrap()))
+    });
+    group.bench_function("clone", |b| {
+        b.iter(|| sweep(target, || black_box(&parsed).clone()))
+    });
+    group.bench_function("predecoded clone", |b| {
+        b.iter(|| sweep(target, || black_box(&predecoded).clone()))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, day02_sweep);
+criterion_main!(benches);
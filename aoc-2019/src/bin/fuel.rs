@@ -0,0 +1,47 @@
+use anyhow::{anyhow, Result};
+use aoc_2019::fuel::FuelTable;
+use clap::Parser;
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+/// Plans the fuel for a list of module masses, one per line
+#[derive(Parser)]
+struct Cli {
+    /// File containing the masses. Reads from stdin if omitted
+    file: Option<PathBuf>,
+    /// Print a per-module table of mass, base fuel and total fuel instead of just the sums
+    #[arg(short, long)]
+    table: bool,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let input = match cli.file {
+        Some(path) => fs::read_to_string(path)?,
+        None => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            input
+        }
+    };
+
+    let masses = input
+        .lines()
+        .map(str::parse)
+        .collect::<Result<Vec<u64>, _>>()?;
+    let table = FuelTable::new(&masses);
+
+    if cli.table {
+        println!("{}", table);
+    } else {
+        let totals = table
+            .totals()
+            .ok_or_else(|| anyhow!("Total fuel overflows a u64"))?;
+        println!("Base fuel: {}", totals.base);
+        println!("Total fuel: {}", totals.total);
+    }
+
+    Ok(())
+}
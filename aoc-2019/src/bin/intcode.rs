@@ -0,0 +1,151 @@
+use anyhow::Result;
+use aoc_2019::intcode::ascii::Console;
+use aoc_2019::intcode::debugger::Breakpoint;
+use aoc_2019::intcode::{disassembler, Error, Stack, State};
+use clap::{Parser, Subcommand};
+use std::fs;
+use std::io::{self, BufReader, Read};
+use std::path::PathBuf;
+
+/// Tools for inspecting Intcode programs
+#[derive(Parser)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print a symbolic listing of a program
+    Disasm {
+        /// File containing the program. Reads from stdin if omitted
+        file: Option<PathBuf>,
+    },
+    /// Run a program, printing its outputs and reporting any breakpoints or watchpoints hit
+    Run {
+        /// File containing the program
+        file: PathBuf,
+        /// Values to feed to the program's input instructions
+        #[arg(short, long, value_delimiter = ',', allow_negative_numbers = true)]
+        input: Vec<i64>,
+        /// Print each instruction to stderr as it executes
+        #[arg(short, long)]
+        trace: bool,
+        /// Report when execution reaches an address
+        #[arg(long, value_name = "ADDRESS")]
+        break_at: Vec<usize>,
+        /// Report when an instruction with this opcode is about to execute
+        #[arg(long, value_name = "OPCODE")]
+        break_on: Vec<i64>,
+        /// Report writes to a memory cell
+        #[arg(long, value_name = "ADDRESS")]
+        watch: Vec<usize>,
+    },
+    /// Run a program which speaks ASCII, as a session driven from stdin or a script
+    Ascii {
+        /// File containing the program
+        file: PathBuf,
+        /// File of lines to send whenever the program asks for input, echoed into the transcript
+        #[arg(short, long)]
+        script: Option<PathBuf>,
+    },
+}
+
+fn read_program(file: Option<PathBuf>) -> Result<Stack> {
+    let source = match file {
+        Some(path) => fs::read_to_string(path)?,
+        None => {
+            let mut source = String::new();
+            io::stdin().read_to_string(&mut source)?;
+            source
+        }
+    };
+
+    Ok(source.parse()?)
+}
+
+fn debug(
+    mut stack: Stack,
+    trace: bool,
+    breakpoints: impl IntoIterator<Item = Breakpoint>,
+    watchpoints: impl IntoIterator<Item = usize>,
+) -> Result<()> {
+    let debugger = stack.debugger_mut();
+
+    if trace {
+        debugger.enable_tracing();
+    }
+    for breakpoint in breakpoints {
+        debugger.add_breakpoint(breakpoint);
+    }
+    for address in watchpoints {
+        debugger.add_watchpoint(address);
+    }
+
+    loop {
+        let state = stack.run()?;
+
+        for entry in stack.debugger_mut().take_trace() {
+            eprintln!("{}", entry);
+        }
+
+        match state {
+            State::Output(value) => println!("{}", value),
+            State::Halted => return Ok(()),
+            State::NeedsInput => {
+                return Err(Error::InputExhausted {
+                    pc: stack.instruction_pointer(),
+                }
+                .into())
+            }
+            State::Breakpoint(breakpoint) => eprintln!(
+                "Hit {:?} (relative base {})",
+                breakpoint,
+                stack.relative_base()
+            ),
+            State::Watchpoint { address, old, new } => {
+                eprintln!("Watched [{}] changed from {} to {}", address, old, new)
+            }
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Disasm { file } => println!("{}", disassembler::listing(&read_program(file)?)),
+        Command::Run {
+            file,
+            input,
+            trace,
+            break_at,
+            break_on,
+            watch,
+        } => {
+            let mut stack = read_program(Some(file))?;
+            stack.extend_inputs(input);
+
+            let breakpoints = break_at
+                .into_iter()
+                .map(Breakpoint::Address)
+                .chain(break_on.into_iter().map(Breakpoint::Opcode));
+
+            debug(stack, trace, breakpoints, watch)?;
+        }
+        Command::Ascii { file, script } => {
+            let mut console = Console::from(read_program(Some(file))?);
+
+            let state = match script {
+                Some(script) => {
+                    console.session(BufReader::new(fs::File::open(script)?), io::stdout(), true)?
+                }
+                None => console.session(io::stdin().lock(), io::stdout(), false)?,
+            };
+
+            if state == State::NeedsInput {
+                eprintln!("Program is still waiting for input");
+            }
+        }
+    }
+
+    Ok(())
+}
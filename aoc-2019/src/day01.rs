@@ -1,31 +1,24 @@
-use std::num::ParseIntError;
-use std::str::FromStr;
+use crate::fuel;
+use anyhow::{anyhow, Result};
+use aoc_common::input::{parse_lines, InputError};
 
-pub fn parse(input: &str) -> Result<Vec<u32>, ParseIntError> {
-    input.lines().map(FromStr::from_str).collect()
-}
-
-pub fn part_1(input: &[u32]) -> u32 {
-    input.iter().map(marginal_fuel_required).sum()
-}
+const DAY: u8 = 1;
 
-pub fn part_2(input: &[u32]) -> u32 {
-    input.iter().map(fuel_required).sum()
+pub fn parse(input: &str) -> Result<Vec<u64>, InputError> {
+    parse_lines(DAY, input, str::parse)
 }
 
-fn marginal_fuel_required(weight: &u32) -> u32 {
-    (weight / 3).checked_sub(2).unwrap_or_default()
+pub fn part_1(input: &[u64]) -> Result<u64> {
+    sum(input.iter().map(|mass| fuel::marginal_fuel_required(*mass)))
 }
 
-fn fuel_required(weight: &u32) -> u32 {
-    total_weight(weight) - weight
+pub fn part_2(input: &[u64]) -> Result<u64> {
+    sum(input.iter().map(|mass| fuel::fuel_required(*mass)))
 }
 
-fn total_weight(weight: &u32) -> u32 {
-    match marginal_fuel_required(weight) {
-        0 => *weight,
-        x => weight + total_weight(&x),
-    }
+fn sum(mut fuel: impl Iterator<Item = u64>) -> Result<u64> {
+    fuel.try_fold(0u64, |sum, fuel| sum.checked_add(fuel))
+        .ok_or_else(|| anyhow!("Total fuel overflows a u64"))
 }
 
 #[cfg(test)]
@@ -37,32 +30,30 @@ mod tests {
     #[test]
     fn test_parse() {
         assert_eq!(parse(SAMPLE), Ok(vec![12, 14, 1969, 100756]));
+        assert_eq!(
+            parse("12\n-14").map_err(|err| err.to_string()),
+            Err(String::from(
+                "Day 1, line 2, column 1: invalid digit found in string at \"-14\""
+            ))
+        );
     }
 
     #[test]
-    fn test_marginal_fuel_required() {
-        assert_eq!(marginal_fuel_required(&12), 2);
-        assert_eq!(marginal_fuel_required(&14), 2);
-        assert_eq!(marginal_fuel_required(&1969), 654);
-        assert_eq!(marginal_fuel_required(&100756), 33583);
-    }
-
-    #[test]
-    fn test_fuel_required() {
-        assert_eq!(fuel_required(&14), 2);
-        assert_eq!(fuel_required(&1969), 966);
-        assert_eq!(fuel_required(&100756), 50346);
+    fn test_part_1() -> Result<()> {
+        let parsed = parse(SAMPLE)?;
+        assert_eq!(part_1(&parsed)?, 34241);
+        Ok(())
     }
 
     #[test]
-    fn test_part_1() {
-        let parsed = parse(SAMPLE).unwrap();
-        assert_eq!(part_1(&parsed), 34241);
+    fn test_part_2() -> Result<()> {
+        let parsed = parse(SAMPLE)?;
+        assert_eq!(part_2(&parsed)?, 51316);
+        Ok(())
     }
 
     #[test]
-    fn test_part_2() {
-        let parsed = parse(SAMPLE).unwrap();
-        assert_eq!(part_2(&parsed), 51316);
+    fn test_overflow() {
+        assert!(part_1(&[u64::MAX; 4]).is_err());
     }
 }
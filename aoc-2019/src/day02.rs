@@ -1,27 +1,34 @@
+use crate::intcode::symbolic::Symbolic;
 use crate::intcode::Stack;
 use anyhow::{bail, Result};
 
-pub fn part_1(input: &str) -> Result<usize> {
+/// Day 2's programs only add and multiply on their way to halting, so one still running after
+/// this many steps never will
+const STEP_LIMIT: usize = 100_000;
+
+fn program(input: &str) -> Result<Stack> {
     let mut stack: Stack = input.parse()?;
+    stack.set_step_limit(Some(STEP_LIMIT));
+    Ok(stack)
+}
 
-    stack.input(12, 2);
+pub fn part_1(input: &str) -> Result<i64> {
+    let mut stack = program(input)?;
 
-    stack.run()?;
-    stack.output()
+    stack.input(12, 2)?;
+
+    stack.run_to_halt()?;
+    Ok(stack.output()?)
 }
 
-pub fn part_2(input: &str) -> Result<usize> {
-    for noun in 0..=99 {
-        for verb in 0..=99 {
-            let mut stack: Stack = input.parse()?;
-            stack.input(noun, verb);
-            stack.run()?;
+/// Solves for the noun and verb directly, by running the program with both left unknown
+pub fn part_2(input: &str) -> Result<i64> {
+    let stack = program(input)?;
+    let mut symbolic = Symbolic::new(&stack, &[1, 2]);
+    symbolic.run_to_halt()?;
 
-            if stack.output()? == 19690720 {
-                return Ok(100 * noun + verb);
-            }
-        }
+    match symbolic.output()?.solve(19690720, 0..=99).as_deref() {
+        Some([noun, verb]) => Ok(100 * noun + verb),
+        _ => bail!("No valid inputs found"),
     }
-
-    bail!("No valid inputs found");
 }
@@ -1,8 +1,12 @@
 use anyhow::{bail, Result};
+use aoc_common::geometry::{self, Direction};
+use aoc_common::input::{parse_each, InputError};
+use std::fmt;
 use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug, Hash, PartialEq)]
-pub struct Point(i64, i64);
+const DAY: u8 = 3;
+
+pub type Point = geometry::Point<i64>;
 
 #[derive(Debug, Hash, PartialEq)]
 pub enum Vector {
@@ -16,7 +20,7 @@ impl FromStr for Vector {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.split_at(1) {
+        match s.split_at(s.chars().next().map_or(0, char::len_utf8)) {
             ("R", d) => Ok(Vector::Right(d.parse()?)),
             ("L", d) => Ok(Vector::Left(d.parse()?)),
             ("U", d) => Ok(Vector::Up(d.parse()?)),
@@ -26,25 +30,115 @@ impl FromStr for Vector {
     }
 }
 
+impl fmt::Display for Vector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Vector::Right(d) => write!(f, "R{}", d),
+            Vector::Left(d) => write!(f, "L{}", d),
+            Vector::Up(d) => write!(f, "U{}", d),
+            Vector::Down(d) => write!(f, "D{}", d),
+        }
+    }
+}
+
 impl Vector {
-    fn points(&self, origin: &Point) -> Vec<Point> {
-        match &self {
-            Vector::Right(d) => (1..=*d)
-                .map(|i| Point(origin.0 + i as i64, origin.1))
-                .collect(),
-            Vector::Left(d) => (1..=*d)
-                .map(|i| Point(origin.0 - i as i64, origin.1))
-                .collect(),
-            Vector::Up(d) => (1..=*d)
-                .map(|i| Point(origin.0, origin.1 + i as i64))
-                .collect(),
-            Vector::Down(d) => (1..=*d)
-                .map(|i| Point(origin.0, origin.1 - i as i64))
-                .collect(),
+    fn length(&self) -> u64 {
+        match self {
+            Vector::Right(d) | Vector::Left(d) | Vector::Up(d) | Vector::Down(d) => u64::from(*d),
+        }
+    }
+
+    fn direction(&self) -> Direction {
+        match self {
+            Vector::Right(_) => Direction::Right,
+            Vector::Left(_) => Direction::Left,
+            Vector::Up(_) => Direction::Up,
+            Vector::Down(_) => Direction::Down,
+        }
+    }
+
+    fn end(&self, origin: &Point) -> Point {
+        *origin + self.direction().vector() * self.length() as i64
+    }
+}
+
+/// A straight run of wire
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Segment {
+    start: Point,
+    end: Point,
+    /// How far along the wire the segment starts
+    steps: u64,
+}
+
+impl Segment {
+    fn is_horizontal(&self) -> bool {
+        self.start.y == self.end.y
+    }
+
+    fn contains(&self, point: &Point) -> bool {
+        let between = |a: i64, b: i64, c: i64| a.min(b) <= c && c <= a.max(b);
+
+        between(self.start.x, self.end.x, point.x) && between(self.start.y, self.end.y, point.y)
+    }
+
+    /// How far along the wire a point on the segment is
+    fn steps_to(&self, point: &Point) -> u64 {
+        // Both points are on the segment, so the distance between them is never negative
+        self.steps + self.start.manhattan_distance(point) as u64
+    }
+
+    /// Where the segments cross. Segments along the same line can share a run of points, of which
+    /// only the ends and those nearest the origin are given: the distance to the origin and the
+    /// steps along both wires are least at one of them.
+    fn crossings(&self, other: &Segment) -> Vec<Point> {
+        match (self.is_horizontal(), other.is_horizontal()) {
+            (true, true) if self.start.y == other.start.y => {
+                overlap((self.start.x, self.end.x), (other.start.x, other.end.x))
+                    .into_iter()
+                    .map(|x| Point::new(x, self.start.y))
+                    .collect()
+            }
+            (false, false) if self.start.x == other.start.x => {
+                overlap((self.start.y, self.end.y), (other.start.y, other.end.y))
+                    .into_iter()
+                    .map(|y| Point::new(self.start.x, y))
+                    .collect()
+            }
+            (true, false) | (false, true) => {
+                let (horizontal, vertical) = if self.is_horizontal() {
+                    (self, other)
+                } else {
+                    (other, self)
+                };
+
+                let point = Point::new(vertical.start.x, horizontal.start.y);
+                if horizontal.contains(&point) && vertical.contains(&point) {
+                    vec![point]
+                } else {
+                    Vec::new()
+                }
+            }
+            _ => Vec::new(),
         }
     }
 }
 
+/// The ends of the run two spans of a line share, and the points on it nearest to 0 and either
+/// side of that
+fn overlap((a, b): (i64, i64), (c, d): (i64, i64)) -> Vec<i64> {
+    let (low, high) = (a.min(b).max(c.min(d)), a.max(b).min(c.max(d)));
+    if low > high {
+        return Vec::new();
+    }
+
+    let nearest = 0.clamp(low, high);
+    let mut points = vec![low, nearest - 1, nearest, nearest + 1, high];
+    points.retain(|point| (low..=high).contains(point));
+    points.dedup();
+    points
+}
+
 #[derive(Debug, Hash, PartialEq)]
 pub struct Wire(Vec<Vector>);
 
@@ -52,33 +146,46 @@ impl FromStr for Wire {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let vec = s.split(",").map(|x| x.parse()).collect::<Result<_>>()?;
+        let vec = s.split(',').map(|x| x.parse()).collect::<Result<_>>()?;
         Ok(Wire(vec))
     }
 }
 
 impl Wire {
-    fn points(&self) -> Vec<Point> {
-        let mut points = Vec::new();
+    fn segments(&self) -> Vec<Segment> {
+        let mut segments = Vec::with_capacity(self.0.len());
 
-        let mut current_point = Point(0, 0);
+        let mut current_point = Point::origin();
+        let mut steps = 0;
 
         for vector in &self.0 {
-            let mut next_points = vector.points(&current_point);
-            current_point = next_points.last().unwrap_or(&current_point).clone();
-            points.append(&mut next_points);
+            let end = vector.end(&current_point);
+            segments.push(Segment {
+                start: current_point,
+                end,
+                steps,
+            });
+
+            current_point = end;
+            steps += vector.length();
         }
 
-        points
+        segments
     }
 
-    fn intersections(&self, other: &Self) -> Vec<Point> {
+    /// Every point other than the origin where the wires cross, with the combined number of
+    /// steps both wires take to reach it
+    fn intersections(&self, other: &Self) -> Vec<(Point, u64)> {
+        let others = other.segments();
         let mut intersections = Vec::new();
-        
-        for point in self.points() {
-            for other_point in other.points() {
-                if point == other_point {
-                    intersections.push(point);
+
+        for segment in self.segments() {
+            for other_segment in &others {
+                for point in segment.crossings(other_segment) {
+                    if point != Point::origin() {
+                        let steps = segment.steps_to(&point) + other_segment.steps_to(&point);
+                        intersections.push((point, steps));
+                    }
                 }
             }
         }
@@ -87,28 +194,50 @@ impl Wire {
     }
 }
 
-pub fn parse(input: &str) -> Result<Vec<Wire>> {
-    input.lines().map(|line| line.parse()).collect()
+pub fn parse(input: &str) -> Result<Vec<Wire>, InputError> {
+    input
+        .lines()
+        .map(|line| parse_each(DAY, input, line.split(','), Vector::from_str).map(Wire))
+        .collect()
 }
 
-pub fn part_1(input: &[Wire]) -> usize {
-    if let Some(wire) = input.get(0) {
-        dbg!(wire.points());
+fn intersections(input: &[Wire]) -> Result<Vec<(Point, u64)>> {
+    match input {
+        [first, second] => Ok(first.intersections(second)),
+        _ => bail!("Expected two wires, found {}", input.len()),
     }
+}
 
-    input.len()
+pub fn part_1(input: &[Wire]) -> Result<u64> {
+    match intersections(input)?
+        .iter()
+        .map(|(point, _)| point.manhattan_distance(&Point::origin()) as u64)
+        .min()
+    {
+        Some(distance) => Ok(distance),
+        None => bail!("The wires never cross"),
+    }
 }
 
-pub fn part_2(input: &[Wire]) -> usize {
-    input.len()
+pub fn part_2(input: &[Wire]) -> Result<u64> {
+    match intersections(input)?.iter().map(|(_, steps)| *steps).min() {
+        Some(steps) => Ok(steps),
+        None => bail!("The wires never cross"),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::Vector::*;
     use super::*;
+    use aoc_common::strategy::{garbage, lines, malformed};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use std::collections::HashMap;
 
     static SAMPLE: &str = "R75,D30,R83,U83,L12,D49,R71,U7,L72\nU62,R66,U55,R34,D71,R55,D58,R83";
+    static SAMPLE_2: &str =
+        "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51\nU98,R91,D20,R16,D67,R40,U7,R15,U6,R7";
 
     #[test]
     fn test_parse() -> Result<()> {
@@ -142,47 +271,185 @@ mod tests {
     }
 
     #[test]
-    fn test_points() -> Result<()> {
+    fn test_parse_error() {
+        let err = parse("R8,U5\nU7,X6,D4").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 4, "X6"));
+        assert_eq!(err.message, "Unexpected Direction X");
+
+        assert!(parse("R8,,U5").is_err());
+    }
+
+    #[test]
+    fn test_segments() -> Result<()> {
         assert_eq!(
-            "R8,U5,L5,D3".parse::<Wire>()?.points(),
+            "R8,U5,L5,D3".parse::<Wire>()?.segments(),
             vec![
-                Point(1, 0),
-                Point(2, 0),
-                Point(3, 0),
-                Point(4, 0),
-                Point(5, 0),
-                Point(6, 0),
-                Point(7, 0),
-                Point(8, 0),
-                Point(8, 1),
-                Point(8, 2),
-                Point(8, 3),
-                Point(8, 4),
-                Point(8, 5),
-                Point(7, 5),
-                Point(6, 5),
-                Point(5, 5),
-                Point(4, 5),
-                Point(3, 5),
-                Point(3, 4),
-                Point(3, 3),
-                Point(3, 2)
+                Segment {
+                    start: Point::new(0, 0),
+                    end: Point::new(8, 0),
+                    steps: 0
+                },
+                Segment {
+                    start: Point::new(8, 0),
+                    end: Point::new(8, 5),
+                    steps: 8
+                },
+                Segment {
+                    start: Point::new(8, 5),
+                    end: Point::new(3, 5),
+                    steps: 13
+                },
+                Segment {
+                    start: Point::new(3, 5),
+                    end: Point::new(3, 2),
+                    steps: 18
+                }
             ]
         );
         Ok(())
     }
 
+    #[test]
+    fn test_intersections() -> Result<()> {
+        let first: Wire = "R8,U5,L5,D3".parse()?;
+        let second: Wire = "U7,R6,D4,L4".parse()?;
+
+        let mut intersections = first.intersections(&second);
+        intersections.sort_by_key(|(_, steps)| *steps);
+        assert_eq!(
+            intersections,
+            vec![(Point::new(6, 5), 30), (Point::new(3, 3), 40)]
+        );
+
+        // The shared origin is not a crossing
+        let third: Wire = "L3,D4".parse()?;
+        assert_eq!(first.intersections(&third), vec![]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_overlap() -> Result<()> {
+        let first: Wire = "L2,R10".parse()?;
+        let second: Wire = "R5".parse()?;
+
+        let mut intersections = first.intersections(&second);
+        intersections.sort_by_key(|(point, steps)| (*steps, point.x));
+        assert_eq!(
+            intersections,
+            vec![(Point::new(1, 0), 6), (Point::new(5, 0), 14)]
+        );
+
+        // The crossing nearest the origin can be in the middle of a run both wires share
+        assert_eq!(part_1(&parse("L1,U3,R5\nR1,U3,L5")?)?, 3);
+        assert_eq!(part_2(&parse("L2,R10\nR5")?)?, 6);
+
+        assert_eq!(overlap((0, 8), (3, 7)), vec![3, 4, 7]);
+        assert_eq!(overlap((8, -8), (-3, 7)), vec![-3, -1, 0, 1, 7]);
+        assert!(overlap((0, 2), (3, 7)).is_empty());
+        Ok(())
+    }
+
     #[test]
     fn test_part_1() -> Result<()> {
-        let parsed = parse(SAMPLE)?;
-        assert_eq!(part_1(&parsed), 2);
+        assert_eq!(part_1(&parse(SAMPLE)?)?, 159);
+        assert_eq!(part_1(&parse(SAMPLE_2)?)?, 135);
+        assert!(part_1(&parse("R8,U5,L5,D3")?).is_err());
         Ok(())
     }
 
     #[test]
     fn test_part_2() -> Result<()> {
-        let parsed = parse(SAMPLE)?;
-        assert_eq!(part_2(&parsed), 2);
+        assert_eq!(part_2(&parse(SAMPLE)?)?, 610);
+        assert_eq!(part_2(&parse(SAMPLE_2)?)?, 410);
         Ok(())
     }
+
+    fn vector() -> impl Strategy<Value = Vector> {
+        prop_oneof![
+            any::<u32>().prop_map(Right),
+            any::<u32>().prop_map(Left),
+            any::<u32>().prop_map(Up),
+            any::<u32>().prop_map(Down),
+        ]
+    }
+
+    fn vector_up_to(length: u32) -> impl Strategy<Value = Vector> {
+        prop_oneof![
+            (0..=length).prop_map(Right),
+            (0..=length).prop_map(Left),
+            (0..=length).prop_map(Up),
+            (0..=length).prop_map(Down),
+        ]
+    }
+
+    fn wires() -> impl Strategy<Value = String> {
+        let wire = vec(vector(), 1..10).prop_map(|vectors| {
+            vectors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        });
+
+        lines(wire, 2)
+    }
+
+    /// Every point each wire visits, with the fewest steps to reach it
+    fn visited(wire: &Wire) -> HashMap<Point, u64> {
+        let mut visited = HashMap::new();
+        let (mut point, mut steps) = (Point::origin(), 0);
+
+        for vector in &wire.0 {
+            for _ in 0..vector.length() {
+                point += vector.direction().vector();
+                steps += 1;
+                visited.entry(point).or_insert(steps);
+            }
+        }
+
+        visited
+    }
+
+    proptest! {
+        #[test]
+        fn test_crossings_match_visited_points(
+            first in vec(vector_up_to(5), 1..6),
+            second in vec(vector_up_to(5), 1..6),
+        ) {
+            let (first, second) = (Wire(first), Wire(second));
+            let (a, b) = (visited(&first), visited(&second));
+            let crossings = a
+                .iter()
+                .filter_map(|(point, steps)| Some((*point, steps + b.get(point)?)))
+                .filter(|(point, _)| *point != Point::origin())
+                .collect::<Vec<_>>();
+            let found = first.intersections(&second);
+
+            prop_assert_eq!(
+                found.iter().map(|(point, _)| point.manhattan_distance(&Point::origin())).min(),
+                crossings.iter().map(|(point, _)| point.manhattan_distance(&Point::origin())).min()
+            );
+            prop_assert_eq!(
+                found.iter().map(|(_, steps)| *steps).min(),
+                crossings.iter().map(|(_, steps)| *steps).min()
+            );
+        }
+
+        #[test]
+        fn test_vector_round_trip(vector in vector()) {
+            prop_assert_eq!(vector.to_string().parse::<Vector>().ok(), Some(vector));
+        }
+
+        #[test]
+        fn test_parse_wires(input in wires()) {
+            let wires = parse(&input).map_err(|err| TestCaseError::fail(err.to_string()))?;
+            prop_assert_eq!(wires.len(), 2);
+        }
+
+        #[test]
+        fn test_parse_garbage(input in prop_oneof![malformed(wires()), garbage()]) {
+            let _ = parse(&input);
+        }
+    }
 }
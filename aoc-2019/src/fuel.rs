@@ -0,0 +1,133 @@
+use itertools::Itertools;
+use std::fmt;
+use std::iter;
+
+/// The fuel needed to launch `mass` alone, ignoring the mass of the fuel itself
+pub fn marginal_fuel_required(mass: u64) -> u64 {
+    (mass / 3).saturating_sub(2)
+}
+
+/// The fuel for `mass`, followed by the fuel for that fuel, and so on until no more is needed
+pub fn fuel_chain(mass: u64) -> impl Iterator<Item = u64> {
+    iter::successors(Some(marginal_fuel_required(mass)), |fuel| {
+        Some(marginal_fuel_required(*fuel))
+    })
+    .take_while(|fuel| *fuel > 0)
+}
+
+/// The fuel needed to launch `mass`, including the fuel's own mass
+pub fn fuel_required(mass: u64) -> u64 {
+    // Each stage is under a third of the one before, so the sum is always less than `mass`
+    fuel_chain(mass).sum()
+}
+
+/// The fuel needed for a single module
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ModuleFuel {
+    pub mass: u64,
+    /// Fuel for the module's mass alone
+    pub base: u64,
+    /// Fuel for the module and all of its fuel
+    pub total: u64,
+}
+
+impl From<u64> for ModuleFuel {
+    fn from(mass: u64) -> Self {
+        ModuleFuel {
+            mass,
+            base: marginal_fuel_required(mass),
+            total: fuel_required(mass),
+        }
+    }
+}
+
+/// A per-module breakdown of fuel, with a row of totals
+#[derive(Clone, Debug, PartialEq)]
+pub struct FuelTable(pub Vec<ModuleFuel>);
+
+impl FuelTable {
+    pub fn new(masses: &[u64]) -> Self {
+        FuelTable(masses.iter().copied().map(ModuleFuel::from).collect())
+    }
+
+    /// The summed mass, base fuel and total fuel, or `None` if any of them overflows
+    pub fn totals(&self) -> Option<ModuleFuel> {
+        self.0.iter().try_fold(ModuleFuel::from(0), |sum, module| {
+            Some(ModuleFuel {
+                mass: sum.mass.checked_add(module.mass)?,
+                base: sum.base.checked_add(module.base)?,
+                total: sum.total.checked_add(module.total)?,
+            })
+        })
+    }
+}
+
+impl fmt::Display for FuelTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let row = |f: &mut fmt::Formatter<'_>, cells: [&dyn fmt::Display; 3]| {
+            writeln!(f, "{:>12}  {:>12}  {:>12}", cells[0], cells[1], cells[2])
+        };
+
+        row(f, [&"Mass", &"Base fuel", &"Total fuel"])?;
+        for module in &self.0 {
+            row(f, [&module.mass, &module.base, &module.total])?;
+        }
+
+        write!(f, "{}", (0..3).map(|_| "-".repeat(12)).join("  "))?;
+        match self.totals() {
+            Some(totals) => write!(
+                f,
+                "\n{:>12}  {:>12}  {:>12}",
+                totals.mass, totals.base, totals.total
+            ),
+            None => write!(f, "\n{:>12}", "overflow"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marginal_fuel_required() {
+        assert_eq!(marginal_fuel_required(12), 2);
+        assert_eq!(marginal_fuel_required(14), 2);
+        assert_eq!(marginal_fuel_required(1969), 654);
+        assert_eq!(marginal_fuel_required(100756), 33583);
+        assert_eq!(marginal_fuel_required(5), 0);
+    }
+
+    #[test]
+    fn test_fuel_chain() {
+        assert_eq!(fuel_chain(14).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(
+            fuel_chain(1969).collect::<Vec<_>>(),
+            vec![654, 216, 70, 21, 5]
+        );
+        assert_eq!(fuel_chain(2).count(), 0);
+    }
+
+    #[test]
+    fn test_fuel_required() {
+        assert_eq!(fuel_required(14), 2);
+        assert_eq!(fuel_required(1969), 966);
+        assert_eq!(fuel_required(100756), 50346);
+        assert!(fuel_required(u64::MAX) < u64::MAX / 2);
+    }
+
+    #[test]
+    fn test_table() {
+        let table = FuelTable::new(&[12, 1969]);
+        assert_eq!(
+            table.to_string(),
+            "        Mass     Base fuel    Total fuel
+          12             2             2
+        1969           654           966
+------------  ------------  ------------
+        1981           656           968"
+        );
+
+        assert_eq!(FuelTable::new(&[u64::MAX, 1]).totals(), None);
+    }
+}
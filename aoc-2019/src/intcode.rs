@@ -1,25 +1,115 @@
-use anyhow::{anyhow, bail, Result};
 use itertools::Itertools;
+use std::collections::VecDeque;
 use std::fmt;
+use std::num::ParseIntError;
 use std::str::FromStr;
+use thiserror::Error;
 
-#[derive(Debug)]
+pub mod ascii;
+pub mod assembler;
+pub mod debugger;
+pub mod disassembler;
+pub mod network;
+pub mod snapshot;
+pub mod symbolic;
+
+use debugger::{Breakpoint, Debugger, TraceEntry};
+use snapshot::Change;
+
+/// The default number of cells memory may grow to, 8 MiB worth of `i64`s
+pub const DEFAULT_MEMORY_LIMIT: usize = 1 << 20;
+
+/// Everything that can go wrong loading or running a program.
+/// Errors raised while running carry the instruction pointer `pc` of the failing instruction.
+#[derive(Error, Debug, PartialEq)]
+pub enum Error {
+    #[error("Invalid program: {0}")]
+    Parse(#[from] ParseIntError),
+    #[error("Invalid opcode {opcode} at {pc}")]
+    InvalidOpcode { pc: usize, opcode: i64 },
+    #[error("Invalid parameter mode {mode} in opcode {opcode} at {pc}")]
+    InvalidMode { pc: usize, opcode: i64, mode: i64 },
+    #[error("Address {address} accessed at {pc} is outside of memory, which is limited to {limit} cells")]
+    OutOfBounds {
+        pc: usize,
+        address: i64,
+        limit: usize,
+    },
+    #[error("Arithmetic at {pc} overflows a 64-bit value")]
+    Overflow { pc: usize },
+    #[error("Instruction at {pc} writes to an immediate parameter")]
+    WriteToImmediate { pc: usize },
+    #[error("Ran out of input at {pc}")]
+    InputExhausted { pc: usize },
+    #[error("Exceeded the limit of {limit} steps at {pc}")]
+    StepLimitExceeded { pc: usize, limit: usize },
+    #[error("Value computed at {pc} is not linear in the unknowns")]
+    NonLinear { pc: usize },
+    #[error("Cannot send {0:?} as ASCII input")]
+    NonAsciiInput(char),
+}
+
+/// Why a running machine handed control back to its caller
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum State {
+    /// The machine is blocked on an input instruction and the input queue is empty
+    NeedsInput,
+    /// The machine produced a value
+    Output(i64),
+    /// The machine reached a halt instruction
+    Halted,
+    /// The machine is about to execute an instruction matching a breakpoint
+    Breakpoint(Breakpoint),
+    /// The machine wrote to a watched memory cell
+    Watchpoint { address: usize, old: i64, new: i64 },
+}
+
+#[derive(Clone, Debug)]
 pub struct Stack {
-    memory: Vec<usize>,
+    memory: Vec<i64>,
+    /// Instructions already decoded, by address. Entries are cleared when a write touches any of
+    /// their cells, so self-modifying programs see their changes.
+    decoded: Vec<Option<Instruction>>,
+    memory_limit: usize,
     instruction_pointer: usize,
+    relative_base: i64,
+    inputs: VecDeque<i64>,
+    outputs: Vec<i64>,
+    steps: usize,
+    step_limit: Option<usize>,
+    /// Set once a halt instruction executes, so that running again doesn't execute it again
+    halted: bool,
+    debugger: Debugger,
+}
+
+impl From<Vec<i64>> for Stack {
+    fn from(memory: Vec<i64>) -> Self {
+        Stack {
+            memory,
+            decoded: Vec::new(),
+            memory_limit: DEFAULT_MEMORY_LIMIT,
+            instruction_pointer: 0,
+            relative_base: 0,
+            inputs: VecDeque::new(),
+            outputs: Vec::new(),
+            steps: 0,
+            step_limit: None,
+            halted: false,
+            debugger: Debugger::default(),
+        }
+    }
 }
 
 impl FromStr for Stack {
-    type Err = anyhow::Error;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Stack {
-            memory: s
+        Ok(Stack::from(
+            s.trim()
                 .split(',')
                 .map(FromStr::from_str)
-                .collect::<Result<_, _>>()?,
-            instruction_pointer: 0,
-        })
+                .collect::<Result<Vec<_>, _>>()?,
+        ))
     }
 }
 
@@ -30,92 +120,588 @@ impl fmt::Display for Stack {
 }
 
 impl Stack {
-    pub fn input(&mut self, noun: usize, verb: usize) {
-        self.memory[1] = noun;
-        self.memory[2] = verb;
+    pub fn input(&mut self, noun: i64, verb: i64) -> Result<(), Error> {
+        self.write(1, noun)?;
+        self.write(2, verb)
+    }
+
+    pub fn push_input(&mut self, value: i64) {
+        self.inputs.push_back(value);
+    }
+
+    pub fn extend_inputs(&mut self, values: impl IntoIterator<Item = i64>) {
+        self.inputs.extend(values);
+    }
+
+    /// Inputs that have been queued but not yet consumed by the program
+    pub fn inputs(&self) -> &VecDeque<i64> {
+        &self.inputs
+    }
+
+    /// Outputs buffered by [`Stack::run_until_blocked`]
+    pub fn outputs(&self) -> &[i64] {
+        &self.outputs
+    }
+
+    pub fn take_outputs(&mut self) -> Vec<i64> {
+        std::mem::take(&mut self.outputs)
+    }
+
+    pub fn instruction_pointer(&self) -> usize {
+        self.instruction_pointer
+    }
+
+    pub fn relative_base(&self) -> i64 {
+        self.relative_base
+    }
+
+    pub fn debugger(&self) -> &Debugger {
+        &self.debugger
+    }
+
+    pub fn debugger_mut(&mut self) -> &mut Debugger {
+        &mut self.debugger
+    }
+
+    /// Sets the number of cells memory may grow to before accesses fail with [`Error::OutOfBounds`]
+    pub fn set_memory_limit(&mut self, limit: usize) {
+        self.memory_limit = limit;
+    }
+
+    /// Decodes every cell of memory up front, so that copies of this machine start with a full
+    /// instruction cache. Anything which fails to decode is left to fail when executed.
+    pub fn predecode(&mut self) {
+        self.decoded = (0..self.memory.len())
+            .map(|address| self.decode(address).ok())
+            .collect();
     }
 
-    pub fn run(&mut self) -> Result<&Stack> {
+    /// The number of instructions executed so far
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Sets the number of instructions that may execute before failing with
+    /// [`Error::StepLimitExceeded`], to guard against programs which never halt
+    pub fn set_step_limit(&mut self, limit: Option<usize>) {
+        self.step_limit = limit;
+    }
+
+    /// Runs until the machine produces an output, needs more input, halts or hits a breakpoint or
+    /// watchpoint. Execution can be resumed by calling `run` again.
+    pub fn run(&mut self) -> Result<State, Error> {
+        loop {
+            if let Some(state) = self.step()? {
+                return Ok(state);
+            }
+        }
+    }
+
+    /// Runs until the machine needs more input, halts or hits a breakpoint or watchpoint,
+    /// buffering any outputs
+    pub fn run_until_blocked(&mut self) -> Result<State, Error> {
         loop {
-            if (self.apply()?).is_none() {
-                return Ok(self);
+            match self.run()? {
+                State::Output(value) => self.outputs.push(value),
+                state => return Ok(state),
+            }
+        }
+    }
+
+    /// Runs to completion, buffering any outputs and passing through breakpoints and watchpoints.
+    /// Fails with [`Error::InputExhausted`] if the program needs more input than has been queued.
+    pub fn run_to_halt(&mut self) -> Result<(), Error> {
+        loop {
+            match self.run_until_blocked()? {
+                State::Halted => return Ok(()),
+                State::NeedsInput => {
+                    return Err(Error::InputExhausted {
+                        pc: self.instruction_pointer,
+                    })
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    /// Executes a single instruction, returning the state if control should pass back to the caller
+    /// Once the machine has halted, it stays halted without executing anything further.
+    pub fn step(&mut self) -> Result<Option<State>, Error> {
+        if self.halted {
+            return Ok(Some(State::Halted));
+        }
+
+        let address = self.instruction_pointer;
+
+        if let Some(breakpoint) = self.breakpoint(address)? {
+            return Ok(Some(State::Breakpoint(breakpoint)));
+        }
+
+        if let Some(limit) = self.step_limit {
+            if self.steps >= limit {
+                return Err(Error::StepLimitExceeded { pc: address, limit });
             }
         }
+
+        let state = if self.debugger.is_observing() {
+            self.observe(address)?
+        } else {
+            self.apply()?
+        };
+
+        // Blocking on input doesn't execute the instruction, so a breakpoint on it stays passed
+        if state != Some(State::NeedsInput) {
+            self.steps += 1;
+            self.debugger.release();
+        }
+        self.halted = state == Some(State::Halted);
+
+        Ok(state)
     }
 
-    pub fn output(&self) -> Result<usize> {
+    /// Executes a single instruction, reporting it to the debugger
+    fn observe(&mut self, address: usize) -> Result<Option<State>, Error> {
+        let instruction = self.instruction()?;
+        let reads = instruction
+            .sources()
+            .into_iter()
+            .map(|source| self.load(source))
+            .collect::<Result<Vec<_>, _>>()?;
+        let target = instruction
+            .target()
+            .map(|target| self.location(target))
+            .transpose()?;
+        let old = target.map(|target| self.read(target)).transpose()?;
+        let mut change = Change {
+            instruction_pointer: self.instruction_pointer,
+            relative_base: self.relative_base,
+            steps: self.steps,
+            memory_len: self.memory.len(),
+            outputs_len: self.outputs.len(),
+            write: target.zip(old),
+            input: self.inputs.front().copied(),
+        };
+        let inputs = self.inputs.len();
+
+        let state = self.apply()?;
+        if state == Some(State::NeedsInput) {
+            return Ok(state);
+        }
+
+        if self.inputs.len() == inputs {
+            change.input = None;
+        }
+        self.debugger.remember(change);
+
+        let write = target
+            .map(|target| Ok::<_, Error>((target, self.read(target)?)))
+            .transpose()?;
+        self.debugger.record(TraceEntry {
+            address,
+            instruction,
+            reads,
+            write,
+        });
+
+        match (write, old) {
+            (Some((address, new)), Some(old)) if self.debugger.is_watching(address) => {
+                Ok(Some(State::Watchpoint { address, old, new }))
+            }
+            _ => Ok(state),
+        }
+    }
+
+    /// The breakpoint matching the instruction at `address`, unless execution is resuming from it
+    fn breakpoint(&mut self, address: usize) -> Result<Option<Breakpoint>, Error> {
+        if !self.debugger.has_breakpoints() || self.debugger.is_suspended_at(address) {
+            return Ok(None);
+        }
+
+        let opcode = self.read(address)? % 100;
+        let breakpoint = self.debugger.breakpoint(address, opcode);
+
+        if breakpoint.is_some() {
+            self.debugger.suspend(address);
+        }
+
+        Ok(breakpoint)
+    }
+
+    pub fn output(&self) -> Result<i64, Error> {
         self.read(0)
     }
 
-    fn read(&self, index: usize) -> Result<usize> {
-        self.memory
-            .get(index)
-            .copied()
-            .ok_or_else(|| anyhow!("Index {} does not exist", index))
+    fn address(&self, address: i64) -> Result<usize, Error> {
+        match usize::try_from(address) {
+            Ok(index) if index < self.memory_limit => Ok(index),
+            _ => Err(Error::OutOfBounds {
+                pc: self.instruction_pointer,
+                address,
+                limit: self.memory_limit,
+            }),
+        }
+    }
+
+    fn bounded(&self, address: usize) -> Result<usize, Error> {
+        if address < self.memory_limit {
+            Ok(address)
+        } else {
+            Err(Error::OutOfBounds {
+                pc: self.instruction_pointer,
+                address: i64::try_from(address).unwrap_or(i64::MAX),
+                limit: self.memory_limit,
+            })
+        }
+    }
+
+    /// Reads beyond the end of memory see the zero-filled cells it would grow into
+    fn read(&self, index: usize) -> Result<i64, Error> {
+        let index = self.bounded(index)?;
+        Ok(self.memory.get(index).copied().unwrap_or_default())
+    }
+
+    /// Writes beyond the end of memory grow it, zero-filling any cells in between
+    fn write(&mut self, index: usize, value: i64) -> Result<(), Error> {
+        let index = self.bounded(index)?;
+
+        if index >= self.memory.len() {
+            self.memory.resize(index + 1, 0);
+        }
+
+        self.memory[index] = value;
+        self.invalidate(index);
+        Ok(())
+    }
+
+    /// Forgets any decoded instruction which includes the cell at `index`
+    fn invalidate(&mut self, index: usize) {
+        let first = index.saturating_sub(Instruction::MAX_SIZE - 1);
+
+        for decoded in self.decoded.iter_mut().take(index + 1).skip(first) {
+            *decoded = None;
+        }
+    }
+
+    fn parameter(&self, address: usize, opcode: i64, offset: usize) -> Result<Parameter, Error> {
+        let mode = (opcode / 10i64.pow(offset as u32 + 1)) % 10;
+
+        Ok(Parameter {
+            mode: Mode::try_from(mode).map_err(|mode| Error::InvalidMode {
+                pc: address,
+                opcode,
+                mode,
+            })?,
+            value: self.read(address + offset)?,
+        })
+    }
+
+    fn location(&self, parameter: Parameter) -> Result<usize, Error> {
+        match parameter.mode {
+            Mode::Position => self.address(parameter.value),
+            Mode::Immediate => Err(Error::WriteToImmediate {
+                pc: self.instruction_pointer,
+            }),
+            Mode::Relative => {
+                self.address(self.checked(self.relative_base.checked_add(parameter.value))?)
+            }
+        }
+    }
+
+    fn load(&self, parameter: Parameter) -> Result<i64, Error> {
+        match parameter.mode {
+            Mode::Immediate => Ok(parameter.value),
+            _ => self.read(self.location(parameter)?),
+        }
+    }
+
+    /// The result of checked arithmetic, failing with [`Error::Overflow`] if it overflowed
+    fn checked(&self, value: Option<i64>) -> Result<i64, Error> {
+        value.ok_or(Error::Overflow {
+            pc: self.instruction_pointer,
+        })
+    }
+
+    fn store(&mut self, parameter: Parameter, value: i64) -> Result<(), Error> {
+        self.write(self.location(parameter)?, value)
+    }
+
+    fn instruction(&mut self) -> Result<Instruction, Error> {
+        let address = self.instruction_pointer;
+
+        if let Some(Some(instruction)) = self.decoded.get(address) {
+            return Ok(*instruction);
+        }
+
+        let instruction = self.decode(address)?;
+        if address < self.memory.len() {
+            if address >= self.decoded.len() {
+                self.decoded.resize(address + 1, None);
+            }
+            self.decoded[address] = Some(instruction);
+        }
+
+        Ok(instruction)
     }
 
-    fn instruction(&self) -> Result<Instruction> {
-        match self.memory[self.instruction_pointer] {
+    /// Decodes the instruction starting at `address`, regardless of where execution currently is
+    fn decode(&self, address: usize) -> Result<Instruction, Error> {
+        let opcode = self.read(address)?;
+        let parameter = |offset| self.parameter(address, opcode, offset);
+
+        match opcode % 100 {
             1 => Ok(Instruction::Add {
-                left_index: self.read(self.instruction_pointer + 1)?,
-                right_index: self.read(self.instruction_pointer + 2)?,
-                target_index: self.read(self.instruction_pointer + 3)?,
+                left: parameter(1)?,
+                right: parameter(2)?,
+                target: parameter(3)?,
             }),
             2 => Ok(Instruction::Multiply {
-                left_index: self.read(self.instruction_pointer + 1)?,
-                right_index: self.read(self.instruction_pointer + 2)?,
-                target_index: self.read(self.instruction_pointer + 3)?,
+                left: parameter(1)?,
+                right: parameter(2)?,
+                target: parameter(3)?,
+            }),
+            3 => Ok(Instruction::Input {
+                target: parameter(1)?,
+            }),
+            4 => Ok(Instruction::Output {
+                source: parameter(1)?,
+            }),
+            5 => Ok(Instruction::JumpIfTrue {
+                condition: parameter(1)?,
+                target: parameter(2)?,
+            }),
+            6 => Ok(Instruction::JumpIfFalse {
+                condition: parameter(1)?,
+                target: parameter(2)?,
+            }),
+            7 => Ok(Instruction::LessThan {
+                left: parameter(1)?,
+                right: parameter(2)?,
+                target: parameter(3)?,
+            }),
+            8 => Ok(Instruction::Equals {
+                left: parameter(1)?,
+                right: parameter(2)?,
+                target: parameter(3)?,
+            }),
+            9 => Ok(Instruction::AdjustRelativeBase {
+                offset: parameter(1)?,
             }),
             99 => Ok(Instruction::Halt),
-            a => bail!("Invalid opcode {}", a),
+            _ => Err(Error::InvalidOpcode {
+                pc: address,
+                opcode,
+            }),
         }
     }
 
-    fn apply(&mut self) -> Result<Option<()>> {
-        match self.instruction()? {
+    fn apply(&mut self) -> Result<Option<State>, Error> {
+        let instruction = self.instruction()?;
+        let next_instruction = self.instruction_pointer + instruction.size();
+
+        match instruction {
             Instruction::Add {
-                left_index,
-                right_index,
-                target_index,
+                left,
+                right,
+                target,
             } => {
-                self.memory[target_index] = self.read(left_index)? + self.read(right_index)?;
-                self.instruction_pointer += 4;
-
-                Ok(Some(()))
+                let value = self.checked(self.load(left)?.checked_add(self.load(right)?))?;
+                self.store(target, value)?;
+                self.instruction_pointer = next_instruction;
             }
             Instruction::Multiply {
-                left_index,
-                right_index,
-                target_index,
+                left,
+                right,
+                target,
             } => {
-                self.memory[target_index] = self.read(left_index)? * self.read(right_index)?;
-                self.instruction_pointer += 4;
-
-                Ok(Some(()))
+                let value = self.checked(self.load(left)?.checked_mul(self.load(right)?))?;
+                self.store(target, value)?;
+                self.instruction_pointer = next_instruction;
+            }
+            Instruction::Input { target } => match self.inputs.pop_front() {
+                Some(value) => {
+                    self.store(target, value)?;
+                    self.instruction_pointer = next_instruction;
+                }
+                None => return Ok(Some(State::NeedsInput)),
+            },
+            Instruction::Output { source } => {
+                let value = self.load(source)?;
+                self.instruction_pointer = next_instruction;
+                return Ok(Some(State::Output(value)));
             }
-            Instruction::Halt => Ok(None),
+            Instruction::JumpIfTrue { condition, target } => {
+                self.instruction_pointer = if self.load(condition)? != 0 {
+                    self.address(self.load(target)?)?
+                } else {
+                    next_instruction
+                };
+            }
+            Instruction::JumpIfFalse { condition, target } => {
+                self.instruction_pointer = if self.load(condition)? == 0 {
+                    self.address(self.load(target)?)?
+                } else {
+                    next_instruction
+                };
+            }
+            Instruction::LessThan {
+                left,
+                right,
+                target,
+            } => {
+                let value = self.load(left)? < self.load(right)?;
+                self.store(target, value as i64)?;
+                self.instruction_pointer = next_instruction;
+            }
+            Instruction::Equals {
+                left,
+                right,
+                target,
+            } => {
+                let value = self.load(left)? == self.load(right)?;
+                self.store(target, value as i64)?;
+                self.instruction_pointer = next_instruction;
+            }
+            Instruction::AdjustRelativeBase { offset } => {
+                self.relative_base =
+                    self.checked(self.relative_base.checked_add(self.load(offset)?))?;
+                self.instruction_pointer = next_instruction;
+            }
+            Instruction::Halt => return Ok(Some(State::Halted)),
         }
+
+        Ok(None)
     }
 }
 
-#[derive(Debug)]
-enum Instruction {
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    Position,
+    Immediate,
+    Relative,
+}
+
+impl TryFrom<i64> for Mode {
+    type Error = i64;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Mode::Position),
+            1 => Ok(Mode::Immediate),
+            2 => Ok(Mode::Relative),
+            a => Err(a),
+        }
+    }
+}
+
+impl From<Mode> for i64 {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Position => 0,
+            Mode::Immediate => 1,
+            Mode::Relative => 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Parameter {
+    pub mode: Mode,
+    pub value: i64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Instruction {
     Add {
-        left_index: usize,
-        right_index: usize,
-        target_index: usize,
+        left: Parameter,
+        right: Parameter,
+        target: Parameter,
     },
     Multiply {
-        left_index: usize,
-        right_index: usize,
-        target_index: usize,
+        left: Parameter,
+        right: Parameter,
+        target: Parameter,
+    },
+    Input {
+        target: Parameter,
+    },
+    Output {
+        source: Parameter,
+    },
+    JumpIfTrue {
+        condition: Parameter,
+        target: Parameter,
+    },
+    JumpIfFalse {
+        condition: Parameter,
+        target: Parameter,
+    },
+    LessThan {
+        left: Parameter,
+        right: Parameter,
+        target: Parameter,
+    },
+    Equals {
+        left: Parameter,
+        right: Parameter,
+        target: Parameter,
+    },
+    AdjustRelativeBase {
+        offset: Parameter,
     },
     Halt,
 }
 
+impl Instruction {
+    /// The most memory cells any instruction takes up
+    pub const MAX_SIZE: usize = 4;
+
+    /// The parameters read by the instruction
+    pub fn sources(&self) -> Vec<Parameter> {
+        match *self {
+            Instruction::Add { left, right, .. }
+            | Instruction::Multiply { left, right, .. }
+            | Instruction::LessThan { left, right, .. }
+            | Instruction::Equals { left, right, .. } => vec![left, right],
+            Instruction::JumpIfTrue { condition, target }
+            | Instruction::JumpIfFalse { condition, target } => vec![condition, target],
+            Instruction::Output { source } => vec![source],
+            Instruction::AdjustRelativeBase { offset } => vec![offset],
+            Instruction::Input { .. } | Instruction::Halt => vec![],
+        }
+    }
+
+    /// The parameter written to by the instruction, if any
+    pub fn target(&self) -> Option<Parameter> {
+        match *self {
+            Instruction::Add { target, .. }
+            | Instruction::Multiply { target, .. }
+            | Instruction::LessThan { target, .. }
+            | Instruction::Equals { target, .. }
+            | Instruction::Input { target } => Some(target),
+            _ => None,
+        }
+    }
+
+    /// The number of memory cells taken up by the instruction, including the opcode
+    pub fn size(&self) -> usize {
+        match self {
+            Instruction::Add { .. }
+            | Instruction::Multiply { .. }
+            | Instruction::LessThan { .. }
+            | Instruction::Equals { .. } => 4,
+            Instruction::JumpIfTrue { .. } | Instruction::JumpIfFalse { .. } => 3,
+            Instruction::Input { .. }
+            | Instruction::Output { .. }
+            | Instruction::AdjustRelativeBase { .. } => 2,
+            Instruction::Halt => 1,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use anyhow::Result;
 
     static SAMPLE_1: &str = "1,9,10,3,2,3,11,0,99,30,40,50";
     static SAMPLE_2: &str = "1,0,0,0,99";
@@ -123,6 +709,30 @@ mod tests {
     static SAMPLE_4: &str = "2,4,4,5,99,0";
     static SAMPLE_5: &str = "1,1,1,4,99,5,6,0,99";
 
+    // Outputs 1 if the input is equal to 8, otherwise 0
+    static EQUAL_TO_8_POSITION: &str = "3,9,8,9,10,9,4,9,99,0,8";
+    static EQUAL_TO_8_IMMEDIATE: &str = "3,3,1108,0,8,3,4,3,99";
+    // Outputs 0 if the input was 0, otherwise 1
+    static JUMP_POSITION: &str = "3,12,6,12,15,1,13,14,13,4,13,99,0,0,1,9";
+    static JUMP_IMMEDIATE: &str = "3,3,1105,0,9,1101,0,0,12,4,12,99,1";
+    // Outputs a copy of itself
+    static QUINE: &str = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+    // Outputs 999 if the input is below 8, 1000 if equal to 8, and 1001 if above 8
+    static COMPARE_TO_8: &str = "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99";
+
+    fn execute(program: &str) -> Result<Stack> {
+        let mut stack: Stack = program.parse()?;
+        stack.run_to_halt()?;
+        Ok(stack)
+    }
+
+    fn run_with_input(program: &str, input: i64) -> Result<Vec<i64>> {
+        let mut stack: Stack = program.parse()?;
+        stack.push_input(input);
+        stack.run_to_halt()?;
+        Ok(stack.take_outputs())
+    }
+
     #[test]
     fn test_parse() -> Result<()> {
         assert_eq!(SAMPLE_1.parse::<Stack>()?.to_string(), SAMPLE_1);
@@ -137,19 +747,278 @@ mod tests {
     #[test]
     fn test_run() -> Result<()> {
         assert_eq!(
-            SAMPLE_1.parse::<Stack>()?.run()?.to_string(),
+            execute(SAMPLE_1)?.to_string(),
             "3500,9,10,70,2,3,11,0,99,30,40,50"
         );
-        assert_eq!(SAMPLE_2.parse::<Stack>()?.run()?.to_string(), "2,0,0,0,99");
-        assert_eq!(SAMPLE_3.parse::<Stack>()?.run()?.to_string(), "2,3,0,6,99");
+        assert_eq!(execute(SAMPLE_2)?.to_string(), "2,0,0,0,99");
+        assert_eq!(execute(SAMPLE_3)?.to_string(), "2,3,0,6,99");
+        assert_eq!(execute(SAMPLE_4)?.to_string(), "2,4,4,5,99,9801");
+        assert_eq!(execute(SAMPLE_5)?.to_string(), "30,1,1,4,2,5,6,0,99");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_instruction() -> Result<()> {
+        let mut stack: Stack = "1002,4,3,4,33".parse()?;
+        assert_eq!(
+            stack.instruction()?,
+            Instruction::Multiply {
+                left: Parameter {
+                    mode: Mode::Position,
+                    value: 4
+                },
+                right: Parameter {
+                    mode: Mode::Immediate,
+                    value: 3
+                },
+                target: Parameter {
+                    mode: Mode::Position,
+                    value: 4
+                },
+            }
+        );
+
+        let mut stack: Stack = "21201,1,2,3".parse()?;
+        assert_eq!(
+            stack.instruction()?,
+            Instruction::Add {
+                left: Parameter {
+                    mode: Mode::Relative,
+                    value: 1
+                },
+                right: Parameter {
+                    mode: Mode::Immediate,
+                    value: 2
+                },
+                target: Parameter {
+                    mode: Mode::Relative,
+                    value: 3
+                },
+            }
+        );
+
+        assert_eq!(
+            "301,0,0,0".parse::<Stack>()?.instruction(),
+            Err(Error::InvalidMode {
+                pc: 0,
+                opcode: 301,
+                mode: 3
+            })
+        );
+        assert_eq!(
+            "42".parse::<Stack>()?.instruction(),
+            Err(Error::InvalidOpcode { pc: 0, opcode: 42 })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_position_mode() -> Result<()> {
+        assert_eq!(run_with_input(EQUAL_TO_8_POSITION, 8)?, vec![1]);
+        assert_eq!(run_with_input(EQUAL_TO_8_POSITION, 7)?, vec![0]);
+        assert_eq!(run_with_input(JUMP_POSITION, 0)?, vec![0]);
+        assert_eq!(run_with_input(JUMP_POSITION, 5)?, vec![1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_immediate_mode() -> Result<()> {
+        assert_eq!(execute("1002,4,3,4,33")?.to_string(), "1002,4,3,4,99");
+        assert_eq!(execute("1101,98,1,4,0")?.to_string(), "1101,98,1,4,99");
+        assert_eq!(run_with_input(EQUAL_TO_8_IMMEDIATE, 8)?, vec![1]);
+        assert_eq!(run_with_input(EQUAL_TO_8_IMMEDIATE, 9)?, vec![0]);
+        assert_eq!(run_with_input(JUMP_IMMEDIATE, 0)?, vec![0]);
+        assert_eq!(run_with_input(JUMP_IMMEDIATE, 5)?, vec![1]);
+        assert_eq!(
+            "1101,1,1,5,11101,1,1,0,99".parse::<Stack>()?.run(),
+            Err(Error::WriteToImmediate { pc: 4 })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_relative_mode() -> Result<()> {
+        assert_eq!(execute("109,5,204,1,99,0,42")?.outputs(), &[42]);
+        assert_eq!(
+            execute("109,7,21101,3,4,0,99,0")?.to_string(),
+            "109,7,21101,3,4,0,99,7"
+        );
+        assert_eq!(run_with_input("109,2,109,3,203,4,4,9,99,0", 17)?, vec![17]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_comparisons() -> Result<()> {
+        assert_eq!(run_with_input(COMPARE_TO_8, 7)?, vec![999]);
+        assert_eq!(run_with_input(COMPARE_TO_8, 8)?, vec![1000]);
+        assert_eq!(run_with_input(COMPARE_TO_8, 9)?, vec![1001]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_negative_values() -> Result<()> {
+        assert_eq!(execute("1101,100,-1,4,0")?.to_string(), "1101,100,-1,4,99");
+        assert_eq!(run_with_input("3,9,8,9,10,9,4,9,99,-1,8", 8)?, vec![1]);
+        assert_eq!(
+            execute("104,1125899906842624,99")?.outputs(),
+            &[1125899906842624]
+        );
+        assert_eq!(
+            execute("1102,34915192,34915192,7,4,7,99,0")?.outputs(),
+            &[1219070632396864]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_self_modifying() -> Result<()> {
+        // Outputs 5, then rewrites the output's parameter to 7 and runs it again
+        let program = "104,5,1005,17,16,1101,1,0,17,1101,7,0,1,1105,1,0,99,0";
+        assert_eq!(run_with_input(program, 0)?, vec![5, 7]);
+
+        let mut stack: Stack = program.parse()?;
+        stack.predecode();
+        stack.run_to_halt()?;
+        assert_eq!(stack.take_outputs(), vec![5, 7]);
+
+        // Rewrites the halt at 4 into a multiply
+        let mut stack: Stack = SAMPLE_5.parse()?;
+        stack.predecode();
+        stack.run_to_halt()?;
+        assert_eq!(stack.to_string(), "30,1,1,4,2,5,6,0,99");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_growth() -> Result<()> {
+        let outputs = execute(QUINE)?.outputs().iter().join(",");
+        assert_eq!(outputs, QUINE);
+
+        assert_eq!(execute("1101,2,3,7,99")?.to_string(), "1101,2,3,7,99,0,0,5");
+        assert_eq!(execute("4,100,99")?.outputs(), &[0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_limit() -> Result<()> {
+        let mut stack: Stack = "1101,2,3,7,99".parse()?;
+        stack.set_memory_limit(7);
+        assert_eq!(
+            stack.run(),
+            Err(Error::OutOfBounds {
+                pc: 0,
+                address: 7,
+                limit: 7
+            })
+        );
+
+        let mut stack: Stack = "1101,2,3,-1,99".parse()?;
+        assert_eq!(
+            stack.run(),
+            Err(Error::OutOfBounds {
+                pc: 0,
+                address: -1,
+                limit: DEFAULT_MEMORY_LIMIT
+            })
+        );
+
+        let mut stack: Stack = "1101,2,3,1000000000000,99".parse()?;
+        assert!(matches!(stack.run(), Err(Error::OutOfBounds { .. })));
+
+        let mut stack: Stack = "1,2,3".parse()?;
+        stack.set_memory_limit(2);
+        assert!(matches!(stack.input(1, 2), Err(Error::OutOfBounds { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pausable_run() -> Result<()> {
+        let mut stack: Stack = EQUAL_TO_8_POSITION.parse()?;
+        assert_eq!(stack.run()?, State::NeedsInput);
+        assert_eq!(stack.run()?, State::NeedsInput);
+
+        stack.push_input(8);
+        assert_eq!(stack.inputs(), &[8]);
+        assert_eq!(stack.run()?, State::Output(1));
+        assert!(stack.inputs().is_empty());
+        assert!(stack.outputs().is_empty());
+        assert_eq!(stack.run()?, State::Halted);
+        assert_eq!(stack.run()?, State::Halted);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_interactive_run() -> Result<()> {
+        // Echoes each input back, doubled, until it receives a 0
+        let mut stack: Stack = "3,15,1006,15,14,1002,15,2,16,4,16,1105,1,0,99,0,0".parse()?;
+        stack.extend_inputs([1, 2]);
+        assert_eq!(stack.run_until_blocked()?, State::NeedsInput);
+        assert_eq!(stack.take_outputs(), vec![2, 4]);
+        assert!(stack.outputs().is_empty());
+
+        stack.push_input(0);
+        assert_eq!(stack.run_until_blocked()?, State::Halted);
+        assert!(stack.outputs().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() -> Result<()> {
+        assert!(matches!("1,2,x".parse::<Stack>(), Err(Error::Parse(_))));
+
+        let mut stack: Stack = EQUAL_TO_8_POSITION.parse()?;
+        assert_eq!(stack.run_to_halt(), Err(Error::InputExhausted { pc: 0 }));
+
+        // Loops forever
+        let mut stack: Stack = "1105,1,0".parse()?;
+        stack.set_step_limit(Some(100));
+        assert_eq!(
+            stack.run(),
+            Err(Error::StepLimitExceeded { pc: 0, limit: 100 })
+        );
+        assert_eq!(stack.steps(), 100);
+
+        let mut stack: Stack = SAMPLE_1.parse()?;
+        stack.set_step_limit(Some(3));
+        stack.run_to_halt()?;
+        assert_eq!(stack.steps(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_overflow() -> Result<()> {
+        let run = |program: &str| program.parse::<Stack>()?.run_to_halt();
+
+        assert_eq!(
+            run("1101,9223372036854775807,1,0,99"),
+            Err(Error::Overflow { pc: 0 })
+        );
+        assert_eq!(
+            run("1102,4611686018427387904,2,0,99"),
+            Err(Error::Overflow { pc: 0 })
+        );
         assert_eq!(
-            SAMPLE_4.parse::<Stack>()?.run()?.to_string(),
-            "2,4,4,5,99,9801"
+            run("109,9223372036854775807,109,1,99"),
+            Err(Error::Overflow { pc: 2 })
         );
         assert_eq!(
-            SAMPLE_5.parse::<Stack>()?.run()?.to_string(),
-            "30,1,1,4,2,5,6,0,99"
+            run("109,9223372036854775807,204,1,99"),
+            Err(Error::Overflow { pc: 2 })
         );
+        assert_eq!(run("1101,9223372036854775806,1,0,99"), Ok(()));
 
         Ok(())
     }
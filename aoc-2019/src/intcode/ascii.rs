@@ -0,0 +1,231 @@
+use super::{Error, Stack, State};
+use anyhow::Result;
+use std::io::{BufRead, Write};
+
+/// A run of consecutive outputs
+#[derive(Clone, Debug, PartialEq)]
+pub enum Segment {
+    /// Outputs in the ASCII range, decoded
+    Text(String),
+    /// An output outside the ASCII range, such as a puzzle's final answer
+    Value(i64),
+}
+
+/// What a console printed before it blocked
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reply {
+    pub segments: Vec<Segment>,
+    /// Why the machine stopped
+    pub state: State,
+}
+
+impl Reply {
+    /// All of the text, with any values left out
+    pub fn text(&self) -> String {
+        self.segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Text(text) => Some(text.as_str()),
+                Segment::Value(_) => None,
+            })
+            .collect()
+    }
+
+    pub fn values(&self) -> Vec<i64> {
+        self.segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Text(_) => None,
+                Segment::Value(value) => Some(*value),
+            })
+            .collect()
+    }
+}
+
+/// Wraps a machine which reads and writes lines of ASCII text
+#[derive(Clone, Debug)]
+pub struct Console {
+    stack: Stack,
+}
+
+impl From<Stack> for Console {
+    fn from(stack: Stack) -> Self {
+        Console { stack }
+    }
+}
+
+impl Console {
+    pub fn stack(&self) -> &Stack {
+        &self.stack
+    }
+
+    pub fn stack_mut(&mut self) -> &mut Stack {
+        &mut self.stack
+    }
+
+    pub fn into_stack(self) -> Stack {
+        self.stack
+    }
+
+    /// Queues a line of text as input, followed by a newline
+    pub fn send_line(&mut self, line: &str) -> Result<(), Error> {
+        let codes = line
+            .chars()
+            .map(|c| {
+                if c.is_ascii() {
+                    Ok(c as i64)
+                } else {
+                    Err(Error::NonAsciiInput(c))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.stack.extend_inputs(codes);
+        self.stack.push_input('\n' as i64);
+
+        Ok(())
+    }
+
+    /// Runs until the machine blocks, decoding everything it output along the way
+    pub fn run(&mut self) -> Result<Reply, Error> {
+        let state = self.stack.run_until_blocked()?;
+        let mut segments = Vec::new();
+
+        for value in self.stack.take_outputs() {
+            match (u8::try_from(value), segments.last_mut()) {
+                (Ok(code), Some(Segment::Text(text))) if code.is_ascii() => text.push(code as char),
+                (Ok(code), _) if code.is_ascii() => {
+                    segments.push(Segment::Text(String::from(code as char)))
+                }
+                _ => segments.push(Segment::Value(value)),
+            }
+        }
+
+        Ok(Reply { segments, state })
+    }
+
+    /// Runs a session, answering each request for input with the next line of `input` and
+    /// writing the machine's text to `output`, with each value on a line of its own.
+    /// With `echo`, lines read from `input` are also written to `output`, as if typed.
+    ///
+    /// Returns [`State::Halted`] if the machine halts, or [`State::NeedsInput`] if `input` runs
+    /// out first.
+    pub fn session(
+        &mut self,
+        input: impl BufRead,
+        mut output: impl Write,
+        echo: bool,
+    ) -> Result<State> {
+        let mut lines = input.lines();
+
+        loop {
+            let reply = self.run()?;
+            for segment in reply.segments {
+                match segment {
+                    Segment::Text(text) => write!(output, "{}", text)?,
+                    Segment::Value(value) => writeln!(output, "{}", value)?,
+                }
+            }
+            output.flush()?;
+
+            match reply.state {
+                State::Halted => return Ok(State::Halted),
+                State::NeedsInput => {
+                    let Some(line) = lines.next().transpose()? else {
+                        return Ok(State::NeedsInput);
+                    };
+
+                    if echo {
+                        writeln!(output, "{}", line)?;
+                    }
+                    self.send_line(&line)?;
+                }
+                _ => continue,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::assembler::assemble;
+
+    // Echoes each line it reads followed by 1000 plus its length, until it reads an empty line
+    static ECHO: &str = "
+    prompt: OUT #63
+            OUT #10
+            ADD #0, #0 -> [n]
+    read:   IN -> [c]
+            EQ [c], #10 -> [done]
+            JT [done], #finish
+            OUT [c]
+            ADD [n], #1 -> [n]
+            JT #1, #read
+    finish: JF [n], #end
+            OUT #10
+            ADD [n], #1000 -> [n]
+            OUT [n]
+            JT #1, #prompt
+    end:    HLT
+    c:      DATA 0
+    n:      DATA 0
+    done:   DATA 0
+    ";
+
+    #[test]
+    fn test_console() -> Result<()> {
+        let mut console = Console::from(assemble(ECHO)?);
+        assert_eq!(
+            console.run()?,
+            Reply {
+                segments: vec![Segment::Text("?\n".to_owned())],
+                state: State::NeedsInput
+            }
+        );
+
+        console.send_line("hello")?;
+        let reply = console.run()?;
+        assert_eq!(
+            reply.segments,
+            vec![
+                Segment::Text("hello\n".to_owned()),
+                Segment::Value(1005),
+                Segment::Text("?\n".to_owned())
+            ]
+        );
+        assert_eq!(reply.text(), "hello\n?\n");
+        assert_eq!(reply.values(), vec![1005]);
+        assert_eq!(reply.state, State::NeedsInput);
+
+        assert_eq!(console.send_line("é"), Err(Error::NonAsciiInput('é')));
+        assert!(console.stack().inputs().is_empty());
+
+        console.send_line("")?;
+        assert_eq!(console.run()?.state, State::Halted);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_session() -> Result<()> {
+        let mut transcript = Vec::new();
+        let mut console = Console::from(assemble(ECHO)?);
+        let state = console.session("hi\nabc\n\n".as_bytes(), &mut transcript, true)?;
+
+        assert_eq!(state, State::Halted);
+        assert_eq!(
+            String::from_utf8(transcript)?,
+            "?\nhi\nhi\n1002\n?\nabc\nabc\n1003\n?\n\n"
+        );
+
+        let mut transcript = Vec::new();
+        let mut console = Console::from(assemble(ECHO)?);
+        let state = console.session("hi".as_bytes(), &mut transcript, false)?;
+
+        assert_eq!(state, State::NeedsInput);
+        assert_eq!(String::from_utf8(transcript)?, "?\nhi\n1002\n?\n");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,441 @@
+//! Assembles the mnemonic syntax produced by the disassembler back into a program.
+//!
+//! ```text
+//! ; Counts down from the input to 1
+//!         IN -> [n]
+//! loop:   JF [n], #done
+//!         OUT [n]
+//!         ADD [n], #-1 -> [n]
+//!         JT #1, #loop
+//! done:   HLT
+//! n:      DATA 0
+//! ```
+//!
+//! Operands are `#value` (immediate), `[value]` (position) or `[rb+value]` (relative), where a
+//! value is either a number or a label. Labels are defined with a trailing `:` and resolve to the
+//! address of whatever follows them. `DATA` emits its comma-separated values as-is, and `;` starts
+//! a comment.
+
+use super::{Mode, Stack};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// A line of source which could not be assembled, counting lines from 1
+#[derive(Error, Debug, PartialEq)]
+#[error("Line {line}: {reason}")]
+pub struct AssemblyError {
+    pub line: usize,
+    pub reason: Reason,
+}
+
+/// What is wrong with a line of source
+#[derive(Error, Debug, PartialEq)]
+pub enum Reason {
+    #[error("invalid label {0}")]
+    InvalidLabel(String),
+    #[error("duplicate label {0}")]
+    DuplicateLabel(String),
+    #[error("undefined label {0}")]
+    UndefinedLabel(String),
+    #[error("unknown mnemonic {0}")]
+    UnknownMnemonic(String),
+    #[error("{0} expects a `-> target` operand")]
+    MissingTarget(String),
+    #[error("{mnemonic} expects {expected} operands, found {found}")]
+    OperandCount {
+        mnemonic: String,
+        expected: usize,
+        found: usize,
+    },
+    #[error("cannot write to immediate operand {0}")]
+    WriteToImmediate(String),
+    #[error("DATA expects at least one value")]
+    EmptyData,
+    #[error("empty operand in {0}")]
+    EmptyOperand(String),
+    #[error("invalid operand {0}")]
+    InvalidOperand(String),
+    #[error("invalid value {0}")]
+    InvalidValue(String),
+}
+
+#[derive(Debug)]
+enum Value {
+    Number(i64),
+    Label(String),
+}
+
+#[derive(Debug)]
+struct Operand {
+    mode: Mode,
+    value: Value,
+}
+
+#[derive(Debug)]
+enum Statement {
+    Instruction { opcode: i64, operands: Vec<Operand> },
+    Data(Vec<Value>),
+}
+
+impl Statement {
+    fn size(&self) -> usize {
+        match self {
+            Statement::Instruction { operands, .. } => 1 + operands.len(),
+            Statement::Data(values) => values.len(),
+        }
+    }
+}
+
+pub fn assemble(source: &str) -> Result<Stack, AssemblyError> {
+    let mut labels = HashMap::new();
+    let mut statements = Vec::new();
+    let mut address = 0;
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let error = |reason| AssemblyError {
+            line: line_number,
+            reason,
+        };
+        let mut line = line.split(';').next().unwrap_or_default().trim();
+
+        while let Some((label, rest)) = line.split_once(':') {
+            let label = label.trim();
+            if !is_label(label) {
+                return Err(error(Reason::InvalidLabel(label.to_owned())));
+            }
+            if labels.insert(label, address).is_some() {
+                return Err(error(Reason::DuplicateLabel(label.to_owned())));
+            }
+            line = rest.trim();
+        }
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let statement = parse_statement(line).map_err(error)?;
+        address += statement.size();
+        statements.push((line_number, statement));
+    }
+
+    let mut memory = Vec::with_capacity(address);
+
+    for (line_number, statement) in statements {
+        let resolve = |value: &Value| match value {
+            Value::Number(number) => Ok(*number),
+            Value::Label(label) => labels
+                .get(label.as_str())
+                .map(|address| *address as i64)
+                .ok_or_else(|| AssemblyError {
+                    line: line_number,
+                    reason: Reason::UndefinedLabel(label.clone()),
+                }),
+        };
+
+        match statement {
+            Statement::Instruction { opcode, operands } => {
+                let modes: i64 = operands
+                    .iter()
+                    .enumerate()
+                    .map(|(index, operand)| i64::from(operand.mode) * 10i64.pow(index as u32 + 2))
+                    .sum();
+                memory.push(opcode + modes);
+
+                for operand in operands {
+                    memory.push(resolve(&operand.value)?);
+                }
+            }
+            Statement::Data(values) => {
+                for value in values {
+                    memory.push(resolve(&value)?);
+                }
+            }
+        }
+    }
+
+    Ok(Stack::from(memory))
+}
+
+fn parse_statement(line: &str) -> Result<Statement, Reason> {
+    let (mnemonic, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+
+    let (opcode, sources, has_target) = match mnemonic.to_ascii_uppercase().as_str() {
+        "ADD" => (1, 2, true),
+        "MUL" => (2, 2, true),
+        "IN" => (3, 0, true),
+        "OUT" => (4, 1, false),
+        "JT" => (5, 2, false),
+        "JF" => (6, 2, false),
+        "LT" => (7, 2, true),
+        "EQ" => (8, 2, true),
+        "ARB" => (9, 1, false),
+        "HLT" => (99, 0, false),
+        "DATA" => {
+            let values = split_list(rest)?
+                .into_iter()
+                .map(parse_value)
+                .collect::<Result<Vec<_>, _>>()?;
+            if values.is_empty() {
+                return Err(Reason::EmptyData);
+            }
+            return Ok(Statement::Data(values));
+        }
+        _ => return Err(Reason::UnknownMnemonic(mnemonic.to_owned())),
+    };
+
+    let (rest, target) = if has_target {
+        let (rest, target) = rest
+            .split_once("->")
+            .ok_or_else(|| Reason::MissingTarget(mnemonic.to_owned()))?;
+        (rest, Some(target))
+    } else {
+        (rest, None)
+    };
+
+    let mut operands = split_list(rest)?
+        .into_iter()
+        .map(parse_operand)
+        .collect::<Result<Vec<_>, _>>()?;
+    if operands.len() != sources {
+        return Err(Reason::OperandCount {
+            mnemonic: mnemonic.to_owned(),
+            expected: sources,
+            found: operands.len(),
+        });
+    }
+
+    if let Some(target) = target {
+        let operand = parse_operand(target)?;
+        if operand.mode == Mode::Immediate {
+            return Err(Reason::WriteToImmediate(target.trim().to_owned()));
+        }
+        operands.push(operand);
+    }
+
+    Ok(Statement::Instruction { opcode, operands })
+}
+
+fn split_list(text: &str) -> Result<Vec<&str>, Reason> {
+    if text.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    text.split(',')
+        .map(|item| match item.trim() {
+            "" => Err(Reason::EmptyOperand(text.trim().to_owned())),
+            item => Ok(item),
+        })
+        .collect()
+}
+
+fn parse_operand(text: &str) -> Result<Operand, Reason> {
+    let text = text.trim();
+
+    if let Some(value) = text.strip_prefix('#') {
+        return Ok(Operand {
+            mode: Mode::Immediate,
+            value: parse_value(value)?,
+        });
+    }
+
+    let inner = text
+        .strip_prefix('[')
+        .and_then(|text| text.strip_suffix(']'))
+        .ok_or_else(|| Reason::InvalidOperand(text.to_owned()))?
+        .trim();
+
+    match inner.strip_prefix("rb").map(str::trim) {
+        Some("") => Ok(Operand {
+            mode: Mode::Relative,
+            value: Value::Number(0),
+        }),
+        Some(offset) if offset.starts_with('+') => Ok(Operand {
+            mode: Mode::Relative,
+            value: parse_value(&offset[1..])?,
+        }),
+        Some(offset) if offset.starts_with('-') => Ok(Operand {
+            mode: Mode::Relative,
+            value: parse_value(offset)?,
+        }),
+        _ => Ok(Operand {
+            mode: Mode::Position,
+            value: parse_value(inner)?,
+        }),
+    }
+}
+
+fn parse_value(text: &str) -> Result<Value, Reason> {
+    let text = text.trim();
+
+    if let Ok(number) = text.parse() {
+        Ok(Value::Number(number))
+    } else if is_label(text) {
+        Ok(Value::Label(text.to_owned()))
+    } else {
+        Err(Reason::InvalidValue(text.to_owned()))
+    }
+}
+
+fn is_label(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::{disassembler, State};
+    use anyhow::Result;
+
+    fn run(source: &str, inputs: &[i64]) -> Result<Vec<i64>> {
+        let mut stack = assemble(source)?;
+        stack.extend_inputs(inputs.iter().copied());
+        assert_eq!(stack.run_until_blocked()?, State::Halted);
+        Ok(stack.take_outputs())
+    }
+
+    #[test]
+    fn test_assemble() -> Result<()> {
+        let stack = assemble(
+            "
+            ; The first sample from day 2
+                ADD [a], [b] -> [3]
+                MUL [3], [c] -> [0]
+                HLT
+            a:  DATA 30
+            b:  DATA 40
+            c:  DATA 50
+            ",
+        )?;
+        assert_eq!(stack.to_string(), "1,9,10,3,2,3,11,0,99,30,40,50");
+
+        let stack = assemble("start: end: add #1, [rb-3] -> [rb]\nDATA start, -7, end")?;
+        assert_eq!(stack.to_string(), "22101,1,-3,0,0,-7,0");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_trip() -> Result<()> {
+        let program = "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99";
+        let source = disassembler::disassemble(&program.parse()?)
+            .into_iter()
+            .map(|line| match line {
+                disassembler::Line::Code { instruction, .. } => instruction.to_string(),
+                disassembler::Line::Data { value, .. } => format!("DATA {}", value),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(assemble(&source)?.to_string(), program);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |source| assemble(source).unwrap_err().to_string();
+
+        assert_eq!(error("NOP"), "Line 1: unknown mnemonic NOP");
+        assert_eq!(
+            error("ADD #1, #2"),
+            "Line 1: ADD expects a `-> target` operand"
+        );
+        assert_eq!(
+            error("ADD #1 -> [0]"),
+            "Line 1: ADD expects 2 operands, found 1"
+        );
+        assert_eq!(
+            error("ADD #1, #2 -> #0"),
+            "Line 1: cannot write to immediate operand #0"
+        );
+        assert_eq!(error("OUT 5"), "Line 1: invalid operand 5");
+        assert_eq!(
+            error("HLT\nOUT [missing]"),
+            "Line 2: undefined label missing"
+        );
+        assert_eq!(error("a: HLT\na: HLT"), "Line 2: duplicate label a");
+        assert_eq!(error("1a: HLT"), "Line 1: invalid label 1a");
+        assert_eq!(error("DATA"), "Line 1: DATA expects at least one value");
+        assert_eq!(error("DATA 1,,2"), "Line 1: empty operand in 1,,2");
+        assert_eq!(error("OUT #1x"), "Line 1: invalid value 1x");
+
+        assert_eq!(
+            assemble("\n  JT #1, #nowhere").unwrap_err(),
+            AssemblyError {
+                line: 2,
+                reason: Reason::UndefinedLabel("nowhere".to_owned())
+            }
+        );
+    }
+
+    #[test]
+    fn test_arithmetic() -> Result<()> {
+        let source = "
+                ARB #base
+                ADD [x], #5 -> [sum]
+                MUL [rb+0], [rb+1] -> [rb+2]
+                OUT [sum]
+                OUT [rb+2]
+                HLT
+            x:      DATA 10
+            sum:    DATA 0
+            base:   DATA 6, 7, 0
+        ";
+        assert_eq!(run(source, &[])?, vec![15, 42]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_input_and_comparisons() -> Result<()> {
+        let source = "
+                IN -> [a]
+                IN -> [rb+b]
+                LT [a], [b] -> [lt]
+                EQ [a], #7 -> [rb+eq]
+                OUT [lt]
+                OUT [rb+eq]
+                HLT
+            a:  DATA 0
+            b:  DATA 0
+            lt: DATA 0
+            eq: DATA 0
+        ";
+        assert_eq!(run(source, &[7, 9])?, vec![1, 1]);
+        assert_eq!(run(source, &[9, 7])?, vec![0, 0]);
+        assert_eq!(run(source, &[3, 3])?, vec![0, 0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jumps() -> Result<()> {
+        let countdown = "
+                IN -> [n]
+        loop:   JF [n], #done
+                OUT [n]
+                ADD [n], #-1 -> [n]
+                JT #1, #loop
+        done:   HLT
+        n:      DATA 0
+        ";
+        assert_eq!(run(countdown, &[3])?, vec![3, 2, 1]);
+        assert_eq!(run(countdown, &[0])?, Vec::<i64>::new());
+
+        let indirect = "
+                JT #1, [where]
+                OUT #1
+        there:  JF #0, [rb+elsewhere]
+                OUT #2
+        later:  OUT #3
+                HLT
+        where:  DATA there
+        elsewhere: DATA later
+        ";
+        assert_eq!(run(indirect, &[])?, vec![3]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,331 @@
+use super::snapshot::{Change, History};
+use super::Instruction;
+use itertools::Itertools;
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Stops before executing the instruction at an address
+    Address(usize),
+    /// Stops before executing any instruction with an opcode, ignoring parameter modes
+    Opcode(i64),
+}
+
+/// A record of a single executed instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceEntry {
+    pub address: usize,
+    pub instruction: Instruction,
+    /// The values of each of the instruction's sources, in order
+    pub reads: Vec<i64>,
+    /// The address written to, and the value written there
+    pub write: Option<(usize, i64)>,
+}
+
+impl fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut details = Vec::new();
+
+        if !self.reads.is_empty() {
+            details.push(format!("reads {}", self.reads.iter().join(", ")));
+        }
+
+        if let Some((address, value)) = self.write {
+            details.push(format!("writes [{}] = {}", address, value));
+        }
+
+        if details.is_empty() {
+            write!(f, "{:>5}  {}", self.address, self.instruction)
+        } else {
+            write!(
+                f,
+                "{:>5}  {:<32} {}",
+                self.address,
+                self.instruction.to_string(),
+                details.join(" ")
+            )
+        }
+    }
+}
+
+/// Opt-in instrumentation for a [`super::Stack`]. Nothing is recorded or checked until tracing or
+/// history is enabled, or a breakpoint or watchpoint is added.
+#[derive(Clone, Debug, Default)]
+pub struct Debugger {
+    trace: Option<Vec<TraceEntry>>,
+    breakpoints: HashSet<Breakpoint>,
+    watchpoints: HashSet<usize>,
+    suspended_at: Option<usize>,
+    history: Option<History>,
+}
+
+impl Debugger {
+    pub fn enable_tracing(&mut self) {
+        self.trace.get_or_insert_with(Vec::new);
+    }
+
+    pub fn disable_tracing(&mut self) {
+        self.trace = None;
+    }
+
+    pub fn trace(&self) -> &[TraceEntry] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    pub fn take_trace(&mut self) -> Vec<TraceEntry> {
+        self.trace.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Keeps enough of the last `capacity` instructions to undo them with [`super::Stack::step_back`]
+    pub fn enable_history(&mut self, capacity: usize) {
+        self.history = Some(History::new(capacity));
+    }
+
+    pub fn disable_history(&mut self) {
+        self.history = None;
+    }
+
+    /// How many instructions can currently be stepped back over
+    pub fn history_len(&self) -> usize {
+        self.history.as_ref().map_or(0, History::len)
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        self.breakpoints.insert(breakpoint);
+    }
+
+    pub fn remove_breakpoint(&mut self, breakpoint: Breakpoint) {
+        self.breakpoints.remove(&breakpoint);
+    }
+
+    pub fn add_watchpoint(&mut self, address: usize) {
+        self.watchpoints.insert(address);
+    }
+
+    pub fn remove_watchpoint(&mut self, address: usize) {
+        self.watchpoints.remove(&address);
+    }
+
+    /// Whether each instruction needs inspecting as it executes
+    pub(super) fn is_observing(&self) -> bool {
+        self.trace.is_some() || self.history.is_some() || !self.watchpoints.is_empty()
+    }
+
+    pub(super) fn is_watching(&self, address: usize) -> bool {
+        self.watchpoints.contains(&address)
+    }
+
+    pub(super) fn has_breakpoints(&self) -> bool {
+        !self.breakpoints.is_empty()
+    }
+
+    pub(super) fn breakpoint(&self, address: usize, opcode: i64) -> Option<Breakpoint> {
+        [Breakpoint::Address(address), Breakpoint::Opcode(opcode)]
+            .into_iter()
+            .find(|breakpoint| self.breakpoints.contains(breakpoint))
+    }
+
+    /// Remembers that execution stopped at `address`, so the breakpoint is not hit again on resume
+    pub(super) fn suspend(&mut self, address: usize) {
+        self.suspended_at = Some(address);
+    }
+
+    /// Whether execution is resuming from a breakpoint at `address`
+    pub(super) fn is_suspended_at(&self, address: usize) -> bool {
+        self.suspended_at == Some(address)
+    }
+
+    /// Forgets the breakpoint execution stopped at, once an instruction has executed
+    pub(super) fn release(&mut self) {
+        self.suspended_at = None;
+    }
+
+    pub(super) fn record(&mut self, entry: TraceEntry) {
+        if let Some(trace) = &mut self.trace {
+            trace.push(entry);
+        }
+    }
+
+    pub(super) fn remember(&mut self, change: Change) {
+        if let Some(history) = &mut self.history {
+            history.push(change);
+        }
+    }
+
+    pub(super) fn forget(&mut self) -> Option<Change> {
+        self.history.as_mut().and_then(History::pop)
+    }
+
+    pub(super) fn clear_history(&mut self) {
+        if let Some(history) = &mut self.history {
+            history.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::{assembler::assemble, Stack, State};
+    use anyhow::Result;
+
+    static COUNTDOWN: &str = "
+            IN -> [n]
+    loop:   JF [n], #done
+            OUT [n]
+            ADD [n], #-1 -> [n]
+            JT #1, #loop
+    done:   HLT
+    n:      DATA 0
+    ";
+
+    #[test]
+    fn test_trace() -> Result<()> {
+        let mut stack: Stack = "1,9,10,3,2,3,11,0,99,30,40,50".parse()?;
+        stack.debugger_mut().enable_tracing();
+        assert_eq!(stack.run()?, State::Halted);
+
+        assert_eq!(
+            stack
+                .debugger()
+                .trace()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "    0  ADD [9], [10] -> [3]             reads 30, 40 writes [3] = 70",
+                "    4  MUL [3], [11] -> [0]             reads 70, 50 writes [0] = 3500",
+                "    8  HLT",
+            ]
+        );
+
+        let trace = stack.debugger_mut().take_trace();
+        assert_eq!(
+            trace[1],
+            TraceEntry {
+                address: 4,
+                instruction: stack.decode(4)?,
+                reads: vec![70, 50],
+                write: Some((0, 3500))
+            }
+        );
+        assert!(stack.debugger().trace().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trace_is_opt_in() -> Result<()> {
+        let mut stack = assemble(COUNTDOWN)?;
+        stack.push_input(3);
+        stack.run_until_blocked()?;
+        assert!(stack.debugger().trace().is_empty());
+
+        let mut stack = assemble(COUNTDOWN)?;
+        stack.debugger_mut().enable_tracing();
+        assert_eq!(stack.run()?, State::NeedsInput);
+        assert!(stack.debugger().trace().is_empty());
+
+        stack.push_input(1);
+        stack.run_until_blocked()?;
+        assert_eq!(
+            stack
+                .debugger()
+                .trace()
+                .iter()
+                .map(|entry| entry.address)
+                .collect::<Vec<_>>(),
+            vec![0, 2, 5, 7, 11, 2, 14]
+        );
+        assert_eq!(stack.debugger().trace()[0].write, Some((15, 1)));
+        assert_eq!(stack.debugger().trace()[2].reads, vec![1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_breakpoints() -> Result<()> {
+        let mut stack = assemble(COUNTDOWN)?;
+        stack.push_input(2);
+        stack.debugger_mut().add_breakpoint(Breakpoint::Address(5));
+
+        assert_eq!(
+            stack.run_until_blocked()?,
+            State::Breakpoint(Breakpoint::Address(5))
+        );
+        assert_eq!(stack.instruction_pointer(), 5);
+        assert!(stack.outputs().is_empty());
+
+        assert_eq!(
+            stack.run_until_blocked()?,
+            State::Breakpoint(Breakpoint::Address(5))
+        );
+        assert_eq!(stack.take_outputs(), vec![2]);
+
+        stack
+            .debugger_mut()
+            .remove_breakpoint(Breakpoint::Address(5));
+        stack.debugger_mut().add_breakpoint(Breakpoint::Opcode(99));
+        assert_eq!(
+            stack.run_until_blocked()?,
+            State::Breakpoint(Breakpoint::Opcode(99))
+        );
+        assert_eq!(stack.take_outputs(), vec![1]);
+        assert_eq!(stack.run_until_blocked()?, State::Halted);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_breakpoint_waiting_for_input() -> Result<()> {
+        let mut stack = assemble(COUNTDOWN)?;
+        stack.debugger_mut().add_breakpoint(Breakpoint::Address(0));
+
+        assert_eq!(stack.run()?, State::Breakpoint(Breakpoint::Address(0)));
+        assert_eq!(stack.run()?, State::NeedsInput);
+        assert_eq!(stack.run()?, State::NeedsInput);
+
+        stack.push_input(1);
+        assert_eq!(stack.run()?, State::Output(1));
+        assert_eq!(stack.steps(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_watchpoints() -> Result<()> {
+        let mut stack = assemble(COUNTDOWN)?;
+        stack.push_input(2);
+        stack.debugger_mut().add_watchpoint(15);
+
+        assert_eq!(
+            stack.run_until_blocked()?,
+            State::Watchpoint {
+                address: 15,
+                old: 0,
+                new: 2
+            }
+        );
+        assert_eq!(
+            stack.run_until_blocked()?,
+            State::Watchpoint {
+                address: 15,
+                old: 2,
+                new: 1
+            }
+        );
+        assert_eq!(
+            stack.run_until_blocked()?,
+            State::Watchpoint {
+                address: 15,
+                old: 1,
+                new: 0
+            }
+        );
+        assert_eq!(stack.run_until_blocked()?, State::Halted);
+        assert_eq!(stack.take_outputs(), vec![2, 1]);
+
+        Ok(())
+    }
+}
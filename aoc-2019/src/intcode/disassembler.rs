@@ -0,0 +1,266 @@
+use super::{Instruction, Mode, Parameter, Stack};
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Line {
+    Code {
+        address: usize,
+        cells: Vec<i64>,
+        instruction: Instruction,
+    },
+    Data {
+        address: usize,
+        value: i64,
+    },
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Line::Code {
+                address,
+                cells,
+                instruction,
+            } => write!(
+                f,
+                "{:>5}  {:<24}{}",
+                address,
+                cells.iter().join(","),
+                instruction
+            ),
+            Line::Data { address, value } => {
+                write!(f, "{:>5}  {:<24}DATA {}", address, value, value)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mode {
+            Mode::Position => write!(f, "[{}]", self.value),
+            Mode::Immediate => write!(f, "#{}", self.value),
+            Mode::Relative if self.value < 0 => write!(f, "[rb{}]", self.value),
+            Mode::Relative => write!(f, "[rb+{}]", self.value),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Add {
+                left,
+                right,
+                target,
+            } => write!(f, "ADD {}, {} -> {}", left, right, target),
+            Instruction::Multiply {
+                left,
+                right,
+                target,
+            } => write!(f, "MUL {}, {} -> {}", left, right, target),
+            Instruction::Input { target } => write!(f, "IN -> {}", target),
+            Instruction::Output { source } => write!(f, "OUT {}", source),
+            Instruction::JumpIfTrue { condition, target } => {
+                write!(f, "JT {}, {}", condition, target)
+            }
+            Instruction::JumpIfFalse { condition, target } => {
+                write!(f, "JF {}, {}", condition, target)
+            }
+            Instruction::LessThan {
+                left,
+                right,
+                target,
+            } => write!(f, "LT {}, {} -> {}", left, right, target),
+            Instruction::Equals {
+                left,
+                right,
+                target,
+            } => write!(f, "EQ {}, {} -> {}", left, right, target),
+            Instruction::AdjustRelativeBase { offset } => write!(f, "ARB {}", offset),
+            Instruction::Halt => write!(f, "HLT"),
+        }
+    }
+}
+
+/// Splits memory into code and data.
+///
+/// Code is anything reachable by following control flow from address 0 and the current
+/// instruction pointer. Jump targets are followed when they are immediate, or point at a cell
+/// whose current value can be used. Everything else, including cells which fail to decode, is
+/// treated as data.
+pub fn disassemble(stack: &Stack) -> Vec<Line> {
+    let code = reachable(stack);
+
+    let mut lines = Vec::new();
+    let mut address = 0;
+
+    while address < stack.memory.len() {
+        match code.get(&address) {
+            Some(instruction) => {
+                let size = instruction.size();
+                lines.push(Line::Code {
+                    address,
+                    cells: stack.memory[address..address + size].to_vec(),
+                    instruction: *instruction,
+                });
+                address += size;
+            }
+            None => {
+                lines.push(Line::Data {
+                    address,
+                    value: stack.memory[address],
+                });
+                address += 1;
+            }
+        }
+    }
+
+    lines
+}
+
+pub fn listing(stack: &Stack) -> String {
+    disassemble(stack).iter().join("\n")
+}
+
+fn reachable(stack: &Stack) -> HashMap<usize, Instruction> {
+    let mut code = HashMap::new();
+    let mut claimed = vec![false; stack.memory.len()];
+    let mut pending = vec![stack.instruction_pointer, 0];
+
+    while let Some(address) = pending.pop() {
+        if code.contains_key(&address) {
+            continue;
+        }
+
+        let Ok(instruction) = stack.decode(address) else {
+            continue;
+        };
+
+        let cells = address..address + instruction.size();
+        if cells.end > stack.memory.len() || cells.clone().any(|cell| claimed[cell]) {
+            continue;
+        }
+
+        for cell in cells {
+            claimed[cell] = true;
+        }
+
+        code.insert(address, instruction);
+        pending.extend(successors(stack, address, &instruction));
+    }
+
+    code
+}
+
+fn successors(stack: &Stack, address: usize, instruction: &Instruction) -> Vec<usize> {
+    let next = address + instruction.size();
+
+    let branch = |condition: &Parameter, target: &Parameter, jump_if: bool| {
+        let target = constant(stack, target).and_then(|target| usize::try_from(target).ok());
+
+        match condition.mode {
+            // The `JT #1, ...` idiom is an unconditional jump, so it never falls through
+            Mode::Immediate if (condition.value != 0) == jump_if => target.into_iter().collect(),
+            _ => target.into_iter().chain([next]).collect(),
+        }
+    };
+
+    match instruction {
+        Instruction::Halt => vec![],
+        Instruction::JumpIfTrue { condition, target } => branch(condition, target, true),
+        Instruction::JumpIfFalse { condition, target } => branch(condition, target, false),
+        _ => vec![next],
+    }
+}
+
+/// The value of a parameter, if it can be known without running the program
+fn constant(stack: &Stack, parameter: &Parameter) -> Option<i64> {
+    match parameter.mode {
+        Mode::Immediate => Some(parameter.value),
+        Mode::Position => usize::try_from(parameter.value)
+            .ok()
+            .and_then(|address| stack.memory.get(address))
+            .copied(),
+        Mode::Relative => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    fn mnemonics(program: &str) -> Result<Vec<String>> {
+        Ok(disassemble(&program.parse()?)
+            .into_iter()
+            .map(|line| match line {
+                Line::Code { instruction, .. } => instruction.to_string(),
+                Line::Data { value, .. } => format!("DATA {}", value),
+            })
+            .collect())
+    }
+
+    #[test]
+    fn test_listing() -> Result<()> {
+        assert_eq!(
+            listing(&"1,9,10,3,2,3,11,0,99,30,40,50".parse()?),
+            "    0  1,9,10,3                ADD [9], [10] -> [3]
+    4  2,3,11,0                MUL [3], [11] -> [0]
+    8  99                      HLT
+    9  30                      DATA 30
+   10  40                      DATA 40
+   11  50                      DATA 50"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parameter_modes() -> Result<()> {
+        assert_eq!(
+            mnemonics("1002,4,3,4,33")?,
+            vec!["MUL [4], #3 -> [4]", "DATA 33"]
+        );
+        assert_eq!(
+            mnemonics("109,5,204,-1,21101,2,3,7,99")?,
+            vec!["ARB #5", "OUT [rb-1]", "ADD #2, #3 -> [rb+7]", "HLT"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_control_flow() -> Result<()> {
+        // Both branches of a conditional jump are followed
+        assert_eq!(
+            mnemonics("3,3,1105,0,9,1101,0,0,12,4,12,99,1")?,
+            vec![
+                "IN -> [3]",
+                "JT #0, #9",
+                "ADD #0, #0 -> [12]",
+                "OUT [12]",
+                "HLT",
+                "DATA 1"
+            ]
+        );
+
+        // Unconditional jumps skip over data, and position-mode targets are resolved
+        assert_eq!(
+            mnemonics("1105,1,4,7,106,0,7,10,5,5,99")?,
+            vec![
+                "JT #1, #4",
+                "DATA 7",
+                "JF #0, [7]",
+                "DATA 10",
+                "DATA 5",
+                "DATA 5",
+                "HLT"
+            ]
+        );
+
+        Ok(())
+    }
+}
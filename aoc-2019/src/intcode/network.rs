@@ -0,0 +1,295 @@
+use super::{Error, Stack, State};
+
+/// How the outputs of each machine are wired to the inputs of the others
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Topology {
+    /// Each machine feeds the next, and the last machine feeds the network's outputs
+    Chain,
+    /// As with `Chain`, but the last machine also feeds back into the first
+    Ring,
+    /// Machines emit `address, x, y` triples which are delivered to the machine at that address.
+    /// Machines waiting on an empty input queue receive -1, and packets addressed outside the
+    /// network are collected for the caller.
+    PacketBus,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Packet {
+    pub address: i64,
+    pub x: i64,
+    pub y: i64,
+}
+
+/// Why the network stopped running
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    /// Every machine has halted
+    Halted,
+    /// A full round passed without any machine sending or receiving anything
+    Idle,
+}
+
+#[derive(Debug)]
+pub struct Network {
+    machines: Vec<Stack>,
+    states: Vec<Option<State>>,
+    topology: Topology,
+    partial_packets: Vec<Vec<i64>>,
+    outputs: Vec<i64>,
+    packets: Vec<Packet>,
+}
+
+impl Network {
+    pub fn new(machines: Vec<Stack>, topology: Topology) -> Self {
+        Network {
+            states: vec![None; machines.len()],
+            partial_packets: vec![Vec::new(); machines.len()],
+            machines,
+            topology,
+            outputs: Vec::new(),
+            packets: Vec::new(),
+        }
+    }
+
+    /// Builds a packet bus of `size` copies of `program`, each given its address as its first input
+    pub fn packet_bus(program: &Stack, size: usize) -> Self {
+        let machines = (0..size)
+            .map(|address| {
+                let mut machine = program.clone();
+                machine.push_input(address as i64);
+                machine
+            })
+            .collect();
+
+        Network::new(machines, Topology::PacketBus)
+    }
+
+    pub fn machine(&self, index: usize) -> Option<&Stack> {
+        self.machines.get(index)
+    }
+
+    pub fn machine_mut(&mut self, index: usize) -> Option<&mut Stack> {
+        self.machines.get_mut(index)
+    }
+
+    /// Values output by the last machine of a `Chain` or `Ring`
+    pub fn outputs(&self) -> &[i64] {
+        &self.outputs
+    }
+
+    pub fn take_outputs(&mut self) -> Vec<i64> {
+        std::mem::take(&mut self.outputs)
+    }
+
+    /// Packets sent to addresses outside of a `PacketBus`
+    pub fn take_packets(&mut self) -> Vec<Packet> {
+        std::mem::take(&mut self.packets)
+    }
+
+    /// Delivers a packet into a `PacketBus`, or holds it for the caller if nothing lives at its address
+    pub fn send(&mut self, packet: Packet) {
+        match usize::try_from(packet.address)
+            .ok()
+            .and_then(|address| self.machines.get_mut(address))
+        {
+            Some(machine) => machine.extend_inputs([packet.x, packet.y]),
+            None => self.packets.push(packet),
+        }
+    }
+
+    /// Runs rounds until every machine has halted or the network goes idle
+    pub fn run(&mut self) -> Result<Outcome, Error> {
+        loop {
+            if let Some(outcome) = self.round()? {
+                return Ok(outcome);
+            }
+        }
+    }
+
+    /// Gives each machine, in address order, a turn to run until it blocks.
+    /// Returns the outcome if the network made no progress during the round.
+    pub fn round(&mut self) -> Result<Option<Outcome>, Error> {
+        let mut traffic = false;
+
+        for index in 0..self.machines.len() {
+            if self.states[index] == Some(State::Halted) {
+                continue;
+            }
+
+            if self.topology == Topology::PacketBus && self.machines[index].inputs().is_empty() {
+                self.machines[index].push_input(-1);
+            } else {
+                traffic |= !self.machines[index].inputs().is_empty();
+            }
+
+            self.states[index] = Some(self.machines[index].run_until_blocked()?);
+            let outputs = self.machines[index].take_outputs();
+            traffic |= !outputs.is_empty();
+            self.route(index, outputs);
+        }
+
+        if self
+            .states
+            .iter()
+            .all(|state| *state == Some(State::Halted))
+        {
+            Ok(Some(Outcome::Halted))
+        } else if traffic {
+            Ok(None)
+        } else {
+            Ok(Some(Outcome::Idle))
+        }
+    }
+
+    fn route(&mut self, from: usize, outputs: Vec<i64>) {
+        let last = from + 1 == self.machines.len();
+
+        match self.topology {
+            Topology::Chain if last => self.outputs.extend(outputs),
+            Topology::Ring if last => {
+                self.outputs.extend(outputs.iter().copied());
+                self.machines[0].extend_inputs(outputs);
+            }
+            Topology::Chain | Topology::Ring => self.machines[from + 1].extend_inputs(outputs),
+            Topology::PacketBus => {
+                self.partial_packets[from].extend(outputs);
+
+                let complete = self.partial_packets[from].len() / 3 * 3;
+                let packets = self.partial_packets[from]
+                    .drain(..complete)
+                    .collect::<Vec<_>>();
+
+                for packet in packets.chunks(3) {
+                    self.send(Packet {
+                        address: packet[0],
+                        x: packet[1],
+                        y: packet[2],
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use itertools::Itertools;
+
+    static AMPLIFIER: &str = "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0";
+    static FEEDBACK_AMPLIFIER: &str =
+        "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5";
+    // Polls for packets, and forwards each to the next address with its y incremented
+    static FORWARDER: &str =
+        "3,100,3,101,1008,101,-1,102,1005,102,2,3,103,1001,100,1,104,1001,103,1,103,4,104,4,101,4,103,1105,1,2";
+
+    fn amplifiers(program: &str, phases: &[i64], topology: Topology) -> Result<Network> {
+        let program: Stack = program.parse()?;
+        let machines = phases
+            .iter()
+            .map(|phase| {
+                let mut machine = program.clone();
+                machine.push_input(*phase);
+                machine
+            })
+            .collect();
+
+        let mut network = Network::new(machines, topology);
+        network.machine_mut(0).unwrap().push_input(0);
+        Ok(network)
+    }
+
+    #[test]
+    fn test_chain() -> Result<()> {
+        let mut network = amplifiers(AMPLIFIER, &[4, 3, 2, 1, 0], Topology::Chain)?;
+        assert_eq!(network.run()?, Outcome::Halted);
+        assert_eq!(network.outputs(), &[43210]);
+
+        let best = (0..5)
+            .permutations(5)
+            .map(|phases| -> Result<i64> {
+                let mut network = amplifiers(AMPLIFIER, &phases, Topology::Chain)?;
+                network.run()?;
+                Ok(network.outputs()[0])
+            })
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(best.into_iter().max(), Some(43210));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ring() -> Result<()> {
+        let mut network = amplifiers(FEEDBACK_AMPLIFIER, &[9, 8, 7, 6, 5], Topology::Ring)?;
+        assert_eq!(network.run()?, Outcome::Halted);
+        assert_eq!(network.outputs().last(), Some(&139629729));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_chain_idle() -> Result<()> {
+        // The second machine waits on a second input which never arrives
+        let machines = vec!["3,0,4,0,99".parse()?, "3,0,3,0,99".parse()?];
+        let mut network = Network::new(machines, Topology::Chain);
+        network.machine_mut(0).unwrap().push_input(1);
+
+        assert_eq!(network.round()?, None);
+        assert_eq!(network.round()?, Some(Outcome::Idle));
+        assert_eq!(network.machine(1).unwrap().inputs().len(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_packet_bus() -> Result<()> {
+        let mut network = Network::packet_bus(&FORWARDER.parse()?, 3);
+        assert_eq!(network.run()?, Outcome::Idle);
+        assert!(network.take_packets().is_empty());
+
+        network.send(Packet {
+            address: 0,
+            x: 5,
+            y: 10,
+        });
+        assert_eq!(network.run()?, Outcome::Idle);
+        assert_eq!(
+            network.take_packets(),
+            vec![Packet {
+                address: 3,
+                x: 5,
+                y: 13
+            }]
+        );
+
+        network.send(Packet {
+            address: 2,
+            x: 1,
+            y: 1,
+        });
+        network.send(Packet {
+            address: 1,
+            x: 2,
+            y: 2,
+        });
+        assert_eq!(network.run()?, Outcome::Idle);
+        assert_eq!(
+            network.take_packets(),
+            vec![
+                Packet {
+                    address: 3,
+                    x: 1,
+                    y: 2
+                },
+                Packet {
+                    address: 3,
+                    x: 2,
+                    y: 4
+                }
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+use super::{Instruction, Stack};
+use std::collections::VecDeque;
+
+/// A copy of everything needed to resume a machine from an earlier point
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    memory: Vec<i64>,
+    instruction_pointer: usize,
+    relative_base: i64,
+    inputs: VecDeque<i64>,
+    outputs: Vec<i64>,
+    steps: usize,
+    halted: bool,
+}
+
+/// What a single executed instruction changed, so that it can be undone
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Change {
+    pub(super) instruction_pointer: usize,
+    pub(super) relative_base: i64,
+    pub(super) steps: usize,
+    pub(super) memory_len: usize,
+    pub(super) outputs_len: usize,
+    /// The address written to, and the value it held beforehand
+    pub(super) write: Option<(usize, i64)>,
+    /// The input consumed by the instruction
+    pub(super) input: Option<i64>,
+}
+
+/// The most recent changes made by a machine, up to a fixed capacity
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct History {
+    changes: VecDeque<Change>,
+    capacity: usize,
+}
+
+impl History {
+    pub(super) fn new(capacity: usize) -> Self {
+        History {
+            changes: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub(super) fn push(&mut self, change: Change) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.changes.len() == self.capacity {
+            self.changes.pop_front();
+        }
+
+        self.changes.push_back(change);
+    }
+
+    pub(super) fn pop(&mut self) -> Option<Change> {
+        self.changes.pop_back()
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.changes.clear();
+    }
+}
+
+impl Stack {
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            memory: self.memory.clone(),
+            instruction_pointer: self.instruction_pointer,
+            relative_base: self.relative_base,
+            inputs: self.inputs.clone(),
+            outputs: self.outputs.clone(),
+            steps: self.steps,
+            halted: self.halted,
+        }
+    }
+
+    /// Returns the machine to the state it was in when the snapshot was taken.
+    /// Breakpoints and watchpoints are kept, but the history is cleared.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.memory.clone_from(&snapshot.memory);
+        self.decoded.clear();
+        self.instruction_pointer = snapshot.instruction_pointer;
+        self.relative_base = snapshot.relative_base;
+        self.inputs.clone_from(&snapshot.inputs);
+        self.outputs.clone_from(&snapshot.outputs);
+        self.steps = snapshot.steps;
+        self.halted = snapshot.halted;
+        self.debugger.clear_history();
+    }
+
+    /// Undoes the most recently executed instruction, if the debugger's history still holds it.
+    /// Returns whether a step was undone.
+    pub fn step_back(&mut self) -> bool {
+        let Some(change) = self.debugger.forget() else {
+            return false;
+        };
+
+        if let Some((address, old)) = change.write {
+            self.memory[address] = old;
+            self.invalidate(address);
+        }
+        self.memory.truncate(change.memory_len);
+        // Instructions near the end may have been decoded from cells which no longer exist
+        self.decoded
+            .truncate(change.memory_len.saturating_sub(Instruction::MAX_SIZE - 1));
+
+        if let Some(input) = change.input {
+            self.inputs.push_front(input);
+        }
+        self.outputs.truncate(change.outputs_len);
+
+        self.instruction_pointer = change.instruction_pointer;
+        self.relative_base = change.relative_base;
+        self.steps = change.steps;
+        self.halted = false;
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::intcode::{assembler::assemble, State};
+    use anyhow::Result;
+
+    static COUNTDOWN: &str = "
+            IN -> [n]
+    loop:   JF [n], #done
+            OUT [n]
+            ADD [n], #-1 -> [n]
+            JT #1, #loop
+    done:   HLT
+    n:      DATA 0
+    ";
+
+    #[test]
+    fn test_snapshot() -> Result<()> {
+        let mut stack = assemble(COUNTDOWN)?;
+        assert_eq!(stack.run()?, State::NeedsInput);
+        let snapshot = stack.snapshot();
+
+        stack.push_input(3);
+        stack.run_to_halt()?;
+        assert_eq!(stack.take_outputs(), vec![3, 2, 1]);
+
+        stack.restore(&snapshot);
+        assert_eq!(stack.snapshot(), snapshot);
+        stack.push_input(1);
+        stack.run_to_halt()?;
+        assert_eq!(stack.take_outputs(), vec![1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fork() -> Result<()> {
+        let mut stack = assemble(COUNTDOWN)?;
+        stack.push_input(2);
+        assert_eq!(stack.run()?, State::Output(2));
+
+        let mut fork = stack.clone();
+        assert_eq!(fork.run()?, State::Output(1));
+        assert_eq!(fork.run()?, State::Halted);
+
+        assert_eq!(stack.snapshot().instruction_pointer, 7);
+        assert_eq!(stack.run()?, State::Output(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_step_back() -> Result<()> {
+        let mut stack = assemble(COUNTDOWN)?;
+        let initial = stack.snapshot();
+        stack.debugger_mut().enable_history(100);
+        stack.push_input(2);
+        let queued = stack.snapshot();
+
+        stack.run_to_halt()?;
+        assert_eq!(stack.take_outputs(), vec![2, 1]);
+
+        while stack.step_back() {}
+        assert_eq!(stack.snapshot(), queued);
+        assert!(!stack.step_back());
+
+        stack.run_until_blocked()?;
+        assert_eq!(stack.outputs(), &[2, 1]);
+
+        // Undoing the last output, past HLT, JF, JT and ADD, removes it from the buffer
+        for _ in 0..5 {
+            assert!(stack.step_back());
+        }
+        assert_eq!(stack.outputs(), &[2]);
+        assert_eq!(stack.instruction_pointer(), 5);
+
+        stack.restore(&initial);
+        assert!(!stack.step_back());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bounded_history() -> Result<()> {
+        let mut stack = assemble("ADD #1, #2 -> [100]\nADD #3, #4 -> [101]\nHLT")?;
+        stack.debugger_mut().enable_history(1);
+        stack.run_to_halt()?;
+
+        assert!(stack.step_back());
+        assert!(!stack.step_back());
+        assert_eq!(stack.snapshot().steps, 2);
+        assert_eq!(stack.snapshot().instruction_pointer, 8);
+
+        assert_eq!(stack.debugger().history_len(), 0);
+        assert_eq!(stack.run()?, State::Halted);
+        assert_eq!(stack.debugger().history_len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_halted() -> Result<()> {
+        let mut stack = assemble("OUT #1\nHLT")?;
+        stack.debugger_mut().enable_history(10);
+        stack.run_to_halt()?;
+        let halted = stack.snapshot();
+
+        assert_eq!(stack.run()?, State::Halted);
+        assert_eq!(stack.run()?, State::Halted);
+        assert_eq!(stack.snapshot(), halted);
+        assert_eq!(stack.steps(), 2);
+        assert_eq!(stack.debugger().history_len(), 2);
+
+        assert!(stack.step_back());
+        assert_eq!(stack.steps(), 1);
+        assert_eq!(stack.run()?, State::Halted);
+        assert_eq!(stack.steps(), 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,466 @@
+//! Runs a program with some memory cells left as unknowns, tracking every other cell as a linear
+//! expression in them.
+//!
+//! Control flow, addresses and opcodes must not depend on the unknowns, and a value is only
+//! tracked while it stays linear: multiplying two unknowns, comparing them, or reading through an
+//! unknown address all produce a non-linear value. Non-linear values are harmless until they are
+//! needed, so programs which overwrite them, like those from day 2, still solve.
+
+use super::{Error, Mode, Stack};
+use itertools::Itertools;
+use std::fmt;
+use std::iter;
+use std::ops::RangeInclusive;
+
+/// `constant + coefficients[0] * x0 + coefficients[1] * x1 + ...`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Expression {
+    pub constant: i64,
+    pub coefficients: Vec<i64>,
+}
+
+impl Expression {
+    fn constant(value: i64, variables: usize) -> Self {
+        Expression {
+            constant: value,
+            coefficients: vec![0; variables],
+        }
+    }
+
+    fn variable(index: usize, variables: usize) -> Self {
+        let mut expression = Expression::constant(0, variables);
+        expression.coefficients[index] = 1;
+        expression
+    }
+
+    /// The expression's value, if it does not depend on any unknowns
+    pub fn as_constant(&self) -> Option<i64> {
+        self.coefficients
+            .iter()
+            .all(|coefficient| *coefficient == 0)
+            .then_some(self.constant)
+    }
+
+    /// The expression's value for the given unknowns, or `None` if it overflows
+    pub fn evaluate(&self, values: &[i64]) -> Option<i64> {
+        self.coefficients
+            .iter()
+            .zip(values)
+            .try_fold(self.constant, |sum, (coefficient, value)| {
+                sum.checked_add(coefficient.checked_mul(*value)?)
+            })
+    }
+
+    fn add(&self, other: &Expression) -> Option<Expression> {
+        Some(Expression {
+            constant: self.constant.checked_add(other.constant)?,
+            coefficients: self
+                .coefficients
+                .iter()
+                .zip(&other.coefficients)
+                .map(|(a, b)| a.checked_add(*b))
+                .collect::<Option<_>>()?,
+        })
+    }
+
+    fn scale(&self, factor: i64) -> Option<Expression> {
+        Some(Expression {
+            constant: self.constant.checked_mul(factor)?,
+            coefficients: self
+                .coefficients
+                .iter()
+                .map(|c| c.checked_mul(factor))
+                .collect::<Option<_>>()?,
+        })
+    }
+
+    /// Finds values for each unknown within `range` which make the expression equal `target`,
+    /// preferring the smallest values for the earliest unknowns
+    pub fn solve(&self, target: i64, range: RangeInclusive<i64>) -> Option<Vec<i64>> {
+        let Some((last, rest)) = self.coefficients.split_last() else {
+            return (self.constant == target).then(Vec::new);
+        };
+
+        // `multi_cartesian_product` yields nothing at all when there are no ranges to combine
+        let mut choices: Box<dyn Iterator<Item = Vec<i64>>> = if rest.is_empty() {
+            Box::new(iter::once(Vec::new()))
+        } else {
+            Box::new(rest.iter().map(|_| range.clone()).multi_cartesian_product())
+        };
+
+        choices.find_map(|mut values| {
+            let partial = Expression {
+                constant: self.constant,
+                coefficients: rest.to_vec(),
+            };
+            let remainder = target.checked_sub(partial.evaluate(&values)?)?;
+
+            let value = match *last {
+                0 if remainder == 0 => *range.start(),
+                0 => return None,
+                last if remainder.checked_rem(last) == Some(0) => remainder / last,
+                _ => return None,
+            };
+
+            range.contains(&value).then(|| {
+                values.push(value);
+                values
+            })
+        })
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.constant)?;
+
+        for (index, coefficient) in self.coefficients.iter().enumerate() {
+            match *coefficient {
+                0 => {}
+                1 => write!(f, " + x{}", index)?,
+                -1 => write!(f, " - x{}", index)?,
+                c if c < 0 => write!(f, " - {}*x{}", -c, index)?,
+                c => write!(f, " + {}*x{}", c, index)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Cell {
+    Linear(Expression),
+    /// A value which is not linear in the unknowns, and the instruction that produced it
+    NonLinear {
+        pc: usize,
+    },
+}
+
+/// A copy of a [`Stack`] whose memory holds expressions rather than values
+#[derive(Clone, Debug)]
+pub struct Symbolic {
+    memory: Vec<Cell>,
+    variables: usize,
+    memory_limit: usize,
+    instruction_pointer: usize,
+    relative_base: i64,
+    steps: usize,
+    step_limit: Option<usize>,
+}
+
+impl Symbolic {
+    /// Copies a machine, replacing each cell in `unknowns` with an unknown. The first address
+    /// becomes `x0`, the second `x1` and so on.
+    pub fn new(stack: &Stack, unknowns: &[usize]) -> Self {
+        let variables = unknowns.len();
+        let mut memory = stack
+            .memory
+            .iter()
+            .map(|value| Cell::Linear(Expression::constant(*value, variables)))
+            .collect::<Vec<_>>();
+
+        for (index, address) in unknowns.iter().enumerate() {
+            if *address >= memory.len() {
+                memory.resize(
+                    address + 1,
+                    Cell::Linear(Expression::constant(0, variables)),
+                );
+            }
+            memory[*address] = Cell::Linear(Expression::variable(index, variables));
+        }
+
+        Symbolic {
+            memory,
+            variables,
+            memory_limit: stack.memory_limit,
+            instruction_pointer: stack.instruction_pointer,
+            relative_base: stack.relative_base,
+            steps: 0,
+            step_limit: stack.step_limit,
+        }
+    }
+
+    /// The expression held at `address`.
+    /// Fails with [`Error::NonLinear`] if the value there is not linear in the unknowns.
+    pub fn expression(&self, address: usize) -> Result<Expression, Error> {
+        match self.read(address)? {
+            Cell::Linear(expression) => Ok(expression),
+            Cell::NonLinear { pc } => Err(Error::NonLinear { pc }),
+        }
+    }
+
+    pub fn output(&self) -> Result<Expression, Error> {
+        self.expression(0)
+    }
+
+    /// Runs until the program halts. Programs which read input are not supported, and fail with
+    /// [`Error::InputExhausted`].
+    pub fn run_to_halt(&mut self) -> Result<(), Error> {
+        while self.step()? {}
+        Ok(())
+    }
+
+    /// Executes a single instruction, returning whether the program is still running
+    fn step(&mut self) -> Result<bool, Error> {
+        let pc = self.instruction_pointer;
+
+        if let Some(limit) = self.step_limit {
+            if self.steps >= limit {
+                return Err(Error::StepLimitExceeded { pc, limit });
+            }
+        }
+        self.steps += 1;
+
+        let opcode = self.constant(pc, pc)?;
+        let operand = |offset: usize| -> Result<(Mode, Cell), Error> {
+            let mode = (opcode / 10i64.pow(offset as u32 + 1)) % 10;
+            let mode =
+                Mode::try_from(mode).map_err(|mode| Error::InvalidMode { pc, opcode, mode })?;
+            Ok((mode, self.read(pc + offset)?))
+        };
+
+        match opcode % 100 {
+            opcode @ (1 | 2 | 7 | 8) => {
+                let left = self.load(operand(1)?)?;
+                let right = self.load(operand(2)?)?;
+                let value = Symbolic::combine(opcode, left, right, pc)?;
+                let target = self.location(operand(3)?)?;
+                self.write(target, value)?;
+                self.instruction_pointer += 4;
+            }
+            3 => return Err(Error::InputExhausted { pc }),
+            4 => self.instruction_pointer += 2,
+            opcode @ (5 | 6) => {
+                let condition = self.known(self.load(operand(1)?)?, pc)?;
+                let target = self.known(self.load(operand(2)?)?, pc)?;
+
+                if (condition != 0) == (opcode == 5) {
+                    self.instruction_pointer = self.address(target)?;
+                } else {
+                    self.instruction_pointer += 3;
+                }
+            }
+            9 => {
+                let offset = self.known(self.load(operand(1)?)?, pc)?;
+                self.relative_base = self
+                    .relative_base
+                    .checked_add(offset)
+                    .ok_or(Error::Overflow { pc })?;
+                self.instruction_pointer += 2;
+            }
+            99 => return Ok(false),
+            _ => return Err(Error::InvalidOpcode { pc, opcode }),
+        }
+
+        Ok(true)
+    }
+
+    /// Fails with [`Error::Overflow`] if any part of the result overflows, even though it might
+    /// not for the values the unknowns end up taking
+    fn combine(opcode: i64, left: Cell, right: Cell, pc: usize) -> Result<Cell, Error> {
+        let (Cell::Linear(left), Cell::Linear(right)) = (left, right) else {
+            return Ok(Cell::NonLinear { pc });
+        };
+        let overflow = Error::Overflow { pc };
+
+        let value = match (opcode, left.as_constant(), right.as_constant()) {
+            (1, _, _) => Some(left.add(&right).ok_or(overflow)?),
+            (2, Some(factor), _) => Some(right.scale(factor).ok_or(overflow)?),
+            (2, _, Some(factor)) => Some(left.scale(factor).ok_or(overflow)?),
+            (7, Some(a), Some(b)) => Some(Expression::constant(
+                (a < b) as i64,
+                left.coefficients.len(),
+            )),
+            (8, Some(a), Some(b)) => Some(Expression::constant(
+                (a == b) as i64,
+                left.coefficients.len(),
+            )),
+            _ => None,
+        };
+
+        Ok(value.map_or(Cell::NonLinear { pc }, Cell::Linear))
+    }
+
+    fn load(&self, (mode, cell): (Mode, Cell)) -> Result<Cell, Error> {
+        if mode == Mode::Immediate {
+            return Ok(cell);
+        }
+
+        match self.location((mode, cell)) {
+            Ok(address) => self.read(address),
+            // Reading through an unknown address could see anything
+            Err(Error::NonLinear { pc }) => Ok(Cell::NonLinear { pc }),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn location(&self, (mode, cell): (Mode, Cell)) -> Result<usize, Error> {
+        let pc = self.instruction_pointer;
+        let value = self.known(cell, pc)?;
+
+        match mode {
+            Mode::Position => self.address(value),
+            Mode::Immediate => Err(Error::WriteToImmediate { pc }),
+            Mode::Relative => self.address(
+                self.relative_base
+                    .checked_add(value)
+                    .ok_or(Error::Overflow { pc })?,
+            ),
+        }
+    }
+
+    /// The value of a cell which must not depend on the unknowns
+    fn known(&self, cell: Cell, pc: usize) -> Result<i64, Error> {
+        match cell {
+            Cell::Linear(expression) => expression.as_constant().ok_or(Error::NonLinear { pc }),
+            Cell::NonLinear { .. } => Err(Error::NonLinear { pc }),
+        }
+    }
+
+    fn constant(&self, address: usize, pc: usize) -> Result<i64, Error> {
+        self.known(self.read(address)?, pc)
+    }
+
+    fn address(&self, address: i64) -> Result<usize, Error> {
+        match usize::try_from(address) {
+            Ok(index) if index < self.memory_limit => Ok(index),
+            _ => Err(Error::OutOfBounds {
+                pc: self.instruction_pointer,
+                address,
+                limit: self.memory_limit,
+            }),
+        }
+    }
+
+    fn read(&self, address: usize) -> Result<Cell, Error> {
+        let address = self.address(i64::try_from(address).unwrap_or(i64::MAX))?;
+
+        Ok(self
+            .memory
+            .get(address)
+            .cloned()
+            .unwrap_or_else(|| Cell::Linear(Expression::constant(0, self.variables))))
+    }
+
+    fn write(&mut self, address: usize, value: Cell) -> Result<(), Error> {
+        if address >= self.memory.len() {
+            self.memory.resize(
+                address + 1,
+                Cell::Linear(Expression::constant(0, self.variables)),
+            );
+        }
+
+        self.memory[address] = value;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    // Shaped like a day 2 program: the first instruction reads through the unknowns, but its
+    // result is overwritten by `7 * (x0 + x1) + 3`
+    static LINEAR: &str = "1,0,0,3,1,1,2,3,2,3,17,3,1,3,18,0,99,7,3";
+
+    fn solve(program: &str) -> Result<Expression, Error> {
+        let stack: Stack = program.parse()?;
+        let mut symbolic = Symbolic::new(&stack, &[1, 2]);
+        symbolic.run_to_halt()?;
+        symbolic.output()
+    }
+
+    #[test]
+    fn test_linear() -> Result<()> {
+        let expression = solve(LINEAR)?;
+        assert_eq!(
+            expression,
+            Expression {
+                constant: 3,
+                coefficients: vec![7, 7]
+            }
+        );
+        assert_eq!(expression.to_string(), "3 + 7*x0 + 7*x1");
+
+        let mut stack: Stack = LINEAR.parse()?;
+        stack.input(12, 2)?;
+        stack.run_to_halt()?;
+        assert_eq!(Some(stack.output()?), expression.evaluate(&[12, 2]));
+
+        assert_eq!(expression.solve(101, 0..=99), Some(vec![0, 14]));
+        assert_eq!(expression.solve(101, 5..=99), Some(vec![5, 9]));
+        assert_eq!(expression.solve(100, 0..=99), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_non_linear() -> Result<()> {
+        // Multiplies the two unknowns together
+        assert_eq!(solve("1,0,0,3,2,1,2,0,99"), Err(Error::NonLinear { pc: 4 }));
+        // Reads through an unknown address, and keeps the result
+        assert_eq!(solve("1,0,0,0,99"), Err(Error::NonLinear { pc: 0 }));
+        // Branches on an unknown
+        assert_eq!(
+            solve("1,0,0,3,1005,1,9,1101,0,0,0,99"),
+            Err(Error::NonLinear { pc: 4 })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve() {
+        let single = Expression {
+            constant: 1,
+            coefficients: vec![-2],
+        };
+        assert_eq!(single.solve(-9, 0..=10), Some(vec![5]));
+        assert_eq!(single.solve(-8, 0..=10), None);
+        assert_eq!(single.to_string(), "1 - 2*x0");
+
+        let constant = Expression::constant(4, 2);
+        assert_eq!(constant.as_constant(), Some(4));
+        assert_eq!(constant.solve(4, 1..=3), Some(vec![1, 1]));
+        assert_eq!(constant.solve(5, 1..=3), None);
+
+        let large = Expression {
+            constant: i64::MAX,
+            coefficients: vec![i64::MAX],
+        };
+        assert_eq!(large.evaluate(&[0]), Some(i64::MAX));
+        assert_eq!(large.evaluate(&[1]), None);
+        assert_eq!(large.solve(i64::MIN, 0..=2), None);
+    }
+
+    #[test]
+    fn test_overflow() {
+        // Each starts by adding the unknowns, in place of its first two parameters, into [20]
+        let max = i64::MAX;
+        assert_eq!(
+            solve(&format!("1101,0,0,20,1001,20,{},0,99", max)),
+            Ok(Expression {
+                constant: max,
+                coefficients: vec![1, 1]
+            })
+        );
+        assert_eq!(
+            solve(&format!("1101,0,0,20,1102,{},2,0,99", max)),
+            Err(Error::Overflow { pc: 4 })
+        );
+        assert_eq!(
+            solve(&format!("1101,0,0,20,1002,20,{},20,1002,20,2,0,99", max)),
+            Err(Error::Overflow { pc: 8 })
+        );
+        assert_eq!(
+            solve(&format!("1101,0,0,20,109,{},109,1,99", max)),
+            Err(Error::Overflow { pc: 6 })
+        );
+        assert_eq!(
+            solve(&format!("1101,0,0,20,109,{},2201,1,1,0,99", max)),
+            Err(Error::Overflow { pc: 6 })
+        );
+    }
+}
@@ -0,0 +1,13 @@
+pub mod fuel;
+pub mod intcode;
+
+mod day01;
+mod day02;
+mod day03;
+
+aoc_common::solutions! {
+  year 2019;
+  day01 :parse?     => part_1?, part_2?;
+  day02             => part_1?, part_2?;
+  day03 :parse?     => part_1?, part_2?;
+}
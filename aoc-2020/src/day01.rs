@@ -1,10 +1,10 @@
-use std::num::ParseIntError;
-use std::str::FromStr;
-
+use aoc_common::input::{parse_lines, InputError};
 use fnv::FnvHashSet;
 
-pub fn parse(input: &str) -> Result<FnvHashSet<u32>, ParseIntError> {
-    input.lines().map(FromStr::from_str).collect()
+const DAY: u8 = 1;
+
+pub fn parse(input: &str) -> Result<FnvHashSet<u32>, InputError> {
+    parse_lines(DAY, input, str::parse)
 }
 
 pub fn part_1(input: &FnvHashSet<u32>) -> Option<u32> {
@@ -1,8 +1,11 @@
 use std::str::FromStr;
 
+use aoc_common::input::{parse_lines, InputError};
 use recap::Recap;
 use serde::Deserialize;
 
+const DAY: u8 = 2;
+
 #[derive(Debug, Deserialize, Hash, PartialEq, Eq, Recap)]
 #[recap(regex = r#"(?x)
     (?P<number_1>\d+)
@@ -26,9 +29,11 @@ impl PasswordValidator {
         count >= self.number_1 && count <= self.number_2
     }
 
-    // This is 1-indexed
+    // This is 1-indexed. There is no character at 0, or past the end of the password.
     fn has_char_at_index(&self, idx: usize) -> bool {
-        char::from(self.password.as_bytes()[idx - 1]) == self.character
+        idx.checked_sub(1)
+            .and_then(|idx| self.password.chars().nth(idx))
+            == Some(self.character)
     }
 
     fn valid_by_index(&self) -> bool {
@@ -36,16 +41,16 @@ impl PasswordValidator {
     }
 }
 
-pub fn parse(input: &str) -> impl Iterator<Item = PasswordValidator> + '_ {
-    input.lines().map(FromStr::from_str).map(|x| x.unwrap())
+pub fn parse(input: &str) -> Result<Vec<PasswordValidator>, InputError> {
+    parse_lines(DAY, input, PasswordValidator::from_str)
 }
 
-pub fn part_1(input: &str) -> usize {
-    parse(input).filter(|x| x.valid_by_count()).count()
+pub fn part_1(input: &[PasswordValidator]) -> usize {
+    input.iter().filter(|x| x.valid_by_count()).count()
 }
 
-pub fn part_2(input: &str) -> usize {
-    parse(input).filter(|x| x.valid_by_index()).count()
+pub fn part_2(input: &[PasswordValidator]) -> usize {
+    input.iter().filter(|x| x.valid_by_index()).count()
 }
 
 #[cfg(test)]
@@ -57,8 +62,8 @@ mod tests {
     #[test]
     fn test_parse() {
         assert_eq!(
-            parse(SAMPLE).collect::<Vec<_>>(),
-            vec!(
+            parse(SAMPLE),
+            Ok(vec!(
                 PasswordValidator {
                     number_1: 1,
                     number_2: 3,
@@ -77,17 +82,21 @@ mod tests {
                     character: 'c',
                     password: "ccccccccc".into()
                 }
-            )
+            ))
         );
+
+        let err = parse("1-3 a: abcde\n1-x b: cdefg").unwrap_err();
+        assert_eq!((err.line, err.text.as_str()), (2, "1-x b: cdefg"));
     }
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part_1(SAMPLE), 2);
+        assert_eq!(part_1(&parse(SAMPLE).unwrap()), 2);
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(part_2(SAMPLE), 1);
+        assert_eq!(part_2(&parse(SAMPLE).unwrap()), 1);
+        assert_eq!(part_2(&parse("0-3 b: abcde\n2-9 b: abcde").unwrap()), 1);
     }
 }
@@ -1,24 +1,43 @@
-fn count_trees<'a>(lines: impl Iterator<Item = &'a str>, right: usize, down: usize) -> usize {
-    lines
+use aoc_common::grid::Grid;
+use aoc_common::input::InputError;
+
+const DAY: u8 = 3;
+
+/// Where the trees are, repeating to the right
+pub fn parse(input: &str) -> Result<Grid<bool>, InputError> {
+    let map = Grid::parse(input, |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })
+    .map_err(|err| InputError::grid(DAY, input, err))?;
+
+    if map.width() == 0 {
+        return Err(InputError::at(DAY, input, input, "Expected a map of trees"));
+    }
+
+    Ok(map)
+}
+
+fn count_trees(map: &Grid<bool>, right: usize, down: usize) -> usize {
+    (0..map.height())
         .step_by(down)
         .enumerate()
-        .map(|(index, line)| ((index * right) % line.len(), line))
-        .map(|(index, line)| char::from(line.as_bytes()[index]))
-        .filter(|&square| square == '#')
+        .filter(|(index, y)| map[((index * right) % map.width(), *y)])
         .count()
 }
 
-pub fn part_1(input: &str) -> usize {
-    count_trees(input.lines(), 3, 1)
+pub fn part_1(map: &Grid<bool>) -> usize {
+    count_trees(map, 3, 1)
 }
 
-pub fn part_2(input: &str) -> usize {
+pub fn part_2(map: &Grid<bool>) -> usize {
     [
-        count_trees(input.lines(), 1, 1),
-        count_trees(input.lines(), 3, 1),
-        count_trees(input.lines(), 5, 1),
-        count_trees(input.lines(), 7, 1),
-        count_trees(input.lines(), 1, 2),
+        count_trees(map, 1, 1),
+        count_trees(map, 3, 1),
+        count_trees(map, 5, 1),
+        count_trees(map, 7, 1),
+        count_trees(map, 1, 2),
     ]
     .iter()
     .product()
@@ -40,22 +59,34 @@ mod tests {
 #...##....#
 .#..#...#.#";
 
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("").unwrap_err().message, "Expected a map of trees");
+        assert_eq!(
+            parse("\n\n").unwrap_err().message,
+            "Expected a map of trees"
+        );
+        assert_eq!(parse("..#\n.x.").unwrap_err().column, 2);
+        assert_eq!(parse("..#\n..").unwrap_err().line, 2);
+    }
+
     #[test]
     fn test_count_trees() {
-        assert_eq!(count_trees(SAMPLE.lines(), 1, 1), 2);
-        assert_eq!(count_trees(SAMPLE.lines(), 3, 1), 7);
-        assert_eq!(count_trees(SAMPLE.lines(), 5, 1), 3);
-        assert_eq!(count_trees(SAMPLE.lines(), 7, 1), 4);
-        assert_eq!(count_trees(SAMPLE.lines(), 1, 2), 2);
+        let map = parse(SAMPLE).unwrap();
+        assert_eq!(count_trees(&map, 1, 1), 2);
+        assert_eq!(count_trees(&map, 3, 1), 7);
+        assert_eq!(count_trees(&map, 5, 1), 3);
+        assert_eq!(count_trees(&map, 7, 1), 4);
+        assert_eq!(count_trees(&map, 1, 2), 2);
     }
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part_1(SAMPLE), 7);
+        assert_eq!(part_1(&parse(SAMPLE).unwrap()), 7);
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(part_2(SAMPLE), 336);
+        assert_eq!(part_2(&parse(SAMPLE).unwrap()), 336);
     }
 }
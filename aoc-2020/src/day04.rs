@@ -1,8 +1,14 @@
 use anyhow::{anyhow, bail, Result};
+use aoc_common::input::InputError;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::{convert::TryFrom, str::FromStr};
 
+const DAY: u8 = 4;
+
+/// The keys a passport's fields may have
+const KEYS: [&str; 8] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"];
+
 #[derive(Debug, Hash, PartialEq, Eq)]
 pub struct Credentials {
     birth_year: String,
@@ -185,19 +191,42 @@ impl TryFrom<Credentials> for ValidatedCredentials {
     }
 }
 
-pub fn parse(input: &str) -> impl Iterator<Item = Credentials> + '_ {
+/// Checks that every field of a passport is a `key:value` pair with a known key
+fn fields<'a>(input: &'a str, passport: &'a str) -> Result<&'a str, InputError> {
+    for field in passport.split_whitespace() {
+        match field.split_once(':') {
+            Some((key, value)) if KEYS.contains(&key) && !value.is_empty() => {}
+            Some((key, _)) if !KEYS.contains(&key) => {
+                return Err(InputError::at(DAY, input, field, "Unknown key"))
+            }
+            _ => return Err(InputError::at(DAY, input, field, "Expected key:value")),
+        }
+    }
+
+    Ok(passport)
+}
+
+/// Each passport's fields, which may not include every one needed
+pub fn parse(input: &str) -> Result<Vec<&str>, InputError> {
     input
         .split("\n\n")
-        .map(|s| s.parse())
-        .filter_map(Result::ok)
+        .map(|passport| fields(input, passport))
+        .collect()
+}
+
+/// The passports which have every required field
+fn complete<'a>(passports: &'a [&str]) -> impl Iterator<Item = Credentials> + 'a {
+    passports
+        .iter()
+        .filter_map(|passport| passport.parse().ok())
 }
 
-pub fn part_1(input: &str) -> usize {
-    parse(input).count()
+pub fn part_1(input: &[&str]) -> usize {
+    complete(input).count()
 }
 
-pub fn part_2(input: &str) -> usize {
-    parse(input)
+pub fn part_2(input: &[&str]) -> usize {
+    complete(input)
         .map(ValidatedCredentials::try_from)
         .filter_map(Result::ok)
         //.inspect(|c| { dbg!(c); })
@@ -253,7 +282,7 @@ iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719
     #[test]
     fn test_parse() -> Result<()> {
         assert_eq!(
-            parse(SAMPLE_1).collect::<Vec<_>>(),
+            complete(&parse(SAMPLE_1)?).collect::<Vec<_>>(),
             vec![
                 Credentials {
                     birth_year: "1937".into(),
@@ -282,16 +311,26 @@ iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719
         Ok(())
     }
 
+    #[test]
+    fn test_parse_error() {
+        let err = parse("ecl:gry pid:860033327\n\neyr:2020 hcl #fffffd").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (3, 10, "hcl"));
+        assert_eq!(err.message, "Expected key:value");
+
+        let err = parse("ecl:gry pid:860033327 age:12").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 23, "age:12"));
+    }
+
     #[test]
     fn test_part_1() -> Result<()> {
-        assert_eq!(part_1(SAMPLE_1), 2);
+        assert_eq!(part_1(&parse(SAMPLE_1)?), 2);
         Ok(())
     }
 
     #[test]
     fn test_part_2() -> Result<()> {
-        assert_eq!(part_2(SAMPLE_1), 2);
-        assert_eq!(part_2(SAMPLE_2), 4);
+        assert_eq!(part_2(&parse(SAMPLE_1)?), 2);
+        assert_eq!(part_2(&parse(SAMPLE_2)?), 4);
         Ok(())
     }
 }
@@ -1,7 +1,10 @@
-use anyhow::Result;
-use itertools::Itertools;
+use anyhow::{bail, Result};
+use aoc_common::input::{parse_lines, InputError};
+use std::fmt;
 use std::str::FromStr;
 
+const DAY: u8 = 5;
+
 #[derive(Debug, Hash, PartialEq, Eq)]
 pub struct Seat(u16);
 
@@ -9,20 +12,44 @@ impl FromStr for Seat {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let binary_id = s
-            .chars()
-            .map(|x| match x {
-                'B' | 'R' => 1,
-                _ => 0,
-            })
-            .join("");
-
-        Ok(Seat(u16::from_str_radix(&binary_id, 2)?))
+        if s.len() != 10 {
+            bail!("Expected 10 characters, found {}", s.len());
+        }
+
+        // The row is picked by the first seven, front or back, then the column by the last three
+        let mut id = 0;
+        for (i, c) in s.chars().enumerate() {
+            id = id * 2
+                + match (i < 7, c) {
+                    (true, 'B') | (false, 'R') => 1,
+                    (true, 'F') | (false, 'L') => 0,
+                    _ => bail!("Unexpected {:?} at {}", c, i),
+                };
+        }
+
+        Ok(Seat(id))
+    }
+}
+
+impl fmt::Display for Seat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for bit in (0..10).rev() {
+            let half = (self.0 >> bit) & 1 == 1;
+            let c = match (bit >= 3, half) {
+                (true, false) => 'F',
+                (true, true) => 'B',
+                (false, false) => 'L',
+                (false, true) => 'R',
+            };
+            write!(f, "{}", c)?;
+        }
+
+        Ok(())
     }
 }
 
-pub fn parse(input: &str) -> Result<Vec<Seat>> {
-    input.lines().map(Seat::from_str).collect()
+pub fn parse(input: &str) -> Result<Vec<Seat>, InputError> {
+    parse_lines(DAY, input, Seat::from_str)
 }
 
 pub fn part_1(input: &[Seat]) -> Option<u16> {
@@ -50,6 +77,8 @@ pub fn part_2(input: &[Seat]) -> Option<u16> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::strategy::{garbage, lines, malformed};
+    use proptest::prelude::*;
 
     static SAMPLE: &str = "BFFFBBFRRR\nFFFBBBFRRR\nBBFFBBFRLL";
 
@@ -58,6 +87,10 @@ mod tests {
         assert_eq!("BFFFBBFRRR".parse::<Seat>()?, Seat(567));
         assert_eq!("FFFBBBFRRR".parse::<Seat>()?, Seat(119));
         assert_eq!("BBFFBBFRLL".parse::<Seat>()?, Seat(820));
+        assert!("BBFFBBFRL".parse::<Seat>().is_err());
+        assert!("BBFFXBFRLL".parse::<Seat>().is_err());
+        assert!("BBFFBBRRLL".parse::<Seat>().is_err());
+        assert_eq!(Seat(820).to_string(), "BBFFBBFRLL");
         Ok(())
     }
 
@@ -80,4 +113,28 @@ mod tests {
         assert_eq!(part_2(&parsed), Some(120));
         Ok(())
     }
+
+    fn seat() -> impl Strategy<Value = String> {
+        "[FB]{7}[LR]{3}"
+    }
+
+    proptest! {
+        #[test]
+        fn test_seat_round_trip(id in 0..1024_u16) {
+            prop_assert_eq!(Seat(id).to_string().parse::<Seat>().ok(), Some(Seat(id)));
+        }
+
+        /// Anything accepted is written back the same, so nothing malformed is accepted
+        #[test]
+        fn test_seat_strict(s in prop_oneof![seat(), malformed(seat()), garbage()]) {
+            if let Ok(seat) = s.parse::<Seat>() {
+                prop_assert_eq!(seat.to_string(), s);
+            }
+        }
+
+        #[test]
+        fn test_parse_garbage(input in prop_oneof![malformed(lines(seat(), 1..10)), garbage()]) {
+            let _ = parse(&input);
+        }
+    }
 }
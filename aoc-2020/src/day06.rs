@@ -4,7 +4,10 @@ use std::{
     str::FromStr,
 };
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use aoc_common::input::{parse_each, InputError};
+
+const DAY: u8 = 6;
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct Answer(BTreeSet<char>);
@@ -13,23 +16,16 @@ impl FromStr for Answer {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Answer(s.chars().filter(|&c| c != '\n').collect()))
+        match s.chars().find(|c| !c.is_ascii_lowercase()) {
+            Some(c) => bail!("Unexpected {:?}", c),
+            None => Ok(Answer(s.chars().collect())),
+        }
     }
 }
 
 #[derive(Debug, Hash, PartialEq, Eq)]
 pub struct AnswerSet(Vec<Answer>);
 
-impl FromStr for AnswerSet {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(AnswerSet(
-            s.lines().map(Answer::from_str).collect::<Result<_>>()?,
-        ))
-    }
-}
-
 impl AnswerSet {
     fn any(&self) -> HashSet<&char> {
         self.0.iter().flat_map(|answer| answer.0.iter()).collect()
@@ -44,8 +40,11 @@ impl AnswerSet {
     }
 }
 
-pub fn parse(input: &str) -> Result<Vec<AnswerSet>> {
-    input.split("\n\n").map(AnswerSet::from_str).collect()
+pub fn parse(input: &str) -> Result<Vec<AnswerSet>, InputError> {
+    input
+        .split("\n\n")
+        .map(|group| parse_each(DAY, input, group.lines(), Answer::from_str).map(AnswerSet))
+        .collect()
 }
 
 pub fn part_1(input: &[AnswerSet]) -> usize {
@@ -112,11 +111,11 @@ b";
         assert_eq!(
             parsed.iter().map(|set| set.any()).collect::<Vec<_>>(),
             vec![
-                vec!['a', 'b', 'c'].iter().collect(),
-                vec!['a', 'b', 'c'].iter().collect(),
-                vec!['a', 'b', 'c'].iter().collect(),
-                vec!['a'].iter().collect(),
-                vec!['b'].iter().collect()
+                ['a', 'b', 'c'].iter().collect(),
+                ['a', 'b', 'c'].iter().collect(),
+                ['a', 'b', 'c'].iter().collect(),
+                ['a'].iter().collect(),
+                ['b'].iter().collect()
             ]
         );
         Ok(())
@@ -128,11 +127,11 @@ b";
         assert_eq!(
             parsed.iter().map(|set| set.all()).collect::<Vec<_>>(),
             vec![
-                Some(vec!['a', 'b', 'c'].iter().collect()),
-                Some(vec![].iter().collect()),
-                Some(vec!['a'].iter().collect()),
-                Some(vec!['a'].iter().collect()),
-                Some(vec!['b'].iter().collect())
+                Some(['a', 'b', 'c'].iter().collect()),
+                Some([].iter().collect()),
+                Some(['a'].iter().collect()),
+                Some(['a'].iter().collect()),
+                Some(['b'].iter().collect())
             ]
         );
         Ok(())
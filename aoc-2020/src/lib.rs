@@ -0,0 +1,20 @@
+mod day01;
+// `recap` derives its `FromStr` impl inside an anonymous const
+#[allow(non_local_definitions)]
+mod day02;
+mod day03;
+mod day04;
+mod day05;
+mod day06;
+//mod day07;
+
+aoc_common::solutions! {
+  year 2020;
+  day01 :parse?     => part_1?, part_2?;
+  day02 :parse?     => part_1, part_2;
+  day03 :parse?     => part_1, part_2;
+  day04 :parse?     => part_1, part_2;
+  day05 :parse?     => part_1?, part_2?;
+  day06 :parse?     => part_1, part_2;
+  //day07             => part1, part2;
+}
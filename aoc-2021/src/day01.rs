@@ -1,20 +1,20 @@
 use std::iter;
-use std::num::ParseIntError;
 
+use aoc_common::input::{parse_lines, InputError};
 use itertools::izip;
 
-fn generator(input: &str) -> Result<Vec<u32>, ParseIntError> {
-    input.lines().map(str::parse).collect()
-}
+const DAY: u8 = 1;
 
-pub fn part1(input: &str) -> usize {
-    let input = generator(input).unwrap();
+pub fn generator(input: &str) -> Result<Vec<u32>, InputError> {
+    parse_lines(DAY, input, str::parse)
+}
 
-    let offset = iter::once(None).chain(input.clone().into_iter().map(Some));
+pub fn part1(input: &[u32]) -> usize {
+    let offset = iter::once(None).chain(input.iter().copied().map(Some));
 
     // Creates an iterator of (Option<u8>, u8),
     // representing the previous value, and the current value.
-    let cmp = offset.zip(input);
+    let cmp = offset.zip(input.iter().copied());
 
     // Filter by those that have increased
     let increases = cmp.filter(|(a, b)| if let Some(a) = a { b > a } else { false });
@@ -22,17 +22,15 @@ pub fn part1(input: &str) -> usize {
     increases.count()
 }
 
-pub fn part2(input: &str) -> usize {
-    let input = generator(input).unwrap();
-
-    let offset_1 = iter::once(None).chain(input.clone().into_iter().map(Some));
+pub fn part2(input: &[u32]) -> usize {
+    let offset_1 = iter::once(None).chain(input.iter().copied().map(Some));
     let offset_2 = iter::once(None)
         .chain(iter::once(None))
-        .chain(input.clone().into_iter().map(Some));
+        .chain(input.iter().copied().map(Some));
 
-    let windows = izip!(offset_2, offset_1, input).filter_map(|(a, b, c)| {
+    let windows = izip!(offset_2, offset_1, input.iter().copied()).filter_map(|(a, b, c)| {
         if let Some(a) = a {
-            b.map(|b| a + b + c)
+            b.map(|b| u64::from(a) + u64::from(b) + u64::from(c))
         } else {
             None
         }
@@ -62,11 +60,12 @@ mod tests {
 
     #[test]
     fn part1() {
-        assert_eq!(super::part1(SAMPLE), 7);
+        assert_eq!(super::part1(&super::generator(SAMPLE).unwrap()), 7);
     }
 
     #[test]
     fn part2() {
-        assert_eq!(super::part2(SAMPLE), 5);
+        assert_eq!(super::part2(&super::generator(SAMPLE).unwrap()), 5);
+        assert_eq!(super::part2(&[u32::MAX, u32::MAX, u32::MAX, 1]), 0);
     }
 }
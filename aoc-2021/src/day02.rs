@@ -1,18 +1,22 @@
+use std::fmt;
 use std::str::FromStr;
 
 use anyhow::{anyhow, Result};
-use strum::EnumString;
+use aoc_common::input::{parse_lines, InputError};
+use strum::{Display, EnumString};
 
-#[derive(Debug, PartialEq, EnumString)]
+const DAY: u8 = 2;
+
+#[derive(Clone, Debug, PartialEq, Display, EnumString)]
 #[strum(serialize_all = "lowercase")]
-enum Direction {
+pub enum Direction {
     Forward,
     Down,
     Up,
 }
 
 #[derive(Debug, PartialEq)]
-struct Vector(Direction, u32);
+pub struct Vector(Direction, u32);
 
 impl FromStr for Vector {
     type Err = anyhow::Error;
@@ -20,7 +24,8 @@ impl FromStr for Vector {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut split = s.split_whitespace();
 
-        if let (Some(direction), Some(magnitude)) = (split.next(), split.next()) {
+        if let (Some(direction), Some(magnitude), None) = (split.next(), split.next(), split.next())
+        {
             Ok(Vector(direction.parse()?, magnitude.parse()?))
         } else {
             Err(anyhow!("Unable to parse '{}' as a vector", s))
@@ -28,8 +33,14 @@ impl FromStr for Vector {
     }
 }
 
-fn generator(input: &str) -> Result<Vec<Vector>> {
-    input.lines().map(str::parse).collect()
+impl fmt::Display for Vector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.0, self.1)
+    }
+}
+
+pub fn generator(input: &str) -> Result<Vec<Vector>, InputError> {
+    parse_lines(DAY, input, str::parse)
 }
 
 struct Location {
@@ -37,9 +48,7 @@ struct Location {
     depth: i64,
 }
 
-pub fn part1(input: &str) -> i64 {
-    let input = &generator(input).unwrap();
-
+pub fn part1(input: &[Vector]) -> i64 {
     let mut location = Location {
         horizontal: 0,
         depth: 0,
@@ -62,9 +71,7 @@ struct OrientedLocation {
     aim: i64,
 }
 
-pub fn part2(input: &str) -> i64 {
-    let input = &generator(input).unwrap();
-
+pub fn part2(input: &[Vector]) -> i64 {
     let mut location = OrientedLocation {
         horizontal: 0,
         depth: 0,
@@ -87,6 +94,10 @@ pub fn part2(input: &str) -> i64 {
 
 #[cfg(test)]
 mod tests {
+    use super::{Direction, Vector};
+    use aoc_common::strategy::{garbage, lines, malformed};
+    use proptest::prelude::*;
+
     const SAMPLE: &str = "forward 5
 down 5
 forward 8
@@ -96,11 +107,41 @@ forward 2";
 
     #[test]
     fn part1() {
-        assert_eq!(super::part1(SAMPLE), 150);
+        assert_eq!(super::part1(&super::generator(SAMPLE).unwrap()), 150);
     }
 
     #[test]
     fn part2() {
-        assert_eq!(super::part2(SAMPLE), 900);
+        assert_eq!(super::part2(&super::generator(SAMPLE).unwrap()), 900);
+    }
+
+    #[test]
+    fn generator() {
+        assert!(super::generator("forward 5 5").is_err());
+        assert!(super::generator("sideways 5").is_err());
+    }
+
+    fn vector() -> impl Strategy<Value = Vector> {
+        let direction = prop_oneof![
+            Just(Direction::Forward),
+            Just(Direction::Down),
+            Just(Direction::Up)
+        ];
+
+        (direction, any::<u32>()).prop_map(|(direction, magnitude)| Vector(direction, magnitude))
+    }
+
+    proptest! {
+        #[test]
+        fn vector_round_trip(vector in vector()) {
+            prop_assert_eq!(vector.to_string().parse::<Vector>().ok(), Some(vector));
+        }
+
+        #[test]
+        fn generator_garbage(
+            input in prop_oneof![malformed(lines(vector().prop_map(|v| v.to_string()), 1..10)), garbage()]
+        ) {
+            let _ = super::generator(&input);
+        }
     }
 }
@@ -1,4 +1,27 @@
 use anyhow::Result;
+use aoc_common::input::{parse_lines, InputError};
+
+const DAY: u8 = 3;
+
+/// Readings are kept as text, all the same width and at most 32 bits
+pub fn generator(input: &str) -> Result<Vec<String>, InputError> {
+    let width = input.lines().next().map_or(0, str::len);
+    if width == 0 {
+        return Err(InputError::at(DAY, input, input, "Expected a reading"));
+    }
+
+    parse_lines(DAY, input, |line| {
+        if !line.chars().all(|c| c == '0' || c == '1') {
+            Err(String::from("Expected a reading of 0s and 1s"))
+        } else if line.len() != width {
+            Err(format!("Expected {} bits, found {}", width, line.len()))
+        } else if width > 32 {
+            Err(String::from("Expected at most 32 bits"))
+        } else {
+            Ok(line.to_owned())
+        }
+    })
+}
 
 fn reading_column(readings: &[String], index: usize) -> Vec<char> {
     readings
@@ -52,13 +75,11 @@ fn epsilon(readings: &[String]) -> Result<u32> {
     u32::from_str_radix(&epsilon, 2).map_err(Into::into)
 }
 
-pub fn part1(input: &str) -> u64 {
-    let input_str: Vec<String> = input.lines().map(ToOwned::to_owned).collect();
-
-    let gamma = gamma(&input_str).unwrap();
-    let epsilon = epsilon(&input_str).unwrap();
+pub fn part1(readings: &[String]) -> Result<u64> {
+    let gamma = gamma(readings)?;
+    let epsilon = epsilon(readings)?;
 
-    gamma as u64 * epsilon as u64
+    Ok(gamma as u64 * epsilon as u64)
 }
 
 fn filter_readings(readings: Vec<String>, index: usize, filter_bit: char) -> Vec<String> {
@@ -71,7 +92,8 @@ fn filter_readings(readings: Vec<String>, index: usize, filter_bit: char) -> Vec
 fn oxygen_generator(readings: Vec<String>) -> Result<u32> {
     let mut candidates = readings;
 
-    for i in 0.. {
+    // Stops early if there are duplicates of the last reading standing
+    for i in 0..candidates[0].len() {
         let mcb = most_common_bit(&candidates, i);
 
         candidates = filter_readings(candidates, i, mcb);
@@ -87,7 +109,12 @@ fn oxygen_generator(readings: Vec<String>) -> Result<u32> {
 fn co2_scrubber(readings: Vec<String>) -> Result<u32> {
     let mut candidates = readings;
 
-    for i in 0.. {
+    for i in 0..candidates[0].len() {
+        // When every reading left has the same bit there is no less common one, so all are kept
+        if let (0, _) | (_, 0) = bit_counts(&candidates, i) {
+            continue;
+        }
+
         let lcb = least_common_bit(&candidates, i);
 
         candidates = filter_readings(candidates, i, lcb);
@@ -100,13 +127,11 @@ fn co2_scrubber(readings: Vec<String>) -> Result<u32> {
     u32::from_str_radix(&candidates[0], 2).map_err(Into::into)
 }
 
-pub fn part2(input: &str) -> u64 {
-    let input_chars: Vec<String> = input.lines().map(ToOwned::to_owned).collect();
-
-    let oxygen_generator = oxygen_generator(input_chars.clone()).unwrap();
-    let co2_scrubber = co2_scrubber(input_chars).unwrap();
+pub fn part2(readings: &[String]) -> Result<u64> {
+    let oxygen_generator = oxygen_generator(readings.to_vec())?;
+    let co2_scrubber = co2_scrubber(readings.to_vec())?;
 
-    oxygen_generator as u64 * co2_scrubber as u64
+    Ok(oxygen_generator as u64 * co2_scrubber as u64)
 }
 
 #[cfg(test)]
@@ -124,13 +149,45 @@ mod tests {
 00010
 01010";
 
+    #[test]
+    fn generator() {
+        assert_eq!(super::generator(SAMPLE).unwrap().len(), 12);
+        assert_eq!(super::generator("01\n01").unwrap(), vec!["01", "01"]);
+
+        let error = |input| {
+            let err = super::generator(input).unwrap_err();
+            (err.line, err.message)
+        };
+        assert_eq!(error(""), (1, String::from("Expected a reading")));
+        assert_eq!(
+            error("0101\n01"),
+            (2, String::from("Expected 4 bits, found 2"))
+        );
+        assert_eq!(
+            error("0101\n0121"),
+            (2, String::from("Expected a reading of 0s and 1s"))
+        );
+        assert_eq!(
+            error(&"1".repeat(33)),
+            (1, String::from("Expected at most 32 bits"))
+        );
+    }
+
     #[test]
     fn part1() {
-        assert_eq!(super::part1(SAMPLE), 198);
+        let readings = super::generator(SAMPLE).unwrap();
+        assert_eq!(super::part1(&readings).unwrap(), 198);
     }
 
     #[test]
     fn part2() {
-        assert_eq!(super::part2(SAMPLE), 230);
+        let readings = super::generator(SAMPLE).unwrap();
+        assert_eq!(super::part2(&readings).unwrap(), 230);
+
+        // Duplicates are never told apart
+        let readings = super::generator("01\n01\n10").unwrap();
+        assert_eq!(super::part2(&readings).unwrap(), 2);
+        let readings = super::generator("01\n01").unwrap();
+        assert_eq!(super::part2(&readings).unwrap(), 1);
     }
 }
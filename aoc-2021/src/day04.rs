@@ -1,7 +1,10 @@
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
+use aoc_common::input::{parse_each, InputError};
+
+const DAY: u8 = 4;
 
 #[derive(Debug, Clone)]
-struct Board(Vec<Vec<u32>>);
+pub struct Board(Vec<Vec<u32>>);
 
 #[derive(Debug, Clone)]
 struct MarkedBoard(Vec<Vec<(u32, bool)>>);
@@ -33,19 +36,18 @@ impl MarkedBoard {
         rows || columns
     }
 
-    fn score(&self, called: u32) -> u32 {
-        let unmarked: u32 = self
+    fn score(&self, called: u32) -> Result<u64> {
+        let unmarked: u64 = self
             .0
             .iter()
-            .map(|line| {
-                line.iter()
-                    .filter(|(_, mark)| !mark)
-                    .map(|(number, _)| number)
-                    .sum::<u32>()
-            })
+            .flatten()
+            .filter(|(_, mark)| !mark)
+            .map(|(number, _)| u64::from(*number))
             .sum();
 
-        unmarked * called
+        unmarked
+            .checked_mul(called.into())
+            .ok_or_else(|| anyhow!("The score overflows a 64-bit number"))
     }
 }
 
@@ -61,40 +63,49 @@ impl From<Board> for MarkedBoard {
     }
 }
 
-fn generator(input: &str) -> (Vec<u32>, Vec<Board>) {
+pub fn generator(input: &str) -> Result<(Vec<u32>, Vec<Board>), InputError> {
     let mut blocks = input.split("\n\n");
 
-    let numbers = blocks
-        .next()
-        .unwrap()
-        .split(',')
-        .map(str::parse)
-        .collect::<Result<_, _>>()
-        .unwrap();
+    let numbers = parse_each(
+        DAY,
+        input,
+        blocks.next().unwrap_or_default().split(','),
+        str::parse,
+    )?;
 
+    // Boards are square, so that there are as many columns to check for a win as rows
     let boards = blocks
         .map(|block| {
-            Board(
-                block
-                    .lines()
-                    .map(|line| {
-                        line.split_whitespace()
-                            .map(str::parse)
-                            .collect::<Result<_, _>>()
-                            .unwrap()
-                    })
-                    .collect(),
-            )
+            let size = block.lines().count();
+            if size == 0 {
+                return Err(InputError::at(DAY, input, block, "Expected a board"));
+            }
+
+            block
+                .lines()
+                .map(|line| {
+                    let row: Vec<u32> =
+                        parse_each(DAY, input, line.split_whitespace(), str::parse)?;
+                    if row.len() != size {
+                        return Err(InputError::at(
+                            DAY,
+                            input,
+                            line,
+                            format!("Expected {} numbers, as the board has {} rows", size, size),
+                        ));
+                    }
+
+                    Ok(row)
+                })
+                .collect::<Result<_, _>>()
+                .map(Board)
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
 
-    (numbers, boards)
+    Ok((numbers, boards))
 }
 
-pub fn part1(input: &str) -> u32 {
-    let input = generator(input);
-
-    let (numbers, boards) = &input;
+pub fn part1((numbers, boards): &(Vec<u32>, Vec<Board>)) -> Result<u64> {
     let mut marked_boards: Vec<MarkedBoard> = boards
         .iter()
         .map(ToOwned::to_owned)
@@ -112,13 +123,10 @@ pub fn part1(input: &str) -> u32 {
         }
     }
 
-    unreachable!()
+    bail!("No board wins")
 }
 
-pub fn part2(input: &str) -> u32 {
-    let input = generator(input);
-
-    let (numbers, boards) = &input;
+pub fn part2((numbers, boards): &(Vec<u32>, Vec<Board>)) -> Result<u64> {
     let mut marked_boards: Vec<MarkedBoard> = boards
         .iter()
         .map(ToOwned::to_owned)
@@ -142,7 +150,7 @@ pub fn part2(input: &str) -> u32 {
         }
     }
 
-    unreachable!()
+    bail!("Not every board wins")
 }
 
 #[cfg(test)]
@@ -169,11 +177,33 @@ mod tests {
 
     #[test]
     fn part1() {
-        assert_eq!(super::part1(SAMPLE), 4512);
+        assert_eq!(
+            super::part1(&super::generator(SAMPLE).unwrap()).unwrap(),
+            4512
+        );
+
+        let err = super::part1(&super::generator("1,2\n\n3").unwrap()).unwrap_err();
+        assert_eq!(err.to_string(), "No board wins");
     }
 
     #[test]
     fn part2() {
-        assert_eq!(super::part2(SAMPLE), 1924);
+        assert_eq!(
+            super::part2(&super::generator(SAMPLE).unwrap()).unwrap(),
+            1924
+        );
+
+        let err = super::part2(&super::generator("1,2\n\n1\n\n3").unwrap()).unwrap_err();
+        assert_eq!(err.to_string(), "Not every board wins");
+    }
+
+    #[test]
+    fn generator() {
+        let err = super::generator("1,2\n\n1 2\n3").unwrap_err();
+        assert_eq!((err.line, err.column), (4, 1));
+        assert_eq!(err.message, "Expected 2 numbers, as the board has 2 rows");
+
+        let err = super::generator("1,2\n\n1\n\n").unwrap_err();
+        assert_eq!((err.line, err.message.as_str()), (5, "Expected a board"));
     }
 }
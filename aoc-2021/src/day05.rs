@@ -1,5 +1,5 @@
-use std::cmp::Ordering;
-
+use aoc_common::geometry::Point;
+use aoc_common::input::{parse_all, InputError};
 use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
@@ -8,25 +8,14 @@ use nom::{
     sequence::separated_pair,
     IResult,
 };
-use num::rational::Ratio;
+use num::integer::gcd;
 
-#[derive(Debug, Clone, Eq, PartialEq)]
-struct Coordinate {
-    x: u32,
-    y: u32,
-}
+const DAY: u8 = 5;
 
-impl Ord for Coordinate {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.x.cmp(&other.x).then(self.y.cmp(&other.y))
-    }
-}
+type Coordinate = Point<i64>;
 
-impl PartialOrd for Coordinate {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
+/// The most points the vent lines cover in all, counting overlaps
+const MAX_POINTS: i64 = 1 << 20;
 
 fn coordinate(input: &str) -> IResult<&str, Coordinate> {
     let (input, (x, y)) = separated_pair(
@@ -37,7 +26,8 @@ fn coordinate(input: &str) -> IResult<&str, Coordinate> {
     Ok((input, Coordinate { x, y }))
 }
 
-struct VentLine(Coordinate, Coordinate);
+#[derive(Debug)]
+pub struct VentLine(Coordinate, Coordinate);
 
 impl VentLine {
     fn is_horizontal(&self) -> bool {
@@ -48,54 +38,21 @@ impl VentLine {
         self.0.x == self.1.x
     }
 
-    fn delta(&self) -> (i64, i64) {
-        (
-            self.1.x as i64 - self.0.x as i64,
-            self.1.y as i64 - self.0.y as i64,
-        )
+    /// How many steps there are between the points on the line
+    fn steps(&self) -> i64 {
+        let delta = self.1 - self.0;
+        gcd(delta.x, delta.y)
     }
 
-    fn step(&self) -> (i64, i64) {
-        let (delta_x, delta_y) = self.delta();
+    fn points(&self) -> Vec<Coordinate> {
+        let steps = self.steps();
 
-        if delta_y == 0 {
-            match delta_x.cmp(&0) {
-                Ordering::Greater => (1, 0),
-                Ordering::Equal => (0, 0),
-                Ordering::Less => (-1, 0),
-            }
-        } else {
-            let ratio = Ratio::new(delta_x, delta_y);
-            let (reduced_x, reduced_y) = (*ratio.numer(), *ratio.denom());
-            let (x_step, y_step) = (reduced_x.abs(), reduced_y.abs());
-
-            match (delta_x >= 0, delta_y >= 0) {
-                (true, true) => (x_step, y_step),
-                (true, false) => (x_step, -y_step),
-                (false, true) => (-x_step, y_step),
-                (false, false) => (-x_step, -y_step),
-            }
+        if steps == 0 {
+            return vec![self.0];
         }
-    }
 
-    fn points(&self) -> Vec<Coordinate> {
-        let (step_x, step_y) = self.step();
-        let (delta_x, delta_y) = self.delta();
-
-        let steps = if step_x != 0 {
-            (delta_x / step_x).abs()
-        } else if step_y != 0 {
-            (delta_y / step_y).abs()
-        } else {
-            0
-        };
-
-        (0..=steps)
-            .map(|step| Coordinate {
-                x: (self.0.x as i64 + (step * step_x)) as u32,
-                y: (self.0.y as i64 + (step * step_y)) as u32,
-            })
-            .collect()
+        let step = (self.1 - self.0) / steps;
+        (0..=steps).map(|k| self.0 + step * k).collect()
     }
 }
 
@@ -104,38 +61,49 @@ fn vent_line(input: &str) -> IResult<&str, VentLine> {
     Ok((input, VentLine(from, to)))
 }
 
-fn generator(input: &str) -> Vec<VentLine> {
-    input.lines().map(|l| vent_line(l).unwrap().1).collect()
-}
-
-pub fn part1(input: &str) -> usize {
-    let input = generator(input);
-    let vent_lines: Vec<&VentLine> = input
-        .iter()
-        .filter(|line| line.is_horizontal() || line.is_vertical())
-        .collect();
+pub fn generator(input: &str) -> Result<Vec<VentLine>, InputError> {
+    let mut points = 0i64;
+
+    input
+        .lines()
+        .map(|line| {
+            let vent_line = parse_all(DAY, input, line, vent_line)?;
+
+            points = points.saturating_add(vent_line.steps()).saturating_add(1);
+            if points > MAX_POINTS {
+                return Err(InputError::at(
+                    DAY,
+                    input,
+                    line,
+                    format!("Expected at most {} points in all", MAX_POINTS),
+                ));
+            }
 
-    let vents: Vec<_> = vent_lines.iter().flat_map(|line| line.points()).collect();
+            Ok(vent_line)
+        })
+        .collect()
+}
 
-    vents
-        .iter()
-        .filter(|vent| vents.iter().filter(|all| all == vent).count() >= 2)
-        .sorted()
-        .dedup()
+/// How many points at least two of the vent lines cover
+fn overlaps<'a>(vent_lines: impl Iterator<Item = &'a VentLine>) -> usize {
+    vent_lines
+        .flat_map(|line| line.points())
+        .counts()
+        .values()
+        .filter(|count| **count >= 2)
         .count()
 }
 
-pub fn part2(input: &str) -> usize {
-    let input = generator(input);
-
-    let vents: Vec<_> = input.iter().flat_map(|line| line.points()).collect();
+pub fn part1(input: &[VentLine]) -> usize {
+    overlaps(
+        input
+            .iter()
+            .filter(|line| line.is_horizontal() || line.is_vertical()),
+    )
+}
 
-    vents
-        .iter()
-        .filter(|vent| vents.iter().filter(|all| all == vent).count() >= 2)
-        .sorted()
-        .dedup()
-        .count()
+pub fn part2(input: &[VentLine]) -> usize {
+    overlaps(input.iter())
 }
 
 #[cfg(test)]
@@ -153,11 +121,22 @@ mod tests {
 
     #[test]
     fn part1() {
-        assert_eq!(super::part1(SAMPLE), 5);
+        assert_eq!(super::part1(&super::generator(SAMPLE).unwrap()), 5);
     }
 
     #[test]
     fn part2() {
-        assert_eq!(super::part2(SAMPLE), 12);
+        assert_eq!(super::part2(&super::generator(SAMPLE).unwrap()), 12);
+    }
+
+    #[test]
+    fn generator() {
+        assert_eq!(super::generator(SAMPLE).unwrap().len(), 10);
+
+        let err = super::generator("0,0 -> 0,1048575\n0,0 -> 0,0").unwrap_err();
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (2, "Expected at most 1048576 points in all")
+        );
     }
 }
@@ -1,14 +1,22 @@
-use anyhow::Result;
-
-fn generator(input: &str) -> [u64; 9] {
-    input
-        .split(',')
-        .map(str::parse)
-        .map(Result::unwrap)
-        .fold([0; 9], |mut acc, fish: usize| {
-            acc[fish] += 1;
-            acc
-        })
+use aoc_common::input::{parse_each, InputError};
+
+const DAY: u8 = 6;
+
+fn timer(s: &str) -> Result<usize, String> {
+    match s.parse() {
+        Ok(timer) if timer < 9 => Ok(timer),
+        Ok(timer) => Err(format!("Timers only go up to 8, found {}", timer)),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+pub fn generator(input: &str) -> Result<[u64; 9], InputError> {
+    let timers: Vec<usize> = parse_each(DAY, input, input.split(','), timer)?;
+
+    Ok(timers.into_iter().fold([0; 9], |mut acc, fish| {
+        acc[fish] += 1;
+        acc
+    }))
 }
 
 fn lanternfish(initial: Vec<u64>, day: u32) -> u64 {
@@ -22,13 +30,11 @@ fn lanternfish(initial: Vec<u64>, day: u32) -> u64 {
     school.into_iter().sum()
 }
 
-pub fn part1(input: &str) -> u64 {
-    let input = generator(input);
+pub fn part1(input: &[u64; 9]) -> u64 {
     lanternfish(Vec::from_iter(input.to_owned()), 80)
 }
 
-pub fn part2(input: &str) -> u64 {
-    let input = generator(input);
+pub fn part2(input: &[u64; 9]) -> u64 {
     lanternfish(Vec::from_iter(input.to_owned()), 256)
 }
 
@@ -38,11 +44,21 @@ mod tests {
 
     #[test]
     fn part1() {
-        assert_eq!(super::part1(SAMPLE), 5934);
+        assert_eq!(super::part1(&super::generator(SAMPLE).unwrap()), 5934);
     }
 
     #[test]
     fn part2() {
-        assert_eq!(super::part2(SAMPLE), 26984457539);
+        assert_eq!(
+            super::part2(&super::generator(SAMPLE).unwrap()),
+            26984457539
+        );
+    }
+
+    #[test]
+    fn generator() {
+        let err = super::generator("3,4,9").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 5, "9"));
+        assert_eq!(err.message, "Timers only go up to 8, found 9");
     }
 }
@@ -1,57 +1,81 @@
-use anyhow::Result;
-
-fn generator(input: &str) -> Result<Vec<u32>> {
-    input
-        .split(',')
-        .map(str::parse)
-        .collect::<Result<_, _>>()
-        .map_err(Into::into)
-}
+use aoc_common::input::{parse_each, InputError};
 
-pub fn part1(input: &str) -> u32 {
-    let input = generator(input).unwrap();
+const DAY: u8 = 7;
 
-    let (min, max) = (*input.iter().min().unwrap(), *input.iter().max().unwrap());
+pub fn generator(input: &str) -> Result<Vec<u32>, InputError> {
+    parse_each(DAY, input, input.split(','), str::parse)
+}
 
-    (min..=max)
-        .map(|align| {
-            input
-                .iter()
-                .map(|crab| (*crab as i64 - align as i64).unsigned_abs() as u32)
-                .sum()
+/// The least fuel needed to line every crab up at one of `aligns`, where `cost` gives the fuel to
+/// move a distance
+///
+/// Alignments whose fuel overflows are skipped, as they can't be the cheapest unless every one is.
+fn least_fuel(
+    input: &[u32],
+    aligns: impl Iterator<Item = u32>,
+    cost: impl Fn(u64) -> Option<u64>,
+) -> Result<u64, &'static str> {
+    aligns
+        .filter_map(|align| {
+            input.iter().try_fold(0u64, |fuel, crab| {
+                fuel.checked_add(cost(u64::from(crab.abs_diff(align)))?)
+            })
         })
         .min()
-        .unwrap()
+        .ok_or("The fuel overflows a 64-bit number")
 }
 
-pub fn part2(input: &str) -> u32 {
-    let input = generator(input).unwrap();
+pub fn part1(input: &[u32]) -> Result<u64, &'static str> {
+    // The median has as many crabs on either side, so moving away from it can't save fuel
+    let mut crabs = input.to_vec();
+    let (_, median, _) = crabs.select_nth_unstable(input.len() / 2);
 
-    let (min, max) = (*input.iter().min().unwrap(), *input.iter().max().unwrap());
+    least_fuel(input, std::iter::once(*median), Some)
+}
 
-    (min..=max)
-        .map(|align| {
-            input
-                .iter()
-                .map(|crab| (*crab as i64 - align as i64).unsigned_abs() as u32)
-                .map(|distance| (distance * (distance + 1)) / 2)
-                .sum()
-        })
-        .min()
-        .unwrap()
+pub fn part2(input: &[u32]) -> Result<u64, &'static str> {
+    // The fuel is least within half a step of the mean, so only the alignments around it can win
+    let sum: u64 = input.iter().copied().map(u64::from).sum();
+    let mean = (sum / input.len() as u64) as u32;
+
+    least_fuel(
+        input,
+        mean.saturating_sub(1)..=mean.saturating_add(2),
+        |distance| Some(distance.checked_mul(distance + 1)? / 2),
+    )
 }
 
 #[cfg(test)]
 mod tests {
+    use super::least_fuel;
+    use proptest::prelude::*;
+
     const SAMPLE: &str = "16,1,2,0,4,2,7,1,2,14";
 
     #[test]
     fn part1() {
-        assert_eq!(super::part1(SAMPLE), 37);
+        assert_eq!(super::part1(&super::generator(SAMPLE).unwrap()), Ok(37));
     }
 
     #[test]
     fn part2() {
-        assert_eq!(super::part2(SAMPLE), 168);
+        assert_eq!(super::part2(&super::generator(SAMPLE).unwrap()), Ok(168));
+        assert_eq!(super::part2(&[0, 4294967295]), Ok(4611686018427387904));
+        assert_eq!(
+            super::part2(&[0, 4294967295, 0, 4294967295, 0, 4294967295, 0, 4294967295]),
+            Err("The fuel overflows a 64-bit number")
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn alignments_match_every_position(input in prop::collection::vec(0u32..100, 1..20)) {
+            let every = || 0..100;
+            prop_assert_eq!(super::part1(&input), least_fuel(&input, every(), Some));
+            prop_assert_eq!(
+                super::part2(&input),
+                least_fuel(&input, every(), |distance| Some(distance * (distance + 1) / 2))
+            );
+        }
     }
 }
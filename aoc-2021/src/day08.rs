@@ -5,9 +5,12 @@ use std::{
 };
 
 use anyhow::{anyhow, Result};
+use aoc_common::input::{parse_each, InputError};
 use bimap::BiHashMap;
 use strum::{AsRefStr, EnumString};
 
+const DAY: u8 = 8;
+
 #[derive(Clone, Debug, PartialEq, EnumString, AsRefStr, Eq, Hash, PartialOrd, Ord)]
 #[strum(serialize_all = "lowercase")]
 enum Signal {
@@ -57,140 +60,164 @@ impl SignalDigit {
 }
 
 #[derive(Clone, Debug)]
-struct DisplayReading {
+pub struct DisplayReading {
     patterns: HashSet<SignalDigit>,
     outputs: Vec<SignalDigit>,
     digit_map: BiHashMap<SignalDigit, u8>,
 }
 
 impl DisplayReading {
-    fn add_three(&mut self) {
-        let one = self.digit_map.get_by_right(&1).unwrap();
-        let three = self
-            .patterns
+    /// The pattern already worked out for a digit
+    fn known(&self, digit: u8) -> Result<SignalDigit> {
+        self.digit_map
+            .get_by_right(&digit)
+            .cloned()
+            .ok_or_else(|| anyhow!("No pattern was found for {}", digit))
+    }
+
+    /// The patterns with as many segments as `digit` has
+    fn candidates(&self, digit: u8) -> impl Iterator<Item = &SignalDigit> {
+        self.patterns
             .iter()
-            .filter(|x| x.as_u8s().unwrap().contains(&3))
+            .filter(move |x| x.as_u8s().is_ok_and(|digits| digits.contains(&digit)))
+    }
+
+    /// Records the pattern found for a digit
+    fn add(&mut self, digit: u8, pattern: Option<&SignalDigit>) -> Result<()> {
+        let pattern = pattern
+            .cloned()
+            .ok_or_else(|| anyhow!("No pattern matches {}", digit))?;
+        self.digit_map.insert(pattern, digit);
+        Ok(())
+    }
+
+    fn add_three(&mut self) -> Result<()> {
+        let one = self.known(1)?;
+        let three = self
+            .candidates(3)
             .find(|x| x.0.is_superset(&one.0))
-            .unwrap();
-        self.digit_map.insert(three.to_owned(), 3);
+            .cloned();
+        self.add(3, three.as_ref())
     }
 
-    fn add_nine(&mut self) {
-        let four = self.digit_map.get_by_right(&4).unwrap();
+    fn add_nine(&mut self) -> Result<()> {
+        let four = self.known(4)?;
         let nine = self
-            .patterns
-            .iter()
-            .filter(|x| x.as_u8s().unwrap().contains(&9))
+            .candidates(9)
             .find(|x| x.0.is_superset(&four.0))
-            .unwrap();
-        self.digit_map.insert(nine.to_owned(), 9);
+            .cloned();
+        self.add(9, nine.as_ref())
     }
 
-    fn add_zero(&mut self) {
-        let seven = self.digit_map.get_by_right(&7).unwrap();
-        let nine = self.digit_map.get_by_right(&9).unwrap();
+    fn add_zero(&mut self) -> Result<()> {
+        let seven = self.known(7)?;
+        let nine = self.known(9)?;
 
         let zero = self
-            .patterns
-            .iter()
-            .filter(|x| x.as_u8s().unwrap().contains(&0))
-            .filter(|x| *x != nine)
+            .candidates(0)
+            .filter(|x| **x != nine)
             .find(|x| x.0.is_superset(&seven.0))
-            .unwrap();
-        self.digit_map.insert(zero.to_owned(), 0);
+            .cloned();
+        self.add(0, zero.as_ref())
     }
 
-    fn add_six(&mut self) {
-        let zero = self.digit_map.get_by_right(&0).unwrap();
-        let nine = self.digit_map.get_by_right(&9).unwrap();
+    fn add_six(&mut self) -> Result<()> {
+        let zero = self.known(0)?;
+        let nine = self.known(9)?;
 
         let six = self
-            .patterns
-            .iter()
-            .filter(|x| x.as_u8s().unwrap().contains(&6))
-            .filter(|x| *x != zero)
-            .find(|x| *x != nine)
-            .unwrap();
-
-        self.digit_map.insert(six.to_owned(), 6);
+            .candidates(6)
+            .filter(|x| **x != zero)
+            .find(|x| **x != nine)
+            .cloned();
+        self.add(6, six.as_ref())
     }
 
-    fn add_five(&mut self) {
-        let six = self.digit_map.get_by_right(&6).unwrap();
+    fn add_five(&mut self) -> Result<()> {
+        let six = self.known(6)?;
 
-        let five = self
-            .patterns
-            .iter()
-            .filter(|x| x.as_u8s().unwrap().contains(&5))
-            .find(|x| x.0.is_subset(&six.0))
-            .unwrap();
-
-        self.digit_map.insert(five.to_owned(), 5);
+        let five = self.candidates(5).find(|x| x.0.is_subset(&six.0)).cloned();
+        self.add(5, five.as_ref())
     }
 
-    fn add_two(&mut self) {
-        let five = self.digit_map.get_by_right(&5).unwrap();
-        let three = self.digit_map.get_by_right(&3).unwrap();
+    fn add_two(&mut self) -> Result<()> {
+        let five = self.known(5)?;
+        let three = self.known(3)?;
 
         let two = self
-            .patterns
-            .iter()
-            .filter(|x| x.as_u8s().unwrap().contains(&5))
-            .filter(|x| *x != five)
-            .find(|x| *x != three)
-            .unwrap();
-
-        self.digit_map.insert(two.to_owned(), 2);
+            .candidates(2)
+            .filter(|x| **x != five)
+            .find(|x| **x != three)
+            .cloned();
+        self.add(2, two.as_ref())
     }
 
-    fn update_digit_map(&mut self) {
+    fn update_digit_map(&mut self) -> Result<()> {
         for digit in &self.patterns {
             if let Ok(number) = digit.as_naive_u8() {
                 self.digit_map.insert(digit.to_owned(), number);
             }
         }
 
-        self.add_three();
-        self.add_nine();
-        self.add_zero();
-        self.add_six();
-        self.add_five();
-        self.add_two();
+        self.add_three()?;
+        self.add_nine()?;
+        self.add_zero()?;
+        self.add_six()?;
+        self.add_five()?;
+        self.add_two()
     }
 
-    fn reading(&self) -> u32 {
+    fn reading(&self) -> Result<u32> {
         self.outputs
             .iter()
             .rev()
             .enumerate()
             .map(|(i, digit)| {
-                (*self.digit_map.get_by_left(digit).unwrap() as u32) * 10u32.pow(i as u32)
+                let value = self
+                    .digit_map
+                    .get_by_left(digit)
+                    .ok_or_else(|| anyhow!("No pattern matches the output {:?}", digit))?;
+                Ok((*value as u32) * 10u32.pow(i as u32))
             })
             .sum()
     }
 }
 
-fn generator(input: &str) -> Vec<DisplayReading> {
+pub fn generator(input: &str) -> Result<Vec<DisplayReading>, InputError> {
     input
         .lines()
-        .map(|line| line.split_once(" | ").unwrap())
-        .map(|(patterns, outputs)| DisplayReading {
-            patterns: patterns
-                .split_whitespace()
-                .map(|s| s.parse().unwrap())
-                .collect(),
-            outputs: outputs
-                .split_whitespace()
-                .map(|s| s.parse().unwrap())
-                .collect(),
-            digit_map: BiHashMap::new(),
+        .map(|line| {
+            let (patterns, outputs) = line
+                .split_once(" | ")
+                .ok_or_else(|| InputError::at(DAY, input, line, "Expected patterns | outputs"))?;
+
+            let patterns: HashSet<SignalDigit> =
+                parse_each(DAY, input, patterns.split_whitespace(), str::parse)?;
+            let outputs: Vec<SignalDigit> =
+                parse_each(DAY, input, outputs.split_whitespace(), str::parse)?;
+
+            if patterns.len() != 10 {
+                return Err(InputError::at(
+                    DAY,
+                    input,
+                    line,
+                    "Expected 10 different patterns",
+                ));
+            }
+            if outputs.len() != 4 {
+                return Err(InputError::at(DAY, input, line, "Expected 4 outputs"));
+            }
+
+            Ok(DisplayReading {
+                patterns,
+                outputs,
+                digit_map: BiHashMap::new(),
+            })
         })
         .collect()
 }
 
-pub fn part1(input: &str) -> usize {
-    let input = generator(input);
-
+pub fn part1(input: &[DisplayReading]) -> usize {
     input
         .iter()
         .map(|line| {
@@ -202,13 +229,11 @@ pub fn part1(input: &str) -> usize {
         .sum()
 }
 
-pub fn part2(input: &str) -> u32 {
-    let input = generator(input);
-
-    let mut readings = input;
+pub fn part2(input: &[DisplayReading]) -> Result<u32> {
+    let mut readings = input.to_vec();
 
     for reading in readings.iter_mut() {
-        reading.update_digit_map();
+        reading.update_digit_map()?;
     }
 
     readings.into_iter().map(|reading| reading.reading()).sum()
@@ -233,12 +258,41 @@ gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
 
     #[test]
     fn part1() {
-        assert_eq!(super::part1(SAMPLE_2), 26);
+        assert_eq!(super::part1(&super::generator(SAMPLE_2).unwrap()), 26);
     }
 
     #[test]
     fn part2() {
-        assert_eq!(super::part2(SAMPLE_1), 5353);
-        assert_eq!(super::part2(SAMPLE_2), 61229);
+        assert_eq!(
+            super::part2(&super::generator(SAMPLE_1).unwrap()).unwrap(),
+            5353
+        );
+        assert_eq!(
+            super::part2(&super::generator(SAMPLE_2).unwrap()).unwrap(),
+            61229
+        );
+
+        let readings =
+            super::generator("ab abc abcd abcde abcdef abcdefg bc bcd bcde bcdef | ab ab ab ab")
+                .unwrap();
+        assert_eq!(
+            super::part2(&readings).unwrap_err().to_string(),
+            "No pattern matches 0"
+        );
+    }
+
+    #[test]
+    fn generator() {
+        let error = |input| super::generator(input).unwrap_err().message;
+
+        assert_eq!(error("ab abc | ab"), "Expected 10 different patterns");
+        assert_eq!(
+            error("ab abc abcd abcde abcdef abcdefg bc bcd bcde bcdef | ab ab"),
+            "Expected 4 outputs"
+        );
+        assert_eq!(
+            error("ab ab abcd abcde abcdef abcdefg bc bcd bcde bcdef | ab ab ab ab"),
+            "Expected 10 different patterns"
+        );
     }
 }
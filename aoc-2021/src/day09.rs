@@ -1,89 +1,61 @@
+use aoc_common::grid::{Grid, Position};
+use aoc_common::input::InputError;
 use itertools::Itertools;
 
+const DAY: u8 = 9;
+
 #[derive(Clone, Debug)]
-struct HeightMap(Vec<Vec<u8>>);
+pub struct HeightMap(Grid<u8>);
 
 impl HeightMap {
-    fn get(&self, x: usize, y: usize) -> Option<&u8> {
-        self.0.get(x).and_then(|x| x.get(y))
-    }
+    fn is_local_minimum(&self, position: Position) -> bool {
+        let value = self.0[position];
 
-    fn neighbours(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
-        vec![
-            if x > 0 { Some((x - 1, y)) } else { None },
-            if y > 0 { Some((x, y - 1)) } else { None },
-            if x < self.0.len() - 1 {
-                Some((x + 1, y))
-            } else {
-                None
-            },
-            if y < self.0[x].len() - 1 {
-                Some((x, y + 1))
-            } else {
-                None
-            },
-        ]
-        .into_iter()
-        .flatten()
-        .collect()
+        self.0
+            .neighbours(position)
+            .all(|neighbour| self.0[neighbour] > value)
     }
 
-    fn is_local_minimum(&self, x: usize, y: usize) -> bool {
-        let value = self.get(x, y).unwrap();
-
-        self.neighbours(x, y)
-            .into_iter()
-            .map(|(x, y)| self.get(x, y).unwrap())
-            .all(|neighbour| neighbour > value)
-    }
-
-    fn local_minima(&self) -> Vec<(usize, usize)> {
-        (0..self.0.len())
-            .flat_map(|x| {
-                (0..self.0[x].len()).filter_map(move |y| {
-                    if self.is_local_minimum(x, y) {
-                        Some((x, y))
-                    } else {
-                        None
-                    }
-                })
-            })
+    fn local_minima(&self) -> Vec<Position> {
+        self.0
+            .positions()
+            .filter(|position| self.is_local_minimum(*position))
             .collect()
     }
 
-    fn risk_level(&self, x: usize, y: usize) -> u8 {
-        self.get(x, y).unwrap() + 1
+    fn risk_level(&self, position: Position) -> u8 {
+        self.0[position] + 1
     }
 
-    fn flatten(&mut self, x: usize, y: usize) -> bool {
-        if self.get(x, y) == Some(&9) {
+    fn flatten(&mut self, position: Position) -> bool {
+        if self.0[position] == 9 {
             false
         } else {
-            self.0[x][y] = 9;
+            self.0[position] = 9;
             true
         }
     }
 
-    fn extract_basin(&mut self, x: usize, y: usize) -> Vec<(usize, usize)> {
-        if self.flatten(x, y) {
-            self.neighbours(x, y)
-                .iter()
-                .flat_map(|(x, y)| self.extract_basin(*x, *y))
-                .chain(std::iter::once((x, y)))
+    fn extract_basin(&mut self, position: Position) -> Vec<Position> {
+        if self.flatten(position) {
+            self.0
+                .neighbours(position)
+                .collect_vec()
+                .into_iter()
+                .flat_map(|neighbour| self.extract_basin(neighbour))
+                .chain(std::iter::once(position))
                 .collect()
         } else {
             Vec::new()
         }
     }
 
-    fn extract_basins(&mut self) -> Vec<Vec<(usize, usize)>> {
+    fn extract_basins(&mut self) -> Vec<Vec<Position>> {
         let mut basins = Vec::new();
 
-        for x in 0..self.0.len() {
-            for y in 0..self.0[x].len() {
-                if self.get(x, y) != Some(&9) {
-                    basins.push(self.extract_basin(x, y));
-                }
+        for position in self.0.positions().collect_vec() {
+            if self.0[position] != 9 {
+                basins.push(self.extract_basin(position));
             }
         }
 
@@ -91,31 +63,22 @@ impl HeightMap {
     }
 }
 
-fn generator(input: &str) -> HeightMap {
-    HeightMap(
-        input
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| c.to_string().parse().unwrap())
-                    .collect()
-            })
-            .collect(),
-    )
+pub fn generator(input: &str) -> Result<HeightMap, InputError> {
+    Grid::digits(input)
+        .map(HeightMap)
+        .map_err(|err| InputError::grid(DAY, input, err))
 }
 
-pub fn part1(input: &str) -> u32 {
-    let input = generator(input);
-
+pub fn part1(input: &HeightMap) -> u32 {
     input
         .local_minima()
         .into_iter()
-        .map(|(x, y)| input.risk_level(x, y) as u32)
+        .map(|position| input.risk_level(position) as u32)
         .sum()
 }
 
-pub fn part2(input: &str) -> u64 {
-    let mut input = generator(input);
+pub fn part2(input: &HeightMap) -> u64 {
+    let mut input = input.clone();
 
     input
         .extract_basins()
@@ -137,11 +100,11 @@ mod tests {
 
     #[test]
     fn part1() {
-        assert_eq!(super::part1(SAMPLE), 15);
+        assert_eq!(super::part1(&super::generator(SAMPLE).unwrap()), 15);
     }
 
     #[test]
     fn part2() {
-        assert_eq!(super::part2(SAMPLE), 1134);
+        assert_eq!(super::part2(&super::generator(SAMPLE).unwrap()), 1134);
     }
 }
@@ -1,37 +1,58 @@
+use aoc_common::input::InputError;
 use thiserror::Error;
 
-fn generator(input: &str) -> Vec<String> {
-    input.lines().map(ToOwned::to_owned).collect()
+const DAY: u8 = 10;
+
+/// Checks the syntax of each line
+pub fn generator(input: &str) -> Result<Vec<Result<(), ParseError>>, InputError> {
+    input
+        .lines()
+        .map(|line| match line.find(|c| !"()[]{}<>".contains(c)) {
+            Some(index) => Err(InputError::at(
+                DAY,
+                input,
+                &line[index..],
+                "Expected a bracket",
+            )),
+            None => Ok(parse_brackets(line)),
+        })
+        .collect()
 }
 
 #[derive(Error, Debug)]
-enum ParseError {
+pub enum ParseError {
     #[error("Incomplete expression. Complete by adding {expected}")]
     Incomplete { expected: String },
     #[error("Expected {expected}, but found {found} instead")]
     Corrupted { expected: char, found: char },
+    #[error("Found {found} with nothing to close")]
+    Unopened { found: char },
 }
 
 impl ParseError {
-    fn score(&self) -> u64 {
+    fn score(&self) -> Result<u64, &'static str> {
         match self {
-            ParseError::Corrupted { found, .. } => match found {
-                ')' => 3,
-                ']' => 57,
-                '}' => 1197,
-                '>' => 25137,
-                _ => unreachable!(),
-            },
-            ParseError::Incomplete { ref expected } => expected.chars().fold(0, |score, c| {
-                score * 5
-                    + match c {
+            ParseError::Corrupted { found, .. } | ParseError::Unopened { found } => {
+                Ok(match found {
+                    ')' => 3,
+                    ']' => 57,
+                    '}' => 1197,
+                    '>' => 25137,
+                    _ => unreachable!(),
+                })
+            }
+            ParseError::Incomplete { ref expected } => expected
+                .chars()
+                .try_fold(0u64, |score, c| {
+                    score.checked_mul(5)?.checked_add(match c {
                         ')' => 1,
                         ']' => 2,
                         '}' => 3,
                         '>' => 4,
                         _ => unreachable!(),
-                    }
-            }),
+                    })
+                })
+                .ok_or("The score overflows a 64-bit number"),
         }
     }
 }
@@ -72,7 +93,7 @@ fn parse_brackets(s: &str) -> Result<(), ParseError> {
                     found: e,
                 });
             }
-            _ => unreachable!(),
+            (None, e) => return Err(ParseError::Unopened { found: e }),
         }
     }
 
@@ -85,28 +106,22 @@ fn parse_brackets(s: &str) -> Result<(), ParseError> {
     }
 }
 
-pub fn part1(input: &str) -> u64 {
-    let input = generator(input);
-
+pub fn part1(input: &[Result<(), ParseError>]) -> Result<u64, &'static str> {
     input
         .iter()
-        .map(|line| parse_brackets(line))
-        .filter_map(|result| {
-            if let Err(e @ ParseError::Corrupted { .. }) = result {
+        .filter_map(|result| match result {
+            Err(e @ (ParseError::Corrupted { .. } | ParseError::Unopened { .. })) => {
                 Some(e.score())
-            } else {
-                None
             }
+            _ => None,
         })
         .sum()
 }
 
-pub fn part2(input: &str) -> u64 {
-    let input = generator(input);
-
-    let mut scores: Vec<_> = input
+/// The middle score of the incomplete lines
+pub fn part2(input: &[Result<(), ParseError>]) -> Result<u64, &'static str> {
+    let mut scores = input
         .iter()
-        .map(|line| parse_brackets(line))
         .filter_map(|result| {
             if let Err(e @ ParseError::Incomplete { .. }) = result {
                 Some(e.score())
@@ -114,10 +129,14 @@ pub fn part2(input: &str) -> u64 {
                 None
             }
         })
-        .collect();
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if scores.is_empty() {
+        return Err("No line is incomplete");
+    }
 
     let n = scores.len() / 2;
-    *scores.select_nth_unstable(n).1
+    Ok(*scores.select_nth_unstable(n).1)
 }
 
 #[cfg(test)]
@@ -135,11 +154,31 @@ mod tests {
 
     #[test]
     fn part1() {
-        assert_eq!(super::part1(SAMPLE), 26397);
+        assert_eq!(super::part1(&super::generator(SAMPLE).unwrap()), Ok(26397));
     }
 
     #[test]
     fn part2() {
-        assert_eq!(super::part2(SAMPLE), 288957);
+        assert_eq!(super::part2(&super::generator(SAMPLE).unwrap()), Ok(288957));
+        assert_eq!(
+            super::part2(&super::generator("()").unwrap()),
+            Err("No line is incomplete")
+        );
+        assert_eq!(
+            super::part2(&super::generator(&"(".repeat(29)).unwrap()),
+            Err("The score overflows a 64-bit number")
+        );
+    }
+
+    #[test]
+    fn generator() {
+        let lines = super::generator("()>\n(<").unwrap();
+        assert_eq!(
+            lines[0].as_ref().unwrap_err().to_string(),
+            "Found > with nothing to close"
+        );
+        assert_eq!(super::part1(&lines), Ok(25137));
+
+        assert!(super::generator("(x)").is_err());
     }
 }
@@ -1,26 +1,18 @@
-use std::{
-    collections::HashSet,
-    fmt::{self, Write},
-};
+use std::{collections::HashSet, fmt, io};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use aoc_common::grid::{Grid, Position};
+use aoc_common::input::InputError;
+use aoc_common::render::{Frames, Image, Rgb};
 
-#[derive(Clone, Debug)]
-struct OctopusGrid(Vec<Vec<u16>>);
+const DAY: u8 = 11;
 
-type Point = (usize, usize);
+#[derive(Clone, Debug)]
+pub struct OctopusGrid(Grid<u16>);
 
 impl fmt::Display for OctopusGrid {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        for line in &self.0 {
-            for energy in line {
-                write!(f, "{:<3}", energy)?;
-            }
-
-            f.write_char('\n')?;
-        }
-
-        Ok(())
+        write!(f, "{:<3}", self.0)
     }
 }
 
@@ -33,10 +25,8 @@ impl OctopusGrid {
     }
 
     fn increment(&mut self) {
-        for line in self.0.iter_mut() {
-            for energy in line {
-                *energy += 1;
-            }
+        for energy in self.0.iter_mut() {
+            *energy += 1;
         }
     }
 
@@ -44,15 +34,12 @@ impl OctopusGrid {
         let mut flashed = HashSet::new();
 
         loop {
-            let mut flashers = Vec::new();
-
-            for x in 0..self.0.len() {
-                for y in 0..self.0[x].len() {
-                    if self.0[x][y] >= 10 && !flashed.contains(&(x, y)) {
-                        flashers.push((x, y));
-                    }
-                }
-            }
+            let flashers = self
+                .0
+                .enumerate()
+                .filter(|(position, energy)| **energy >= 10 && !flashed.contains(position))
+                .map(|(position, _)| position)
+                .collect::<Vec<_>>();
 
             if flashers.is_empty() {
                 break;
@@ -65,103 +52,90 @@ impl OctopusGrid {
         }
     }
 
-    fn flash(&mut self, point: Point) {
+    fn flash(&mut self, point: Position) {
         // increment self so that its not always 9
         // and triggers flashing
-        self.0[point.0][point.1] += 1;
+        self.0[point] += 1;
 
-        for (x, y) in self.neighbours(point) {
-            self.0[x][y] += 1;
+        for neighbour in self.0.all_neighbours(point).collect::<Vec<_>>() {
+            self.0[neighbour] += 1;
         }
     }
 
-    fn neighbours(&self, (x, y): Point) -> Vec<Point> {
-        vec![
-            if x > 0 { Some((x - 1, y)) } else { None },
-            if y > 0 { Some((x, y - 1)) } else { None },
-            if x < self.0.len() - 1 {
-                Some((x + 1, y))
-            } else {
-                None
-            },
-            if y < self.0[x].len() - 1 {
-                Some((x, y + 1))
-            } else {
-                None
-            },
-            if x > 0 && y > 0 {
-                Some((x - 1, y - 1))
-            } else {
-                None
-            },
-            if x > 0 && y < self.0[x].len() - 1 {
-                Some((x - 1, y + 1))
-            } else {
-                None
-            },
-            if x < self.0.len() - 1 && y > 0 {
-                Some((x + 1, y - 1))
-            } else {
-                None
-            },
-            if x < self.0.len() - 1 && y < self.0[x].len() - 1 {
-                Some((x + 1, y + 1))
-            } else {
-                None
-            },
-        ]
-        .into_iter()
-        .flatten()
-        .collect()
-    }
-
     fn normalize(&mut self) {
-        for line in self.0.iter_mut() {
-            for energy in line {
-                if *energy > 9 {
-                    *energy = 0;
-                }
+        for energy in self.0.iter_mut() {
+            if *energy > 9 {
+                *energy = 0;
             }
         }
     }
 
     fn count_flashes(&self) -> usize {
-        self.0
-            .iter()
-            .flat_map(|line| line.iter().filter(|energy| energy == &&0))
-            .count()
+        self.0.iter().filter(|energy| energy == &&0).count()
+    }
+
+    /// Octopodes which just flashed in white, and the rest brighter the more energy they have
+    fn image(&self) -> Image {
+        self.0.map(|energy| match energy {
+            0 => Rgb::WHITE,
+            energy => Rgb::grey(*energy.min(&9) as u8 * 20),
+        })
+    }
+
+    /// Writes the octopodes as the next frame, if frames are being written
+    fn record(&self, frames: Option<&mut Frames>) -> io::Result<()> {
+        if let Some(frames) = frames {
+            frames.push(&self.image())?;
+        }
+
+        Ok(())
     }
 }
 
-fn generator(input: &str) -> OctopusGrid {
-    OctopusGrid(
-        input
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| c.to_string().parse().unwrap())
-                    .collect()
-            })
-            .collect(),
-    )
+pub fn generator(input: &str) -> Result<OctopusGrid, InputError> {
+    Grid::digits(input)
+        .map(|grid| OctopusGrid(grid.map(|energy| u16::from(*energy))))
+        .map_err(|err| InputError::grid(DAY, input, err))
 }
 
-pub fn part1(input: &str) -> usize {
-    let input = generator(input);
-    let mut octopodes = input;
+pub fn part1(input: &OctopusGrid) -> Result<usize> {
+    let mut octopodes = input.clone();
+    let mut frames = Frames::for_part(2021, DAY, 1)?;
+    let mut flashes = 0;
 
-    (0..100).map(|_| octopodes.step()).sum()
+    octopodes.record(frames.as_mut())?;
+    for _ in 0..100 {
+        flashes += octopodes.step();
+        octopodes.record(frames.as_mut())?;
+    }
+
+    Ok(flashes)
 }
 
-pub fn part2(input: &str) -> usize {
-    let input = generator(input);
-    let mut octopodes = input;
+pub fn part2(input: &OctopusGrid) -> Result<usize> {
+    let mut octopodes = input.clone();
+    let mut frames = Frames::for_part(2021, DAY, 2)?;
+
+    let everyone = octopodes.0.width() * octopodes.0.height();
 
-    (1..)
-        .map(|step| (step, octopodes.step()))
-        .find(|(_, flashes)| *flashes == 100)
-        .unwrap()
-        .0
+    // The steps are deterministic, so once the octopodes repeat themselves they never will sync up
+    let mut seen = HashSet::new();
+
+    octopodes.record(frames.as_mut())?;
+    let mut step = 0;
+    loop {
+        if !seen.insert(octopodes.0.clone()) {
+            bail!("The octopodes never all flash at once");
+        }
+
+        step += 1;
+        let flashes = octopodes.step();
+        octopodes.record(frames.as_mut())?;
+
+        if flashes == everyone {
+            return Ok(step);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -179,11 +153,24 @@ mod tests {
 
     #[test]
     fn part1() {
-        assert_eq!(super::part1(SAMPLE), 1656);
+        assert_eq!(
+            super::part1(&super::generator(SAMPLE).unwrap()).unwrap(),
+            1656
+        );
     }
 
     #[test]
     fn sample2() {
-        assert_eq!(super::part2(SAMPLE), 195);
+        assert_eq!(
+            super::part2(&super::generator(SAMPLE).unwrap()).unwrap(),
+            195
+        );
+
+        assert_eq!(
+            super::part2(&super::generator("01\n10\n89").unwrap())
+                .unwrap_err()
+                .to_string(),
+            "The octopodes never all flash at once"
+        );
     }
 }
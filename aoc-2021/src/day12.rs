@@ -1,9 +1,15 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+use aoc_common::input::{parse_lines, InputError};
 use petgraph::graphmap::UnGraphMap;
 
+const DAY: u8 = 12;
+
+/// The most small caves there can be, as the paths are counted for each set of them visited
+const MAX_SMALL_CAVES: usize = 16;
+
 #[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
-enum Cave<'a> {
+pub enum Cave<'a> {
     Start,
     End,
     Large(&'a str),
@@ -30,83 +36,130 @@ impl Cave<'_> {
     }
 }
 
-impl<'a> From<&'a str> for Cave<'a> {
-    fn from(s: &'a str) -> Self {
+impl<'a> TryFrom<&'a str> for Cave<'a> {
+    type Error = String;
+
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
         match s {
-            "start" => Cave::Start,
-            "end" => Cave::End,
-            n if n == n.to_lowercase() => Cave::Small(n),
-            n if n == n.to_uppercase() => Cave::Large(n),
-            _ => unreachable!(),
+            "start" => Ok(Cave::Start),
+            "end" => Ok(Cave::End),
+            n if !n.is_empty() && n.chars().all(|c| c.is_ascii_lowercase()) => Ok(Cave::Small(n)),
+            n if !n.is_empty() && n.chars().all(|c| c.is_ascii_uppercase()) => Ok(Cave::Large(n)),
+            n => Err(format!(
+                "Expected a cave named in lower or upper case letters, found {:?}",
+                n
+            )),
         }
     }
 }
 
-fn paths<'a>(
-    graph: &UnGraphMap<Cave<'a>, ()>,
-    initial: Vec<Cave<'a>>,
-    to: Cave<'a>,
-) -> HashSet<Vec<Cave<'a>>> {
-    graph
-        .neighbors(initial.last().unwrap().to_owned())
-        .filter(|neighbour| !(neighbour.is_small() && initial.contains(neighbour)))
-        .flat_map(|neighbour| {
-            let mut path = initial.clone();
-            path.push(neighbour);
-
-            if neighbour == to {
-                [path].into()
-            } else {
-                paths(graph, path, to)
-            }
-        })
-        .collect()
+pub type Caves<'a> = UnGraphMap<Cave<'a>, ()>;
+
+fn passage(line: &str) -> Result<(Cave<'_>, Cave<'_>), String> {
+    let (from, to) = line
+        .split_once('-')
+        .ok_or("Expected a passage between two caves")?;
+
+    match (Cave::try_from(from)?, Cave::try_from(to)?) {
+        // There would be no end of paths back and forth between them
+        (Cave::Large(_), Cave::Large(_)) => Err(String::from("Two large caves are connected")),
+        passage => Ok(passage),
+    }
 }
 
-fn paths_with_single_revisit<'a>(
-    graph: &UnGraphMap<Cave<'a>, ()>,
-    initial: Vec<Cave<'a>>,
-    to: Cave<'a>,
-) -> HashSet<Vec<Cave<'a>>> {
-    graph
-        .neighbors(initial.last().unwrap().to_owned())
-        .flat_map(|neighbour| {
-            let mut path = initial.clone();
-            path.push(neighbour);
-
-            if neighbour == to {
-                [path].into()
-            } else if initial.contains(&neighbour) && !neighbour.is_revisitable() {
-                // revisit of start or end
-                [].into()
-            } else if initial.contains(&neighbour) && neighbour.is_small() {
-                // revisit of a small cave
-                paths(graph, path, to)
-            } else {
-                paths_with_single_revisit(graph, path, to)
-            }
-        })
-        .collect()
+pub fn generator(input: &str) -> Result<Caves<'_>, InputError> {
+    let mut small_caves = HashSet::new();
+
+    parse_lines(DAY, input, |line| {
+        let (from, to) = passage(line)?;
+
+        small_caves.extend([from, to].into_iter().filter(|cave| cave.is_small()));
+        if small_caves.len() > MAX_SMALL_CAVES {
+            return Err(format!(
+                "Expected at most {} small caves, counting the start and end",
+                MAX_SMALL_CAVES
+            ));
+        }
+
+        Ok((from, to))
+    })
 }
 
-pub fn part1<'a>(input: &'a str) -> usize {
-    let graph: UnGraphMap<Cave<'a>, ()> = input
-        .lines()
-        .map(|line| line.split_once('-').unwrap())
-        .map(|(from, to)| (from.into(), to.into()))
-        .collect();
+/// Counts the paths through the caves
+struct Paths<'g, 'a> {
+    graph: &'g Caves<'a>,
+    /// A bit for each small cave, to mark which have been visited
+    small_caves: HashMap<Cave<'a>, u16>,
+    counts: HashMap<(Cave<'a>, u16, bool), u64>,
+}
 
-    paths(&graph, vec![Cave::Start], Cave::End).len()
+impl<'g, 'a> Paths<'g, 'a> {
+    fn new(graph: &'g Caves<'a>) -> Self {
+        let small_caves = graph
+            .nodes()
+            .filter(Cave::is_small)
+            .zip((0..).map(|bit| 1 << bit))
+            .collect();
+
+        Paths {
+            graph,
+            small_caves,
+            counts: HashMap::new(),
+        }
+    }
+
+    /// The number of paths from the start to the end, which can visit a single small cave
+    /// twice when `revisit` is set, or `None` if there are too many to count
+    fn total(&mut self, revisit: bool) -> Option<u64> {
+        let visited = self
+            .small_caves
+            .get(&Cave::Start)
+            .copied()
+            .unwrap_or_default();
+        self.count(Cave::Start, visited, revisit)
+    }
+
+    /// The number of paths from `cave` to the end, having already visited the small caves in
+    /// `visited`
+    fn count(&mut self, cave: Cave<'a>, visited: u16, revisit: bool) -> Option<u64> {
+        if cave == Cave::End {
+            return Some(1);
+        }
+
+        if let Some(count) = self.counts.get(&(cave, visited, revisit)) {
+            return Some(*count);
+        }
+
+        let mut count = 0u64;
+        for neighbour in self.graph.neighbors(cave) {
+            let paths = match self.small_caves.get(&neighbour) {
+                Some(bit) if visited & bit != 0 => {
+                    if revisit && neighbour.is_revisitable() {
+                        self.count(neighbour, visited, false)
+                    } else {
+                        Some(0)
+                    }
+                }
+                Some(bit) => self.count(neighbour, visited | bit, revisit),
+                None => self.count(neighbour, visited, revisit),
+            };
+
+            count = count.checked_add(paths?)?;
+        }
+
+        self.counts.insert((cave, visited, revisit), count);
+        Some(count)
+    }
 }
 
-pub fn part2<'a>(input: &'a str) -> usize {
-    let graph: UnGraphMap<Cave<'a>, ()> = input
-        .lines()
-        .map(|line| line.split_once('-').unwrap())
-        .map(|(from, to)| (from.into(), to.into()))
-        .collect();
+const TOO_MANY: &str = "There are too many paths to count in a 64-bit number";
+
+pub fn part1(graph: &Caves) -> Result<u64, &'static str> {
+    Paths::new(graph).total(false).ok_or(TOO_MANY)
+}
 
-    paths_with_single_revisit(&graph, vec![Cave::Start], Cave::End).len()
+pub fn part2(graph: &Caves) -> Result<u64, &'static str> {
+    Paths::new(graph).total(true).ok_or(TOO_MANY)
 }
 
 #[cfg(test)]
@@ -153,15 +206,44 @@ start-RW";
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(SAMPLE_1), 10);
-        assert_eq!(part1(SAMPLE_2), 19);
-        assert_eq!(part1(SAMPLE_3), 226);
+        assert_eq!(part1(&generator(SAMPLE_1).unwrap()), Ok(10));
+        assert_eq!(part1(&generator(SAMPLE_2).unwrap()), Ok(19));
+        assert_eq!(part1(&generator(SAMPLE_3).unwrap()), Ok(226));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(SAMPLE_1), 36);
-        assert_eq!(part2(SAMPLE_2), 103);
-        assert_eq!(part2(SAMPLE_3), 3509);
+        assert_eq!(part2(&generator(SAMPLE_1).unwrap()), Ok(36));
+        assert_eq!(part2(&generator(SAMPLE_2).unwrap()), Ok(103));
+        assert_eq!(part2(&generator(SAMPLE_3).unwrap()), Ok(3509));
+
+        // A small cave next to itself can be visited twice in a row
+        assert_eq!(part2(&generator("start-b\nb-b\nb-end").unwrap()), Ok(2));
+    }
+
+    #[test]
+    fn test_generator() {
+        let message = |input| generator(input).unwrap_err().message;
+
+        assert_eq!(
+            message("start-A\nA"),
+            "Expected a passage between two caves"
+        );
+        assert_eq!(
+            message("start-Ab"),
+            "Expected a cave named in lower or upper case letters, found \"Ab\""
+        );
+        assert_eq!(message("start-A\nA-B"), "Two large caves are connected");
+
+        let caves = (b'a'..=b'n')
+            .map(|name| format!("A-{}", name as char))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let hub = format!("start-A\nA-end\n{}", caves);
+        assert!(part2(&generator(&hub).unwrap()).is_ok());
+        assert_eq!(
+            message(&format!("start-A\nA-end\n{}\nA-o", caves)),
+            "Expected at most 16 small caves, counting the start and end"
+        );
     }
 }
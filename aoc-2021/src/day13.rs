@@ -1,17 +1,26 @@
 use std::cmp::Ordering;
-use std::{collections::HashSet, fmt, ops::RangeInclusive};
+use std::collections::HashSet;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use aoc_common::geometry::{Bounds, Point};
+use aoc_common::grid::Grid;
+use aoc_common::input::{parse_all, InputError};
+use aoc_common::ocr;
+use aoc_common::render::{Frames, Rgb};
 use nom::{
     bytes::complete::tag,
-    character::complete::{anychar, char, digit1},
+    character::complete::{char, digit1, one_of},
     combinator::map_res,
     sequence::{preceded, separated_pair},
     IResult,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
-struct Dot(i32, i32);
+const DAY: u8 = 13;
+
+/// The most cells the paper is drawn with, far more than any letters need
+const MAX_AREA: usize = 1 << 20;
+
+type Dot = Point<i32>;
 
 fn parse_dot(input: &str) -> IResult<&str, Dot> {
     let (input, (x, y)) = separated_pair(
@@ -19,130 +28,160 @@ fn parse_dot(input: &str) -> IResult<&str, Dot> {
         char(','),
         map_res(digit1, str::parse),
     )(input)?;
-    Ok((input, Dot(x, y)))
-}
-
-impl Dot {
-    fn fold(&self, fold: &Fold) -> Option<Dot> {
-        match fold {
-            Fold::Left(mid) => match self.0.cmp(mid) {
-                Ordering::Less => Some(Dot(self.0, self.1)),
-                Ordering::Equal => None,
-                Ordering::Greater => Some(Dot(2 * mid - self.0, self.1)),
-            },
-            Fold::Up(mid) => match self.1.cmp(mid) {
-                Ordering::Less => Some(Dot(self.0, self.1)),
-                Ordering::Equal => None,
-                Ordering::Greater => Some(Dot(self.0, 2 * mid - self.1)),
-            },
-        }
-    }
+    Ok((input, Dot::new(x, y)))
 }
 
 #[derive(Clone, Debug)]
-struct Paper(HashSet<Dot>);
-
-impl fmt::Display for Paper {
-    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        for y in self.height() {
-            for x in self.width() {
-                if self.0.contains(&Dot(x, y)) {
-                    write!(f, "#")?;
-                } else {
-                    write!(f, ".")?;
-                }
-            }
-            writeln!(f)?;
-        }
-
-        Ok(())
-    }
-}
+pub struct Paper(HashSet<Dot>);
 
 impl Paper {
-    fn width(&self) -> RangeInclusive<i32> {
-        let (min, max) = (
-            self.0.iter().map(|Dot(x, _)| *x).min().unwrap(),
-            self.0.iter().map(|Dot(x, _)| *x).max().unwrap(),
-        );
+    fn fold(&mut self, fold: &Fold) {
+        self.0 = self.0.iter().filter_map(|dot| fold.apply(dot)).collect();
+    }
 
-        min..=max
+    /// Whether every dot stays on the paper, at or right of and below its top left corner,
+    /// after the fold
+    fn can_fold(&self, fold: &Fold) -> bool {
+        self.0.iter().all(|dot| match fold {
+            Fold::Left(mid) => dot.x - mid <= *mid,
+            Fold::Up(mid) => dot.y - mid <= *mid,
+        })
     }
 
-    fn height(&self) -> RangeInclusive<i32> {
-        let (min, max) = (
-            self.0.iter().map(|Dot(_, y)| *y).min().unwrap(),
-            self.0.iter().map(|Dot(_, y)| *y).max().unwrap(),
-        );
+    /// The dots from the top left corner of the paper, which is where any letters start. The
+    /// generator makes sure no dot is ever folded left of or above the corner.
+    fn grid(&self) -> Result<Grid<bool>> {
+        let Some(bounds) = Bounds::from_points(&self.0) else {
+            return Ok(Grid::new(0, 0, false));
+        };
+
+        let (width, height) = (bounds.max.x as usize + 1, bounds.max.y as usize + 1);
+        if width.saturating_mul(height) > MAX_AREA {
+            bail!("The paper is {}x{}, too large to draw", width, height);
+        }
+
+        let mut grid = Grid::new(width, height, false);
+        for dot in &self.0 {
+            grid[(dot.x as usize, dot.y as usize)] = true;
+        }
 
-        min..=max
+        Ok(grid)
     }
 
-    fn fold(&mut self, fold: &Fold) {
-        self.0 = self.0.iter().filter_map(|dot| dot.fold(fold)).collect();
+    /// Writes the paper as the next frame, if frames are being written
+    fn record(&self, frames: Option<&mut Frames>) -> Result<()> {
+        if let Some(frames) = frames {
+            frames.push(
+                &self
+                    .grid()?
+                    .map(|dot| if *dot { Rgb::WHITE } else { Rgb::BLACK }),
+            )?;
+        }
+
+        Ok(())
     }
 }
 
 #[derive(Debug)]
-enum Fold {
+pub enum Fold {
     Up(i32),
     Left(i32),
 }
 
+impl Fold {
+    /// Where a dot ends up after the fold, or `None` if it lies on the fold line
+    fn apply(&self, dot: &Dot) -> Option<Dot> {
+        match self {
+            Fold::Left(mid) => match dot.x.cmp(mid) {
+                Ordering::Less => Some(*dot),
+                Ordering::Equal => None,
+                Ordering::Greater => Some(Dot::new(mid - (dot.x - mid), dot.y)),
+            },
+            Fold::Up(mid) => match dot.y.cmp(mid) {
+                Ordering::Less => Some(*dot),
+                Ordering::Equal => None,
+                Ordering::Greater => Some(Dot::new(dot.x, mid - (dot.y - mid))),
+            },
+        }
+    }
+}
+
 fn parse_fold(input: &str) -> IResult<&str, Fold> {
     let (input, (dimension, position)) = preceded(
         tag("fold along "),
-        separated_pair(anychar, char('='), map_res(digit1, str::parse)),
+        separated_pair(one_of("xy"), char('='), map_res(digit1, str::parse)),
     )(input)?;
 
     let fold = match dimension {
         'x' => Fold::Left(position),
-        'y' => Fold::Up(position),
-        _ => unreachable!(),
+        _ => Fold::Up(position),
     };
 
     Ok((input, fold))
 }
 
-fn generator(input: &str) -> (Paper, Vec<Fold>) {
-    let mut blocks = input.split("\n\n");
-
-    (
-        Paper(
-            blocks
-                .next()
-                .unwrap()
-                .lines()
-                .map(|line| parse_dot(line).unwrap().1)
-                .collect(),
-        ),
-        blocks
-            .next()
-            .unwrap()
-            .lines()
-            .map(|line| parse_fold(line).unwrap().1)
-            .collect(),
-    )
+pub fn generator(input: &str) -> Result<(Paper, Vec<Fold>), InputError> {
+    let (dots, instructions) = input
+        .split_once("\n\n")
+        .ok_or_else(|| InputError::end(DAY, input, "Expected fold instructions"))?;
+
+    let paper = Paper(
+        dots.lines()
+            .map(|line| parse_all(DAY, input, line, parse_dot))
+            .collect::<Result<_, _>>()?,
+    );
+
+    // Folding as the instructions are read checks none of them folds dots off the paper
+    let mut folded = paper.clone();
+    let mut folds = Vec::new();
+    for line in instructions.lines() {
+        let fold = parse_all(DAY, input, line, parse_fold)?;
+        if !folded.can_fold(&fold) {
+            return Err(InputError::at(
+                DAY,
+                input,
+                line,
+                "Expected a fold which leaves every dot on the paper",
+            ));
+        }
+
+        folded.fold(&fold);
+        folds.push(fold);
+    }
+
+    if folds.is_empty() {
+        return Err(InputError::end(DAY, input, "Expected fold instructions"));
+    }
+
+    Ok((paper, folds))
 }
 
-pub fn part1(input: &str) -> usize {
-    let (paper, folds) = generator(input);
-    let mut paper = paper;
+pub fn part1((paper, folds): &(Paper, Vec<Fold>)) -> usize {
+    let mut paper = paper.clone();
 
+    // The generator makes sure there is a fold
     paper.fold(&folds[0]);
 
     paper.0.len()
 }
 
-pub fn part2(input: &str) -> String {
-    let (paper, folds) = generator(input);
-    let mut paper = paper;
+/// Makes every fold, drawing the paper before and after each one when frames are being written
+fn fold_all(paper: &Paper, folds: &[Fold], mut frames: Option<&mut Frames>) -> Result<Paper> {
+    let mut paper = paper.clone();
+    paper.record(frames.as_deref_mut())?;
 
     for fold in folds {
-        paper.fold(&fold);
+        paper.fold(fold);
+        paper.record(frames.as_deref_mut())?;
     }
 
-    String::from("\n") + &paper.to_string()
+    Ok(paper)
+}
+
+pub fn part2((paper, folds): &(Paper, Vec<Fold>)) -> Result<String> {
+    let paper = fold_all(paper, folds, Frames::for_part(2021, DAY, 2)?.as_mut())?;
+
+    Ok(ocr::answer(&paper.grid()?)?)
 }
 
 #[cfg(test)]
@@ -171,20 +210,53 @@ fold along x=5";
 
     #[test]
     fn part1() {
-        assert_eq!(super::part1(SAMPLE_1), 17);
+        assert_eq!(super::part1(&super::generator(SAMPLE_1).unwrap()), 17);
     }
 
     #[test]
     fn part2() {
+        let (paper, folds) = super::generator(SAMPLE_1).unwrap();
         assert_eq!(
-            super::part2(SAMPLE_1),
-            "
-#####
+            super::ocr::art(
+                &super::fold_all(&paper, &folds, None)
+                    .unwrap()
+                    .grid()
+                    .unwrap()
+            ),
+            "#####
 #...#
 #...#
 #...#
 #####
 "
         );
+
+        // The sample folds into a square, rather than letters
+        let err = super::part2(&(paper, folds)).unwrap_err();
+        assert_eq!(
+            err.downcast_ref(),
+            Some(&aoc_common::ocr::OcrError::Height(5))
+        );
+    }
+
+    #[test]
+    fn generator() {
+        let err = super::generator("6,10\n0,14\n\nfold along z=7").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (4, 12, "z=7"));
+
+        let err = super::generator("1,1\n\n").unwrap_err();
+        assert_eq!(err.message, "Expected fold instructions");
+
+        // The second fold would leave the dot at x=10 left of the paper's edge
+        let err = super::generator("10,1\n12,2\n\nfold along x=11\nfold along x=4").unwrap_err();
+        assert_eq!((err.line, err.column), (5, 1));
+        assert_eq!(
+            err.message,
+            "Expected a fold which leaves every dot on the paper"
+        );
+        assert!(super::generator("10,1\n12,2\n\nfold along x=11\nfold along x=5").is_ok());
+
+        let (paper, folds) = super::generator("2000000000,0\n\nfold along x=1999999999").unwrap();
+        assert!(super::part2(&(paper, folds)).is_err());
     }
 }
@@ -1,5 +1,6 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
+use aoc_common::input::{parse_all, InputError};
 use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
@@ -8,6 +9,8 @@ use nom::{
     IResult,
 };
 
+const DAY: u8 = 14;
+
 type Template = Vec<char>;
 type Pattern = (char, char);
 type Rules = HashMap<Pattern, char>;
@@ -19,29 +22,80 @@ fn parse_rule(input: &str) -> IResult<&str, (Pattern, char)> {
     Ok((input, (pattern, result)))
 }
 
-fn generator_part1(input: &str) -> (Template, Rules) {
-    let mut blocks = input.split("\n\n");
-
-    (
-        blocks.next().unwrap().chars().collect(),
-        blocks
-            .next()
-            .unwrap()
-            .lines()
-            .map(|line| parse_rule(line).unwrap().1)
-            .collect(),
-    )
+/// Makes sure there is a rule for every pair the polymer will ever have, starting from those in
+/// the template and following the pairs each insertion makes
+fn check_rules<'a>(
+    input: &str,
+    template: &'a str,
+    rules: &HashMap<Pattern, (char, &'a str)>,
+) -> Result<(), InputError> {
+    let mut seen = HashSet::new();
+    let mut pending = template
+        .char_indices()
+        .zip(template.chars().skip(1))
+        .map(|((index, left), right)| ((left, right), &template[index..]))
+        .collect::<Vec<_>>();
+
+    while let Some((pattern, source)) = pending.pop() {
+        if !seen.insert(pattern) {
+            continue;
+        }
+
+        let Some(&(insertion, line)) = rules.get(&pattern) else {
+            return Err(InputError::at(
+                DAY,
+                input,
+                source,
+                format!("Expected a rule for {}{}", pattern.0, pattern.1),
+            ));
+        };
+
+        pending.push(((pattern.0, insertion), line));
+        pending.push(((insertion, pattern.1), line));
+    }
+
+    Ok(())
 }
 
-pub fn part1(input: &str) -> u64 {
-    let (template, rules) = generator_part1(input);
+pub fn generator(input: &str) -> Result<(Template, Rules), InputError> {
+    let (template, rules) = input
+        .split_once("\n\n")
+        .ok_or_else(|| InputError::end(DAY, input, "Expected insertion rules"))?;
+
+    if template.is_empty() {
+        return Err(InputError::at(DAY, input, template, "Expected a template"));
+    }
 
-    let mut template = template;
+    let rules = rules
+        .lines()
+        .map(|line| {
+            parse_all(DAY, input, line, parse_rule)
+                .map(|(pattern, insertion)| (pattern, (insertion, line)))
+        })
+        .collect::<Result<_, _>>()?;
+    check_rules(input, template, &rules)?;
 
-    for _ in 0..10 {
-        for (i, window) in template.clone().windows(2).enumerate() {
-            template.insert(2 * i + 1, rules[&(window[0], window[1])]);
+    let rules = rules
+        .into_iter()
+        .map(|(pattern, (insertion, _))| (pattern, insertion))
+        .collect();
+
+    Ok((template.chars().collect(), rules))
+}
+
+/// Builds the polymer, inserting an element between every pair `steps` times, then takes the
+/// least common element's count from the most common's
+fn insert_naive(template: &[char], rules: &Rules, steps: usize) -> u64 {
+    let mut template = template.to_vec();
+
+    for _ in 0..steps {
+        let mut polymer = Vec::with_capacity(2 * template.len());
+        for window in template.windows(2) {
+            polymer.extend([window[0], rules[&(window[0], window[1])]]);
         }
+        polymer.extend(template.last());
+
+        template = polymer;
     }
 
     let counts = template.iter().counts();
@@ -54,24 +108,14 @@ pub fn part1(input: &str) -> u64 {
     (*max.1 as u64) - (*min.1 as u64)
 }
 
-pub fn part2(input: &str) -> u64 {
-    let mut blocks = input.split("\n\n");
-
-    let template = blocks.next().unwrap().chars().collect::<Vec<_>>();
-
+/// As [`insert_naive`], but only counting how many of each pair the polymer would have
+fn insert_pairs(template: &[char], rules: &Rules, steps: usize) -> u64 {
     let mut counts = template
         .windows(2)
         .map(|window| (window[0], window[1]))
         .counts();
 
-    let rules: HashMap<(char, char), char> = blocks
-        .next()
-        .unwrap()
-        .lines()
-        .map(|line| parse_rule(line).unwrap().1)
-        .collect();
-
-    for _ in 0..40 {
+    for _ in 0..steps {
         for (pattern, count) in counts.clone() {
             if count > 0 {
                 let left_count = counts
@@ -92,19 +136,18 @@ pub fn part2(input: &str) -> u64 {
         }
     }
 
-    let mut letter_counts = counts
+    // Every element is in two pairs, except for the first and last, which are in one each. Count
+    // those once more, so every element is counted twice.
+    let (first, last) = (template[0], *template.last().unwrap());
+    let letter_counts = counts
         .into_iter()
         .flat_map(|((left, right), count)| [(left, count), (right, count)])
+        .chain([(first, 1), (last, 1)])
         .into_group_map()
         .into_iter()
         .map(|(c, counts)| (c, counts.into_iter().sum::<usize>() / 2))
         .collect::<HashMap<char, usize>>();
 
-    let (first, last) = (template[0], template.last().unwrap());
-
-    *letter_counts.get_mut(&first).unwrap() += 1;
-    *letter_counts.get_mut(last).unwrap() += 1;
-
     let (min, max) = letter_counts
         .iter()
         .minmax_by_key(|(_, count)| *count)
@@ -114,8 +157,24 @@ pub fn part2(input: &str) -> u64 {
     (*max.1 as u64) - (*min.1 as u64)
 }
 
+pub fn part1((template, rules): &(Template, Rules)) -> u64 {
+    insert_naive(template, rules, 10)
+}
+
+pub fn part1_pairs((template, rules): &(Template, Rules)) -> u64 {
+    insert_pairs(template, rules, 10)
+}
+
+pub fn part2((template, rules): &(Template, Rules)) -> u64 {
+    insert_pairs(template, rules, 40)
+}
+
 #[cfg(test)]
 mod tests {
+    use aoc_common::differential::{assert_solutions_agree, Implementations};
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
     const SAMPLE_1: &str = "NNCB
 
 CH -> B
@@ -137,11 +196,82 @@ CN -> C";
 
     #[test]
     fn part1() {
-        assert_eq!(super::part1(SAMPLE_1), 1588);
+        assert_eq!(super::part1(&super::generator(SAMPLE_1).unwrap()), 1588);
     }
 
     #[test]
     fn part2() {
-        assert_eq!(super::part2(SAMPLE_1), 2188189693529);
+        assert_eq!(
+            super::part2(&super::generator(SAMPLE_1).unwrap()),
+            2188189693529
+        );
+    }
+
+    #[test]
+    fn generator() {
+        let error = |input| {
+            let err = super::generator(input).unwrap_err();
+            (err.line, err.column, err.message)
+        };
+
+        assert_eq!(
+            error("\n\nAB -> A"),
+            (1, 1, String::from("Expected a template"))
+        );
+        assert_eq!(
+            error("ABA\n\nAB -> A"),
+            (1, 2, String::from("Expected a rule for BA"))
+        );
+        // AB makes AC and CB, and there is no rule for CB
+        assert_eq!(
+            error("AB\n\nAB -> C\nAC -> A"),
+            (3, 1, String::from("Expected a rule for CB"))
+        );
+        assert!(super::generator("A\n\n").is_ok());
+    }
+
+    #[test]
+    fn test_samples_agree() {
+        assert_solutions_agree(crate::solutions().day(14).unwrap(), SAMPLE_1);
+    }
+
+    /// A template over a few elements, with a rule for every pair of them
+    fn polymer() -> impl Strategy<Value = String> {
+        (2..=5_usize).prop_flat_map(|elements| {
+            let elements = &"BCHNOS"[..elements];
+            let pairs = elements
+                .chars()
+                .cartesian_product(elements.chars())
+                .collect::<Vec<_>>();
+            let element = move || proptest::sample::select(elements.chars().collect::<Vec<_>>());
+
+            (
+                proptest::collection::vec(element(), 2..8),
+                proptest::collection::vec(element(), pairs.len()),
+            )
+                .prop_map(move |(template, insertions)| {
+                    let rules = pairs
+                        .iter()
+                        .zip(insertions)
+                        .map(|((left, right), insertion)| {
+                            format!("{}{} -> {}", left, right, insertion)
+                        })
+                        .join("\n");
+
+                    format!("{}\n\n{}", template.into_iter().collect::<String>(), rules)
+                })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_insertions_agree(input in polymer(), steps in 0..=10_usize) {
+            let (template, rules) = super::generator(&input).unwrap();
+
+            Implementations::new()
+                .with("naive", |steps| super::insert_naive(&template, &rules, *steps))
+                .with("pairs", |steps| super::insert_pairs(&template, &rules, *steps))
+                .assert_agree(&steps);
+        }
     }
 }
@@ -1,98 +1,62 @@
-use std::collections::HashMap;
-
+use aoc_common::grid::Grid;
+use aoc_common::input::InputError;
 use petgraph::algo::astar::astar;
 use petgraph::graphmap::DiGraphMap;
 
-pub fn generator(input: &str) -> HashMap<(i32, i32), u64> {
-    input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            line.chars()
-                .enumerate()
-                .map(move |(x, c)| ((x as i32, y as i32), c.to_digit(10).unwrap() as u64))
-        })
-        .collect()
-}
+const DAY: u8 = 15;
 
-pub fn part1(input: &str) -> u64 {
-    let nodes = generator(input);
+pub fn generator(input: &str) -> Result<Grid<u64>, InputError> {
+    // Risk levels run from 1 to 9
+    let grid = Grid::parse(input, |c| {
+        c.to_digit(10).filter(|risk| *risk > 0).map(u64::from)
+    })
+    .map_err(|err| InputError::grid(DAY, input, err))?;
 
-    let mut graph = DiGraphMap::new();
+    if grid.width() == 0 || grid.height() == 0 {
+        return Err(InputError::end(DAY, input, "Expected a map of risk levels"));
+    }
 
-    for location in nodes.keys() {
-        for (dx, dy) in [(0, 1), (1, 0), (-1, 0), (0, -1)] {
-            let neighbour = (location.0 + dx, location.1 + dy);
+    Ok(grid)
+}
+
+fn lowest_total_risk(nodes: &Grid<u64>) -> u64 {
+    let mut graph = DiGraphMap::new();
 
-            if let Some(weight) = nodes.get(&neighbour) {
-                graph.add_edge(*location, neighbour, *weight);
-            }
+    for location in nodes.positions() {
+        for neighbour in nodes.neighbours(location) {
+            graph.add_edge(location, neighbour, nodes[neighbour]);
         }
     }
 
-    let max = graph.nodes().max().unwrap();
+    let max = (nodes.width() - 1, nodes.height() - 1);
 
     astar(
         &graph,
         (0, 0),
         |finish| finish == max,
         |(_, _, weight)| *weight,
-        |(x, y)| ((max.0 - x) + (max.1 - y)).unsigned_abs() as u64,
+        |(x, y)| ((max.0 - x) + (max.1 - y)) as u64,
     )
     .unwrap()
     .0
 }
 
-pub fn part2(input: &str) -> u64 {
-    let mut nodes = generator(input);
-
-    let min = nodes.keys().min().unwrap().to_owned();
-    let max = nodes.keys().max().unwrap().to_owned();
-    let (span_x, span_y) = (max.0 - min.0 + 1, max.1 - min.1 + 1);
-
-    // Stretch horizontally
-    for ((x, y), weight) in nodes.clone() {
-        for i in 1..5 {
-            nodes.insert(
-                (x + (span_x * i), y),
-                ((((weight as i32 + i) - 1) % 9) + 1) as u64,
-            );
-        }
-    }
-
-    // Stretch vertically
-    for ((x, y), weight) in nodes.clone() {
-        for i in 1..5 {
-            nodes.insert(
-                (x, y + (span_y * i)),
-                ((((weight as i32 + i) - 1) % 9) + 1) as u64,
-            );
-        }
-    }
-
-    let mut graph = DiGraphMap::new();
+pub fn part1(input: &Grid<u64>) -> u64 {
+    lowest_total_risk(input)
+}
 
-    for location in nodes.keys() {
-        for (dx, dy) in [(0, 1), (1, 0), (-1, 0), (0, -1)] {
-            let neighbour = (location.0 + dx, location.1 + dy);
+pub fn part2(tile: &Grid<u64>) -> u64 {
+    let (span_x, span_y) = (tile.width(), tile.height());
 
-            if let Some(weight) = nodes.get(&neighbour) {
-                graph.add_edge(*location, neighbour, *weight);
-            }
-        }
+    // Stretch five times in each direction, with risk increasing by one for each tile moved
+    let mut nodes = Grid::new(span_x * 5, span_y * 5, 0);
+    for (x, y) in nodes.positions().collect::<Vec<_>>() {
+        let weight = tile[(x % span_x, y % span_y)];
+        let i = (x / span_x + y / span_y) as u64;
+        nodes[(x, y)] = ((weight + i - 1) % 9) + 1;
     }
 
-    let max = graph.nodes().max().unwrap();
-
-    astar(
-        &graph,
-        (0, 0),
-        |finish| finish == max,
-        |(_, _, weight)| *weight,
-        |(x, y)| ((max.0 - x) + (max.1 - y)).unsigned_abs() as u64,
-    )
-    .unwrap()
-    .0
+    lowest_total_risk(&nodes)
 }
 
 #[cfg(test)]
@@ -112,11 +76,18 @@ mod tests {
 
     #[test]
     fn sample1() {
-        assert_eq!(part1(SAMPLE), 40);
+        assert_eq!(part1(&generator(SAMPLE).unwrap()), 40);
     }
 
     #[test]
     fn sample2() {
-        assert_eq!(part2(SAMPLE), 315);
+        assert_eq!(part2(&generator(SAMPLE).unwrap()), 315);
+    }
+
+    #[test]
+    fn generator_errors() {
+        let error = |input| generator(input).unwrap_err().message;
+        assert_eq!(error("19\n10"), "unexpected '0'");
+        assert_eq!(error(""), "Expected a map of risk levels");
     }
 }
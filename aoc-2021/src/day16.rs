@@ -1,15 +1,31 @@
 use std::iter;
 
+use aoc_common::input::InputError;
 use bitvec::bits;
 use bitvec::prelude::*;
+use bitvec_nom2::BSlice;
+use hex::FromHexError;
 use nom::branch::alt;
 use nom::bytes::complete::{tag, take};
-use nom::combinator::map;
+use nom::combinator::{all_consuming, map};
+use nom::error::{Error, ErrorKind};
 use nom::multi::many_m_n;
 use nom::multi::{many0, many_till};
 use nom::sequence::{preceded, tuple};
-use nom::IResult;
-use bitvec_nom::BSlice;
+use nom::{IResult, Offset};
+
+const DAY: u8 = 16;
+
+type Bits<'a> = BSlice<'a, u8, Msb0>;
+
+/// How deep packets may be nested, well beyond any real transmission, so that a malicious one
+/// can't exhaust the stack
+const MAX_DEPTH: usize = 256;
+
+// Ways a transmission can be wrong, other than ending early, as nom sees them
+const LITERAL_TOO_LARGE: ErrorKind = ErrorKind::TooLarge;
+const WRONG_SUB_PACKETS: ErrorKind = ErrorKind::Verify;
+const TOO_DEEP: ErrorKind = ErrorKind::Fail;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Packet {
@@ -88,44 +104,52 @@ pub enum Length {
     Packets(u16),
 }
 
-fn parse_header(input: BSlice<Msb0, u8>) -> IResult<BSlice<Msb0, u8>, (u8, u8)> {
+fn parse_header(input: Bits) -> IResult<Bits, (u8, u8)> {
     tuple((
-        map(take(3usize), |bits: BSlice<Msb0, u8>| bits.0.load_be()),
-        map(take(3usize), |bits: BSlice<Msb0, u8>| bits.0.load_be()),
+        map(take(3usize), |bits: Bits| bits.0.load_be()),
+        map(take(3usize), |bits: Bits| bits.0.load_be()),
     ))(input)
 }
 
-fn parse_length(input: BSlice<Msb0, u8>) -> IResult<BSlice<Msb0, u8>, Length> {
+fn parse_length(input: Bits) -> IResult<Bits, Length> {
     alt((
         map(
-            preceded(tag(BSlice(bits![0])), take(15usize)),
-            |bits: BSlice<Msb0, u8>| Length::Bits(bits.0.load_be()),
+            preceded(tag(BSlice(bits![u8, Msb0; 0])), take(15usize)),
+            |bits: Bits| Length::Bits(bits.0.load_be()),
         ),
         map(
-            preceded(tag(BSlice(bits![1])), take(11usize)),
-            |bits: BSlice<Msb0, u8>| Length::Packets(bits.0.load_be()),
+            preceded(tag(BSlice(bits![u8, Msb0; 1])), take(11usize)),
+            |bits: Bits| Length::Packets(bits.0.load_be()),
         ),
     ))(input)
 }
 
-fn parse_literal(input: BSlice<Msb0, u8>) -> IResult<BSlice<Msb0, u8>, u64> {
+fn parse_literal(input: Bits) -> IResult<Bits, u64> {
     let (input, (continues, terminal)) = many_till(
-        preceded(tag(BSlice(bits![1])), take(4usize)),
-        preceded(tag(BSlice(bits![0])), take(4usize)),
+        preceded(tag(BSlice(bits![u8, Msb0; 1])), take(4usize)),
+        preceded(tag(BSlice(bits![u8, Msb0; 0])), take(4usize)),
     )(input)?;
 
-    let mut bits = BitVec::<Msb0, u8>::new();
+    let mut bits = BitVec::<u8, Msb0>::new();
 
     for slice in continues {
-        bits.extend(slice.0);
+        bits.extend_from_bitslice(slice.0);
+    }
+    bits.extend_from_bitslice(terminal.0);
+
+    if bits.len() > 64 {
+        return Err(nom::Err::Failure(Error::new(input, LITERAL_TOO_LARGE)));
     }
-    bits.extend(terminal.0);
 
     Ok((input, bits.load_be()))
 }
 
-fn parse_packet(input: BSlice<Msb0, u8>) -> IResult<BSlice<Msb0, u8>, Packet> {
-    let (input, header) = parse_header(input)?;
+fn parse_packet(packet: Bits, depth: usize) -> IResult<Bits, Packet> {
+    if depth > MAX_DEPTH {
+        return Err(nom::Err::Failure(Error::new(packet, TOO_DEEP)));
+    }
+
+    let (input, header) = parse_header(packet)?;
 
     match header {
         (version, 4) => {
@@ -140,10 +164,19 @@ fn parse_packet(input: BSlice<Msb0, u8>) -> IResult<BSlice<Msb0, u8>, Packet> {
         }
         (version, type_id) => {
             let (input, length) = parse_length(input)?;
-            let (input, packets) = parse_packets(input, length)?;
+            let (rest, packets) = parse_packets(input, length, depth + 1)?;
+
+            // Comparisons are between two packets, and the other operators need at least one
+            let expected = match type_id {
+                5..=7 => 2..=2,
+                _ => 1..=usize::MAX,
+            };
+            if !expected.contains(&packets.len()) {
+                return Err(nom::Err::Failure(Error::new(packet, WRONG_SUB_PACKETS)));
+            }
 
             Ok((
-                input,
+                rest,
                 Packet {
                     version,
                     content: match type_id {
@@ -162,52 +195,84 @@ fn parse_packet(input: BSlice<Msb0, u8>) -> IResult<BSlice<Msb0, u8>, Packet> {
     }
 }
 
-fn parse_packets(
-    input: BSlice<Msb0, u8>,
-    length: Length,
-) -> IResult<BSlice<Msb0, u8>, Vec<Packet>> {
+fn parse_packets(input: Bits, length: Length, depth: usize) -> IResult<Bits, Vec<Packet>> {
+    let packet = |input| parse_packet(input, depth);
+
     match length {
         Length::Bits(l) => {
             let (input, packet_data) = take(l as usize)(input)?;
-            let (_, packets) = many0(parse_packet)(packet_data)?;
+            let (_, packets) = all_consuming(many0(packet))(packet_data)?;
             Ok((input, packets))
         }
-        Length::Packets(l) => many_m_n(l as usize, l as usize, parse_packet)(input),
+        Length::Packets(l) => many_m_n(l as usize, l as usize, packet)(input),
     }
 }
 
-pub fn parse(input: &str) -> Packet {
-    let bytes = hex::decode(&input.trim_end()).unwrap();
-    let bits = BSlice(BitSlice::<Msb0, _>::from_slice(&bytes).unwrap());
-    parse_packet(bits).unwrap().1
+pub fn generator(input: &str) -> Result<Packet, InputError> {
+    let hex = input.trim_end();
+    let bytes = hex::decode(hex).map_err(|err| match err {
+        FromHexError::InvalidHexCharacter { index, .. } => {
+            InputError::at(DAY, input, &hex[index..], "expected a hexadecimal digit")
+        }
+        err => InputError::at(DAY, input, &hex[hex.len()..], err),
+    })?;
+    let bits = BSlice(bytes.view_bits::<Msb0>());
+
+    let (_, packet) = parse_packet(bits, 0).map_err(|err| {
+        let (position, message) = match err {
+            nom::Err::Incomplete(_) => (
+                bits.len(),
+                "the transmission ends part way through a packet",
+            ),
+            nom::Err::Error(err) | nom::Err::Failure(err) => (
+                bits.offset(&err.input),
+                match err.code {
+                    LITERAL_TOO_LARGE => "literal value does not fit in 64 bits",
+                    WRONG_SUB_PACKETS => "wrong number of sub-packets",
+                    TOO_DEEP => "packets are nested too deeply",
+                    _ => "the transmission ends part way through a packet",
+                },
+            ),
+        };
+
+        // Point at the hexadecimal digit the problem starts in
+        let digit = (position / 4).min(hex.len());
+        InputError::at(DAY, input, &hex[digit..], message)
+    })?;
+
+    Ok(packet)
 }
 
-pub fn part1(input: &str) -> u64 {
-    parse(input)
+pub fn part1(packet: &Packet) -> u64 {
+    packet
+        .clone()
         .into_iter()
         .map(|packet| packet.version as u64)
         .sum()
 }
 
-pub fn part2(input: &str) -> u64 {
-    parse(input).into()
+pub fn part2(packet: &Packet) -> u64 {
+    packet.clone().into()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::strategy::{garbage, malformed};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
 
     #[test]
-    fn parse() {
+    fn generator() {
         assert_eq!(
-            super::parse("D2FE28"),
+            super::generator("D2FE28").unwrap(),
             Packet {
                 version: 6,
                 content: PacketContent::Literal(2021)
             }
         );
         assert_eq!(
-            super::parse("38006F45291200"),
+            super::generator("38006F45291200").unwrap(),
             Packet {
                 version: 1,
                 content: PacketContent::LessThan(vec![
@@ -223,7 +288,7 @@ mod tests {
             }
         );
         assert_eq!(
-            super::parse("9C0141080250320F1802104A08"),
+            super::generator("9C0141080250320F1802104A08").unwrap(),
             Packet {
                 version: 4,
                 content: PacketContent::EqualTo(vec![
@@ -260,21 +325,188 @@ mod tests {
 
     #[test]
     fn part1() {
-        assert_eq!(super::part1("8A004A801A8002F478"), 16);
-        assert_eq!(super::part1("620080001611562C8802118E34"), 12);
-        assert_eq!(super::part1("C0015000016115A2E0802F182340"), 23);
-        assert_eq!(super::part1("A0016C880162017C3686B18A3D4780"), 31);
+        assert_eq!(
+            super::part1(&super::generator("8A004A801A8002F478").unwrap()),
+            16
+        );
+        assert_eq!(
+            super::part1(&super::generator("620080001611562C8802118E34").unwrap()),
+            12
+        );
+        assert_eq!(
+            super::part1(&super::generator("C0015000016115A2E0802F182340").unwrap()),
+            23
+        );
+        assert_eq!(
+            super::part1(&super::generator("A0016C880162017C3686B18A3D4780").unwrap()),
+            31
+        );
     }
 
     #[test]
     fn part2() {
-        assert_eq!(super::part2("C200B40A82"), 3);
-        assert_eq!(super::part2("04005AC33890"), 54);
-        assert_eq!(super::part2("880086C3E88112"), 7);
-        assert_eq!(super::part2("CE00C43D881120"), 9);
-        assert_eq!(super::part2("D8005AC2A8F0"), 1);
-        assert_eq!(super::part2("F600BC2D8F"), 0);
-        assert_eq!(super::part2("9C005AC2F8F0"), 0);
-        assert_eq!(super::part2("9C0141080250320F1802104A08"), 1);
+        assert_eq!(super::part2(&super::generator("C200B40A82").unwrap()), 3);
+        assert_eq!(super::part2(&super::generator("04005AC33890").unwrap()), 54);
+        assert_eq!(
+            super::part2(&super::generator("880086C3E88112").unwrap()),
+            7
+        );
+        assert_eq!(
+            super::part2(&super::generator("CE00C43D881120").unwrap()),
+            9
+        );
+        assert_eq!(super::part2(&super::generator("D8005AC2A8F0").unwrap()), 1);
+        assert_eq!(super::part2(&super::generator("F600BC2D8F").unwrap()), 0);
+        assert_eq!(super::part2(&super::generator("9C005AC2F8F0").unwrap()), 0);
+        assert_eq!(
+            super::part2(&super::generator("9C0141080250320F1802104A08").unwrap()),
+            1
+        );
+    }
+
+    #[test]
+    fn generator_errors() {
+        let message = |input| super::generator(input).unwrap_err().message;
+
+        assert_eq!(message("D2FE2X"), "expected a hexadecimal digit");
+        assert_eq!(message("D2FE2"), "Odd number of digits");
+        assert_eq!(
+            message("D2FE"),
+            "the transmission ends part way through a packet"
+        );
+        // A literal of seventeen groups
+        assert_eq!(
+            message("93FFFFFFFFFFFFFFFFFFFDE0"),
+            "literal value does not fit in 64 bits"
+        );
+        // Less than, with a single sub-packet
+        let err = super::generator("3A004408").unwrap_err();
+        assert_eq!(err.message, "wrong number of sub-packets");
+        assert_eq!(err.column, 1);
+
+        // Each a sum of one more, ending with a literal
+        let mut bits = BitVec::<u8, Msb0>::new();
+        for _ in 0..=MAX_DEPTH {
+            push(&mut bits, 0b0010001, 7);
+            push(&mut bits, 1, 11);
+        }
+        push(&mut bits, 0b00010000001, 11);
+        bits.resize(bits.len().div_ceil(8) * 8, false);
+        assert_eq!(
+            message(&hex::encode(bits.into_vec())),
+            "packets are nested too deeply"
+        );
+    }
+
+    fn push(bits: &mut BitVec<u8, Msb0>, value: u64, width: usize) {
+        for bit in (0..width).rev() {
+            bits.push((value >> bit) & 1 == 1);
+        }
+    }
+
+    /// Writes a packet as the transmission would, giving the length of even versions' sub-packets
+    /// in bits and of odd versions' as a count, so both are read
+    fn encode(packet: &Packet, bits: &mut BitVec<u8, Msb0>) {
+        use PacketContent::*;
+
+        push(bits, packet.version.into(), 3);
+
+        let (type_id, packets) = match &packet.content {
+            Literal(value) => {
+                push(bits, 4, 3);
+
+                let groups = (64 - value.leading_zeros() as usize).div_ceil(4).max(1);
+                for group in (0..groups).rev() {
+                    bits.push(group > 0);
+                    push(bits, (value >> (4 * group)) & 0xf, 4);
+                }
+                return;
+            }
+            Sum(packets) => (0, packets),
+            Product(packets) => (1, packets),
+            Minimum(packets) => (2, packets),
+            Maximum(packets) => (3, packets),
+            GreaterThan(packets) => (5, packets),
+            LessThan(packets) => (6, packets),
+            EqualTo(packets) => (7, packets),
+        };
+        push(bits, type_id, 3);
+
+        let mut contents = BitVec::new();
+        for packet in packets {
+            encode(packet, &mut contents);
+        }
+
+        if packet.version.is_multiple_of(2) {
+            bits.push(false);
+            push(bits, contents.len() as u64, 15);
+        } else {
+            bits.push(true);
+            push(bits, packets.len() as u64, 11);
+        }
+        bits.extend_from_bitslice(&contents);
+    }
+
+    fn to_hex(packet: &Packet) -> String {
+        let mut bits = BitVec::new();
+        encode(packet, &mut bits);
+        bits.resize(bits.len().div_ceil(8) * 8, false);
+
+        hex::encode_upper(bits.into_vec())
+    }
+
+    fn packet() -> impl Strategy<Value = Packet> {
+        let literal = (0..8_u8, any::<u64>()).prop_map(|(version, value)| Packet {
+            version,
+            content: PacketContent::Literal(value),
+        });
+
+        literal.prop_recursive(4, 32, 4, |packet| {
+            prop_oneof![
+                (0..8_u8, 0..4, vec(packet.clone(), 1..4)).prop_map(
+                    |(version, operator, packets)| {
+                        Packet {
+                            version,
+                            content: match operator {
+                                0 => PacketContent::Sum(packets),
+                                1 => PacketContent::Product(packets),
+                                2 => PacketContent::Minimum(packets),
+                                _ => PacketContent::Maximum(packets),
+                            },
+                        }
+                    }
+                ),
+                (0..8_u8, 0..3, vec(packet, 2)).prop_map(|(version, comparison, packets)| {
+                    Packet {
+                        version,
+                        content: match comparison {
+                            0 => PacketContent::GreaterThan(packets),
+                            1 => PacketContent::LessThan(packets),
+                            _ => PacketContent::EqualTo(packets),
+                        },
+                    }
+                }),
+            ]
+        })
+    }
+
+    #[test]
+    fn encode_sample() {
+        let packet = super::generator("9C0141080250320F1802104A08").unwrap();
+        assert_eq!(super::generator(&to_hex(&packet)), Ok(packet));
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip(packet in packet()) {
+            prop_assert_eq!(super::generator(&to_hex(&packet)), Ok(packet));
+        }
+
+        #[test]
+        fn generator_garbage(
+            input in prop_oneof![malformed(packet().prop_map(|p| to_hex(&p))), garbage()]
+        ) {
+            let _ = super::generator(&input);
+        }
     }
 }
@@ -1,5 +1,7 @@
-use std::{cmp::Ordering, ops::RangeInclusive};
+use std::ops::RangeInclusive;
 
+use aoc_common::geometry::{Bounds, Point, Vector};
+use aoc_common::input::{parse_all, InputError};
 use nom::{
     branch::alt,
     bytes::complete::{tag, take_while1},
@@ -8,10 +10,16 @@ use nom::{
     AsChar, IResult,
 };
 
+const DAY: u8 = 17;
+
 type TargetRange = RangeInclusive<i32>;
-type Target = (TargetRange, TargetRange);
-type Velocity = (i32, i32);
-type Position = (i32, i32);
+type Target = Bounds<i32>;
+type Velocity = Vector<i32>;
+type Position = Point<i32>;
+
+/// The furthest the target is from the probe in either direction, so trying every velocity
+/// which could reach it stays quick
+const MAX_DISTANCE: i32 = 500;
 
 fn parse_i32(input: &str) -> IResult<&str, i32> {
     let (input, int) = map_res(
@@ -32,37 +40,58 @@ fn parse_target_range(input: &str) -> IResult<&str, TargetRange> {
 }
 
 fn parse_target(input: &str) -> IResult<&str, Target> {
-    let (input, targets) = preceded(
+    let (input, (x, y)) = preceded(
         tag("target area: "),
         separated_pair(parse_target_range, tag(", "), parse_target_range),
     )(input)?;
 
-    Ok((input, targets))
+    Ok((
+        input,
+        Bounds::new(
+            Point::new(*x.start(), *y.start()),
+            Point::new(*x.end(), *y.end()),
+        ),
+    ))
 }
 
-fn step(position: &mut Position, velocity: &mut Velocity) {
-    position.0 += velocity.0;
-    position.1 += velocity.1;
+pub fn generator(input: &str) -> Result<Target, InputError> {
+    let target = parse_all(DAY, input, input, parse_target)?;
+    let Target { min, max } = target;
+
+    let right = (1..=MAX_DISTANCE).contains(&min.x) && (min.x..=MAX_DISTANCE).contains(&max.x);
+    let below = (-MAX_DISTANCE..0).contains(&min.y) && (min.y..0).contains(&max.y);
+    if !(right && below) {
+        return Err(InputError::at(
+            DAY,
+            input,
+            input,
+            format!(
+                "Expected a target below and to the right of the probe, at most {} away",
+                MAX_DISTANCE
+            ),
+        ));
+    }
 
-    match velocity.0.cmp(&0) {
-        Ordering::Greater => velocity.0 -= 1,
-        Ordering::Equal => {}
-        Ordering::Less => velocity.0 += 1,
-    };
+    Ok(target)
+}
+
+fn step(position: &mut Position, velocity: &mut Velocity) {
+    *position += *velocity;
 
-    velocity.1 -= 1;
+    velocity.x -= velocity.x.signum();
+    velocity.y -= 1;
 }
 
 fn in_target(position: &Position, target: &Target) -> bool {
-    target.0.contains(&position.0) && target.1.contains(&position.1)
+    target.contains(position)
 }
 
 fn past_target(position: &Position, target: &Target) -> bool {
-    &position.0 > target.0.end() || &position.1 < target.1.start()
+    position.x > target.max.x || position.y < target.min.y
 }
 
 fn positions(velocity: Velocity, target: &Target) -> Vec<Position> {
-    let mut position = (0, 0);
+    let mut position = Point::origin();
     let mut velocity = velocity;
 
     let mut positions = Vec::new();
@@ -76,16 +105,14 @@ fn positions(velocity: Velocity, target: &Target) -> Vec<Position> {
     positions
 }
 
-pub fn part1(input: &str) -> i32 {
-    let target = parse_target(input).unwrap().1;
-
+pub fn part1(target: &Target) -> i32 {
     let mut max_y = i32::MIN;
 
-    for x in 0..=*target.0.end() {
-        for y in -target.1.start().abs()..=target.1.start().abs() {
-            let positions = positions((x, y), &target);
-            if positions.iter().any(|p| in_target(p, &target)) {
-                let y_height = positions.iter().max_by(|x, y| x.1.cmp(&y.1)).unwrap().1;
+    for x in 0..=target.max.x {
+        for y in -target.min.y.abs()..=target.min.y.abs() {
+            let positions = positions(Vector::new(x, y), target);
+            if positions.iter().any(|p| in_target(p, target)) {
+                let y_height = positions.iter().map(|p| p.y).max().unwrap();
 
                 if y_height > max_y {
                     max_y = y_height;
@@ -97,15 +124,13 @@ pub fn part1(input: &str) -> i32 {
     max_y
 }
 
-pub fn part2(input: &str) -> u64 {
-    let target = parse_target(input).unwrap().1;
-
+pub fn part2(target: &Target) -> u64 {
     let mut velocities = 0;
 
-    for x in 0..=*target.0.end() {
-        for y in -target.1.start().abs()..=target.1.start().abs() {
-            let positions = positions((x, y), &target);
-            if positions.iter().any(|p| in_target(p, &target)) {
+    for x in 0..=target.max.x {
+        for y in -target.min.y.abs()..=target.min.y.abs() {
+            let positions = positions(Vector::new(x, y), target);
+            if positions.iter().any(|p| in_target(p, target)) {
                 velocities += 1;
             }
         }
@@ -120,11 +145,28 @@ mod tests {
 
     #[test]
     fn part1() {
-        assert_eq!(super::part1(SAMPLE), 45);
+        assert_eq!(super::part1(&super::generator(SAMPLE).unwrap()), 45);
     }
 
     #[test]
     fn part2() {
-        assert_eq!(super::part2(SAMPLE), 112);
+        assert_eq!(super::part2(&super::generator(SAMPLE).unwrap()), 112);
+    }
+
+    #[test]
+    fn generator() {
+        assert!(super::generator(SAMPLE).is_ok());
+
+        for target in [
+            "target area: x=-30..-20, y=-10..-5",
+            "target area: x=20..30, y=5..10",
+            "target area: x=30..20, y=-10..-5",
+            "target area: x=20..30, y=-2147483648..-5",
+        ] {
+            assert_eq!(
+                super::generator(target).unwrap_err().message,
+                "Expected a target below and to the right of the probe, at most 500 away"
+            );
+        }
     }
 }
@@ -2,10 +2,13 @@ use std::iter::Sum;
 use std::ops::{Add, RangeInclusive};
 use std::str::FromStr;
 
+use aoc_common::input::InputError;
 use itertools::Itertools;
 
+const DAY: u8 = 18;
+
 #[derive(Clone, Debug, PartialEq)]
-struct Snailfish(String);
+pub struct Snailfish(String);
 
 impl Snailfish {
     fn find_deep(&self) -> Option<usize> {
@@ -123,7 +126,7 @@ impl Snailfish {
             let value = self.regular(large);
 
             self.0
-                .replace_range(range, &format!("[{},{}]", value / 2, (value + 1) / 2));
+                .replace_range(range, &format!("[{},{}]", value / 2, value.div_ceil(2)));
             true
         } else {
             false
@@ -185,23 +188,75 @@ impl Sum for Snailfish {
     }
 }
 
-pub fn part1(input: &str) -> u64 {
-    input
-        .lines()
-        .map(|l| Snailfish(l.to_owned()))
-        .sum::<Snailfish>()
-        .magnitude()
+/// Checks for a pair or a single digit at the start of `text`, inside `depth` other pairs,
+/// returning what follows it. Reducing only explodes pairs inside four others, so numbers are
+/// expected to be nested no deeper than that to begin with.
+fn element(text: &str, depth: usize) -> Result<&str, (&str, &'static str)> {
+    if let Some(rest) = text.strip_prefix('[') {
+        if depth == 4 {
+            return Err((text, "Expected pairs nested at most four deep"));
+        }
+
+        let rest = element(rest, depth + 1)?;
+        let rest = rest.strip_prefix(',').ok_or((rest, "Expected a comma"))?;
+        let rest = element(rest, depth + 1)?;
+        rest.strip_prefix(']')
+            .ok_or((rest, "Expected a closing bracket"))
+    } else if text.starts_with(|c: char| c.is_ascii_digit()) {
+        match &text[1..] {
+            rest if rest.starts_with(|c: char| c.is_ascii_digit()) => {
+                Err((text, "Expected a single digit"))
+            }
+            rest => Ok(rest),
+        }
+    } else {
+        Err((text, "Expected a pair or a digit"))
+    }
 }
 
-pub fn part2(input: &str) -> u64 {
-    input
+pub fn generator(input: &str) -> Result<Vec<Snailfish>, InputError> {
+    let numbers = input
         .lines()
-        .map(|l| Snailfish(l.to_owned()))
+        .map(|line| {
+            let rest = if line.starts_with('[') {
+                element(line, 0)
+            } else {
+                Err((line, "Expected a pair"))
+            };
+
+            match rest {
+                Ok("") => Ok(Snailfish(line.to_owned())),
+                Ok(rest) => Err(InputError::at(
+                    DAY,
+                    input,
+                    rest,
+                    "Unexpected trailing input",
+                )),
+                Err((text, message)) => Err(InputError::at(DAY, input, text, message)),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if numbers.is_empty() {
+        return Err(InputError::end(DAY, input, "Expected a snailfish number"));
+    }
+
+    Ok(numbers)
+}
+
+pub fn part1(numbers: &[Snailfish]) -> u64 {
+    numbers.iter().cloned().sum::<Snailfish>().magnitude()
+}
+
+/// Needs at least two numbers to add together
+pub fn part2(numbers: &[Snailfish]) -> Option<u64> {
+    numbers
+        .iter()
+        .cloned()
         .tuple_combinations()
         .flat_map(|(a, b)| [a.clone() + b.clone(), b + a])
         .map(|sum| sum.magnitude())
         .max()
-        .unwrap()
 }
 
 #[cfg(test)]
@@ -446,13 +501,49 @@ mod tests {
 [[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
 [[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]";
 
+    #[test]
+    fn generator() {
+        assert_eq!(super::generator(SAMPLE).unwrap().len(), 10);
+
+        let error = |input| {
+            let err = super::generator(input).unwrap_err();
+            (err.column, err.message)
+        };
+        assert_eq!(error(""), (1, String::from("Expected a snailfish number")));
+        assert_eq!(error("1"), (1, String::from("Expected a pair")));
+        assert_eq!(
+            error("[1,x]"),
+            (4, String::from("Expected a pair or a digit"))
+        );
+        assert_eq!(
+            error("[[1,2],3"),
+            (9, String::from("Expected a closing bracket"))
+        );
+        assert_eq!(error("[1 2]"), (3, String::from("Expected a comma")));
+        assert_eq!(
+            error("[12,3]"),
+            (2, String::from("Expected a single digit"))
+        );
+        assert_eq!(
+            error("[1,2]]"),
+            (6, String::from("Unexpected trailing input"))
+        );
+        assert_eq!(
+            error("[[[[[1,2],3],4],5],6]"),
+            (5, String::from("Expected pairs nested at most four deep"))
+        );
+    }
+
     #[test]
     fn part1() {
-        assert_eq!(super::part1(SAMPLE), 4140);
+        let numbers = super::generator(SAMPLE).unwrap();
+        assert_eq!(super::part1(&numbers), 4140);
     }
 
     #[test]
     fn part2() {
-        assert_eq!(super::part2(SAMPLE), 3993);
+        let numbers = super::generator(SAMPLE).unwrap();
+        assert_eq!(super::part2(&numbers), Some(3993));
+        assert_eq!(super::part2(&numbers[..1]), None);
     }
 }
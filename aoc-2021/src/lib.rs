@@ -0,0 +1,40 @@
+mod day01;
+mod day02;
+mod day03;
+mod day04;
+mod day05;
+mod day06;
+mod day07;
+mod day08;
+mod day09;
+mod day10;
+mod day11;
+mod day12;
+mod day13;
+mod day14;
+mod day15;
+mod day16;
+mod day17;
+mod day18;
+
+aoc_common::solutions! {
+  year 2021;
+  day01 :generator? => part1, part2;
+  day02 :generator? => part1, part2;
+  day03 :generator? => part1?, part2?;
+  day04 :generator? => part1?, part2?;
+  day05 :generator? => part1, part2;
+  day06 :generator? => part1, part2;
+  day07 :generator? => part1?, part2?;
+  day08 :generator? => part1, part2?;
+  day09 :generator? => part1, part2;
+  day10 :generator? => part1?, part2?;
+  day11 :generator? => part1?, part2?;
+  day12 :generator? => part1?, part2?;
+  day13 :generator? => part1, part2?;
+  day14 :generator? => part1, part1_pairs, part2;
+  day15 :generator? => part1, part2;
+  day16 :generator? => part1, part2;
+  day17 :generator? => part1, part2;
+  day18 :generator? => part1, part2?;
+}
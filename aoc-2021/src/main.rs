@@ -1,40 +1,3 @@
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-//mod day16;
-mod day17;
-mod day18;
-
-aoc_main::main! {
-  year 2021;
-  day01             => part1, part2;
-  day02             => part1, part2;
-  day03             => part1, part2;
-  day04             => part1, part2;
-  day05             => part1, part2;
-  day06             => part1, part2;
-  day07             => part1, part2;
-  day08             => part1, part2;
-  day09             => part1, part2;
-  day10             => part1, part2;
-  day11             => part1, part2;
-  day12             => part1, part2;
-  day13             => part1, part2;
-  day14             => part1, part2;
-  day15             => part1, part2;
-  //day16             => part1, part2;
-  day17             => part1, part2;
-  day18             => part1, part2;
+fn main() {
+    aoc_2021::cli::run()
 }
@@ -1,32 +1,28 @@
-use std::num::ParseIntError;
-
+use aoc_common::input::{parse_each, InputError};
 use itertools::Itertools;
 
-fn generator(input: &str) -> Result<Vec<Vec<u32>>, ParseIntError> {
+const DAY: u8 = 1;
+
+pub fn generator(input: &str) -> Result<Vec<Vec<u32>>, InputError> {
     input
         .split("\n\n")
-        .map(|chunk| chunk.lines().map(str::parse).collect())
+        .map(|chunk| parse_each(DAY, input, chunk.lines(), str::parse))
         .collect()
 }
 
-pub fn part1(input: &str) -> usize {
-    generator(input)
-        .         unwrap()
-        .into_iter()
-        .map(|calories| calories.into_iter().sum::<u32>())
-        .max()
-        .unwrap() as usize
+/// The calories each elf is carrying
+fn totals(input: &[Vec<u32>]) -> impl Iterator<Item = u64> + '_ {
+    input
+        .iter()
+        .map(|calories| calories.iter().copied().map(u64::from).sum())
+}
+
+pub fn part1(input: &[Vec<u32>]) -> u64 {
+    totals(input).max().unwrap()
 }
 
-pub fn part2(input: &str) -> usize {
-    generator(input)
-        .unwrap()
-        .into_iter()
-        .map(|calories| calories.into_iter().sum::<u32>())
-        .sorted()
-        .rev()
-        .take(3)
-        .sum::<u32>() as usize
+pub fn part2(input: &[Vec<u32>]) -> u64 {
+    totals(input).sorted().rev().take(3).sum()
 }
 
 #[cfg(test)]
@@ -50,11 +46,15 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(SAMPLE), 24_000);
+        assert_eq!(part1(&generator(SAMPLE).unwrap()), 24_000);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(SAMPLE), 45_000);
+        assert_eq!(part2(&generator(SAMPLE).unwrap()), 45_000);
+        assert_eq!(
+            part2(&generator("4294967295\n4294967295").unwrap()),
+            8_589_934_590
+        );
     }
 }
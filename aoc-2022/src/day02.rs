@@ -1,37 +1,88 @@
+use aoc_common::input::{parse_lines, InputError};
+
+const DAY: u8 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Opponent {
+    A,
+    B,
+    C,
+}
+
+/// What the second column says to do, which each part reads differently
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Response {
+    X,
+    Y,
+    Z,
+}
+
+type Round = (Opponent, Response);
+
+fn round(line: &str) -> Result<Round, String> {
+    match line.as_bytes() {
+        [opponent, b' ', response] => {
+            let opponent = match opponent {
+                b'A' => Opponent::A,
+                b'B' => Opponent::B,
+                b'C' => Opponent::C,
+                _ => return Err(String::from("Expected A, B or C to start the round")),
+            };
+            let response = match response {
+                b'X' => Response::X,
+                b'Y' => Response::Y,
+                b'Z' => Response::Z,
+                _ => return Err(String::from("Expected X, Y or Z to end the round")),
+            };
+
+            Ok((opponent, response))
+        }
+        _ => Err(String::from("Expected a round like \"A Y\"")),
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<Round>, InputError> {
+    parse_lines(DAY, input, round)
+}
+
 #[allow(clippy::identity_op)]
-pub fn part1(input: &str) -> usize {
+pub fn part1(input: &[Round]) -> usize {
+    use Opponent::*;
+    use Response::*;
+
     input
-        .lines()
-        .map(|event| match event {
-            "A X" => 1 + 3,
-            "A Y" => 2 + 6,
-            "A Z" => 3 + 0,
-            "B X" => 1 + 0,
-            "B Y" => 2 + 3,
-            "B Z" => 3 + 6,
-            "C X" => 1 + 6,
-            "C Y" => 2 + 0,
-            "C Z" => 3 + 3,
-            _ => unreachable!(),
+        .iter()
+        .map(|round| match round {
+            (A, X) => 1 + 3,
+            (A, Y) => 2 + 6,
+            (A, Z) => 3 + 0,
+            (B, X) => 1 + 0,
+            (B, Y) => 2 + 3,
+            (B, Z) => 3 + 6,
+            (C, X) => 1 + 6,
+            (C, Y) => 2 + 0,
+            (C, Z) => 3 + 3,
         })
         .sum::<usize>()
 }
 
 #[allow(clippy::identity_op)]
-pub fn part2(input: &str) -> usize {
+pub fn part2(input: &[Round]) -> usize {
+    use Opponent::*;
+    use Response::*;
+
     input
-        .lines()
-        .map(|event| match event {
-            "A X" => 3 + 0,
-            "A Y" => 1 + 3,
-            "A Z" => 2 + 6,
-            "B X" => 1 + 0,
-            "B Y" => 2 + 3,
-            "B Z" => 3 + 6,
-            "C X" => 2 + 0,
-            "C Y" => 3 + 3,
-            "C Z" => 1 + 6,
-            _ => unreachable!(),
+        .iter()
+        .map(|round| match round {
+            (A, X) => 3 + 0,
+            (A, Y) => 1 + 3,
+            (A, Z) => 2 + 6,
+            (B, X) => 1 + 0,
+            (B, Y) => 2 + 3,
+            (B, Z) => 3 + 6,
+            (C, X) => 2 + 0,
+            (C, Y) => 3 + 3,
+            (C, Z) => 1 + 6,
         })
         .sum::<usize>()
 }
@@ -46,11 +97,20 @@ C Z";
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(SAMPLE), 15);
+        assert_eq!(part1(&parse(SAMPLE).unwrap()), 15);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(SAMPLE), 12);
+        assert_eq!(part2(&parse(SAMPLE).unwrap()), 12);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse("A Y\nD X").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.message, "Expected A, B or C to start the round");
+
+        assert!(parse("A Yes").is_err());
     }
 }
@@ -1,5 +1,22 @@
-pub fn parse(input: &str) -> Vec<&str> {
-    input.lines().collect()
+use aoc_common::input::{parse_lines, InputError};
+
+const DAY: u8 = 3;
+
+fn rucksack(line: &str) -> Result<&str, String> {
+    if !line.chars().all(|c| c.is_ascii_alphabetic()) {
+        Err(String::from("Items must be letters"))
+    } else if !line.len().is_multiple_of(2) {
+        Err(format!(
+            "Expected an even number of items, found {}",
+            line.len()
+        ))
+    } else {
+        Ok(line)
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<&str>, InputError> {
+    parse_lines(DAY, input, rucksack)
 }
 
 pub fn part1(input: &[&str]) -> u32 {
@@ -56,11 +73,11 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&parse(SAMPLE)), 157);
+        assert_eq!(part1(&parse(SAMPLE).unwrap()), 157);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&parse(SAMPLE)), 70);
+        assert_eq!(part2(&parse(SAMPLE).unwrap()), 70);
     }
 }
@@ -1,21 +1,37 @@
 use std::ops::RangeInclusive;
 
-pub fn parse(input: &str) -> Vec<(RangeInclusive<u8>, RangeInclusive<u8>)> {
+use aoc_common::input::InputError;
+
+const DAY: u8 = 4;
+
+type Pair = (RangeInclusive<u8>, RangeInclusive<u8>);
+
+fn range(input: &str, s: &str) -> Result<RangeInclusive<u8>, InputError> {
+    let (start, end) = s
+        .split_once('-')
+        .ok_or_else(|| InputError::at(DAY, input, s, "Expected a range of sections"))?;
+    let section = |n: &str| {
+        n.parse::<u8>()
+            .map_err(|err| InputError::at(DAY, input, n, err))
+    };
+
+    Ok(section(start)?..=section(end)?)
+}
+
+pub fn parse(input: &str) -> Result<Vec<Pair>, InputError> {
     input
         .lines()
         .map(|line| {
-            let elves = line.split_once(',').unwrap();
-            let left = elves.0.split_once('-').unwrap();
-            let right = elves.1.split_once('-').unwrap();
-            (
-                left.0.parse::<u8>().unwrap()..=left.1.parse::<u8>().unwrap(),
-                right.0.parse::<u8>().unwrap()..=right.1.parse::<u8>().unwrap(),
-            )
+            let (left, right) = line
+                .split_once(',')
+                .ok_or_else(|| InputError::at(DAY, input, line, "Expected a pair of elves"))?;
+
+            Ok((range(input, left)?, range(input, right)?))
         })
         .collect()
 }
 
-pub fn part1(input: &[(RangeInclusive<u8>, RangeInclusive<u8>)]) -> usize {
+pub fn part1(input: &[Pair]) -> usize {
     input
         .iter()
         .filter(|(left, right)| {
@@ -25,7 +41,7 @@ pub fn part1(input: &[(RangeInclusive<u8>, RangeInclusive<u8>)]) -> usize {
         .count()
 }
 
-pub fn part2(input: &[(RangeInclusive<u8>, RangeInclusive<u8>)]) -> usize {
+pub fn part2(input: &[Pair]) -> usize {
     input
         .iter()
         .filter(|(left, right)| {
@@ -50,11 +66,17 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&parse(SAMPLE)), 2);
+        assert_eq!(part1(&parse(SAMPLE).unwrap()), 2);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&parse(SAMPLE)), 4);
+        assert_eq!(part2(&parse(SAMPLE).unwrap()), 4);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse("2-4,6-8\n2-x,1-2").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 3, "x"));
     }
 }
@@ -1,5 +1,9 @@
 use std::collections::BTreeMap;
 
+use aoc_common::input::InputError;
+
+const DAY: u8 = 5;
+
 pub type Crates = BTreeMap<usize, Vec<char>>;
 
 #[derive(Debug)]
@@ -9,71 +13,149 @@ pub struct Instruction {
     to: usize,
 }
 
-pub fn parse(input: &str) -> (Crates, Vec<Instruction>) {
-    let (crate_lines, instructions) = input.split_once("\n\n").unwrap();
+impl std::str::FromStr for Instruction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |word: &str| {
+            word.parse()
+                .map_err(|_| format!("Expected a number, found {:?}", word))
+        };
+
+        match s.split_whitespace().collect::<Vec<_>>()[..] {
+            ["move", n, "from", from, "to", to] => Ok(Instruction {
+                n: number(n)?,
+                from: number(from)?,
+                to: number(to)?,
+            }),
+            _ => Err(String::from("Expected move <n> from <stack> to <stack>")),
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<(Crates, Vec<Instruction>), InputError> {
+    let (crate_lines, instructions) = input
+        .split_once("\n\n")
+        .ok_or_else(|| InputError::end(DAY, input, "Expected rearrangement instructions"))?;
+
+    // The stacks are numbered along the bottom, and start out empty
+    let mut lines = crate_lines.lines().rev();
+    let labels = lines.next().unwrap_or(crate_lines);
+    let count = labels.split_whitespace().count();
+    if !labels
+        .split_whitespace()
+        .eq((1..=count).map(|n| n.to_string()))
+    {
+        return Err(InputError::at(
+            DAY,
+            input,
+            labels,
+            "Expected the stacks numbered from 1",
+        ));
+    }
 
-    let mut crates: Crates = BTreeMap::new();
-    for crate_line in crate_lines.lines().rev().skip(1) {
+    let mut crates: Crates = (1..=count).map(|n| (n, Vec::new())).collect();
+    for crate_line in lines {
         for (idx, c) in crate_line
             .char_indices()
             .filter(|(_, c)| c.is_ascii_alphabetic())
         {
-            let idx = ((idx - 1) / 4) + 1;
-            if let Some(stack) = crates.get_mut(&idx) {
-                stack.push(c);
-            } else {
-                crates.insert(idx, vec![c]);
+            // Crates are in brackets, four columns apart
+            if idx % 4 != 1 {
+                return Err(InputError::at(
+                    DAY,
+                    input,
+                    &crate_line[idx..],
+                    "Expected a crate in brackets",
+                ));
             }
+
+            crates
+                .get_mut(&(idx / 4 + 1))
+                .ok_or_else(|| {
+                    InputError::at(
+                        DAY,
+                        input,
+                        &crate_line[idx..],
+                        "Expected a crate above a numbered stack",
+                    )
+                })?
+                .push(c);
         }
     }
 
     let instructions = instructions
         .lines()
-        .map(|i| {
-            let mut parts = i.split_whitespace();
-
-            Instruction {
-                n: parts.nth(1).unwrap().parse().unwrap(),
-                from: parts.nth(1).unwrap().parse().unwrap(),
-                to: parts.nth(1).unwrap().parse().unwrap(),
+        .map(|line| {
+            let instruction: Instruction = line
+                .parse()
+                .map_err(|err| InputError::at(DAY, input, line, err))?;
+
+            if !crates.contains_key(&instruction.from) || !crates.contains_key(&instruction.to) {
+                return Err(InputError::at(
+                    DAY,
+                    input,
+                    line,
+                    format!("Expected stacks from 1 to {}", count),
+                ));
             }
+
+            Ok(instruction)
         })
-        .collect::<Vec<_>>();
+        .collect::<Result<_, _>>()?;
+
+    Ok((crates, instructions))
+}
 
-    (crates, instructions)
+/// Takes the top `n` crates off a stack, in the order they were stacked
+fn take(crates: &mut Crates, from: usize, n: usize) -> Result<Vec<char>, String> {
+    // The generator makes sure instructions only name stacks there are
+    let stack = crates
+        .get_mut(&from)
+        .expect("instructions name existing stacks");
+    let start = stack
+        .len()
+        .checked_sub(n)
+        .ok_or_else(|| format!("Stack {} has fewer than {} crates to move", from, n))?;
+
+    Ok(stack.drain(start..).collect())
 }
 
-pub fn part1((crates, instructions): &(Crates, Vec<Instruction>)) -> String {
+/// The crate on top of each stack
+fn tops(crates: Crates) -> Result<String, String> {
+    crates
+        .into_iter()
+        .map(|(n, mut stack)| stack.pop().ok_or_else(|| format!("Stack {} is empty", n)))
+        .collect()
+}
+
+pub fn part1((crates, instructions): &(Crates, Vec<Instruction>)) -> Result<String, String> {
     let mut crates = crates.clone();
 
     for Instruction { n, from, to } in instructions {
-        for _ in 0..*n {
-            let _crate = crates.get_mut(from).unwrap().pop().unwrap();
-            crates.get_mut(to).unwrap().push(_crate);
-        }
+        // One at a time, so the crates moved end up in the reverse order
+        let moved = take(&mut crates, *from, *n)?;
+        crates
+            .get_mut(to)
+            .expect("instructions name existing stacks")
+            .extend(moved.into_iter().rev());
     }
 
-    crates
-        .into_values()
-        .map(|mut stack| stack.pop().unwrap())
-        .collect()
+    tops(crates)
 }
 
-pub fn part2((crates, instructions): &(Crates, Vec<Instruction>)) -> String {
+pub fn part2((crates, instructions): &(Crates, Vec<Instruction>)) -> Result<String, String> {
     let mut crates = crates.clone();
 
     for Instruction { n, from, to } in instructions {
-        let from_stack = crates.get_mut(from).unwrap();
-        let mut transfer = from_stack
-            .drain((from_stack.len() - *n)..)
-            .collect::<Vec<_>>();
-        crates.get_mut(to).unwrap().append(&mut transfer);
+        let moved = take(&mut crates, *from, *n)?;
+        crates
+            .get_mut(to)
+            .expect("instructions name existing stacks")
+            .extend(moved);
     }
 
-    crates
-        .into_values()
-        .map(|mut stack| stack.pop().unwrap())
-        .collect()
+    tops(crates)
 }
 
 #[cfg(test)]
@@ -92,11 +174,49 @@ move 1 from 1 to 2";
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&parse(SAMPLE)), String::from("CMZ"));
+        assert_eq!(part1(&parse(SAMPLE).unwrap()), Ok(String::from("CMZ")));
+        assert_eq!(
+            part1(&parse("[A]\n 1 \n\nmove 2 from 1 to 1").unwrap()),
+            Err(String::from("Stack 1 has fewer than 2 crates to move"))
+        );
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&parse(SAMPLE)), String::from("MCD"));
+        assert_eq!(part2(&parse(SAMPLE).unwrap()), Ok(String::from("MCD")));
+        assert_eq!(
+            part2(&parse("[A]\n 1   2 \n\nmove 1 from 2 to 1").unwrap()),
+            Err(String::from("Stack 2 has fewer than 1 crates to move"))
+        );
+        assert_eq!(
+            part2(&parse("[A]\n 1   2 \n\nmove 1 from 1 to 2").unwrap()),
+            Err(String::from("Stack 1 is empty"))
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse("[A]\nB\n 1 \n\nmove 1 from 1 to 1").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.message.as_str()),
+            (2, 1, "Expected a crate in brackets")
+        );
+
+        let error = |input| {
+            let err = parse(input).unwrap_err();
+            (err.line, err.message)
+        };
+        assert_eq!(
+            error("[A]\n 2 \n\nmove 1 from 1 to 1"),
+            (2, String::from("Expected the stacks numbered from 1"))
+        );
+        assert_eq!(
+            error("    [A]\n 1 \n\nmove 1 from 1 to 1"),
+            (1, String::from("Expected a crate above a numbered stack"))
+        );
+        assert_eq!(
+            error("[A]\n 1 \n\nmove 1 from 1 to 2"),
+            (4, String::from("Expected stacks from 1 to 1"))
+        );
     }
 }
@@ -1,5 +1,24 @@
 use std::collections::HashSet;
 
+use aoc_common::input::InputError;
+
+const DAY: u8 = 6;
+
+pub fn parse(input: &str) -> Result<&str, InputError> {
+    let signal = input.trim_end();
+
+    match signal.find(|c: char| !c.is_ascii_lowercase()) {
+        _ if signal.is_empty() => Err(InputError::end(DAY, input, "Expected a signal")),
+        Some(idx) => Err(InputError::at(
+            DAY,
+            input,
+            &signal[idx..],
+            "Expected a lowercase letter",
+        )),
+        None => Ok(signal),
+    }
+}
+
 fn is_unique_hashset(slice: &[u8]) -> bool {
     let mut uniq = HashSet::new();
     slice.iter().all(move |x| uniq.insert(x))
@@ -16,70 +35,100 @@ fn is_unique_loops(slice: &[u8]) -> bool {
     true
 }
 
-pub fn part1(s: &str) -> usize {
+pub fn part1(s: &str) -> Option<usize> {
     s.as_bytes()
         .windows(4)
         .enumerate()
         .find(|(_, window)| is_unique_hashset(window))
-        .unwrap()
-        .0
-        + 4
+        .map(|(idx, _)| idx + 4)
 }
 
-pub fn part2_hashset(s: &str) -> usize {
+pub fn part2_hashset(s: &str) -> Option<usize> {
     s.as_bytes()
         .windows(14)
         .enumerate()
         .find(|(_, window)| is_unique_hashset(window))
-        .unwrap()
-        .0
-        + 14
+        .map(|(idx, _)| idx + 14)
 }
 
-pub fn part2_loops(s: &str) -> usize {
+pub fn part2_loops(s: &str) -> Option<usize> {
     s.as_bytes()
         .windows(14)
         .enumerate()
         .find(|(_, window)| is_unique_loops(window))
-        .unwrap()
-        .0
-        + 14
+        .map(|(idx, _)| idx + 14)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::differential::{assert_solutions_agree, Implementations};
+    use proptest::prelude::*;
 
     const SAMPLE_1: &str = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
     const SAMPLE_2: &str = "bvwbjplbgvbhsrlpgdmjqwftvncz";
     const SAMPLE_3: &str = "nppdvjthqldpwncqszvftbrmjlhg";
     const SAMPLE_4: &str = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
     const SAMPLE_5: &str = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
+    const SAMPLES: [&str; 5] = [SAMPLE_1, SAMPLE_2, SAMPLE_3, SAMPLE_4, SAMPLE_5];
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("abc\n"), Ok("abc"));
+        assert_eq!(parse("").unwrap_err().message, "Expected a signal");
+        assert_eq!(parse("abC").unwrap_err().column, 3);
+        assert_eq!(part1("abc"), None);
+    }
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(SAMPLE_1), 7);
-        assert_eq!(part1(SAMPLE_2), 5);
-        assert_eq!(part1(SAMPLE_3), 6);
-        assert_eq!(part1(SAMPLE_4), 10);
-        assert_eq!(part1(SAMPLE_5), 11);
+        assert_eq!(part1(SAMPLE_1), Some(7));
+        assert_eq!(part1(SAMPLE_2), Some(5));
+        assert_eq!(part1(SAMPLE_3), Some(6));
+        assert_eq!(part1(SAMPLE_4), Some(10));
+        assert_eq!(part1(SAMPLE_5), Some(11));
     }
 
     #[test]
     fn test_part2_hashset() {
-        assert_eq!(part2_hashset(SAMPLE_1), 19);
-        assert_eq!(part2_hashset(SAMPLE_2), 23);
-        assert_eq!(part2_hashset(SAMPLE_3), 23);
-        assert_eq!(part2_hashset(SAMPLE_4), 29);
-        assert_eq!(part2_hashset(SAMPLE_5), 26);
+        assert_eq!(part2_hashset(SAMPLE_1), Some(19));
+        assert_eq!(part2_hashset(SAMPLE_2), Some(23));
+        assert_eq!(part2_hashset(SAMPLE_3), Some(23));
+        assert_eq!(part2_hashset(SAMPLE_4), Some(29));
+        assert_eq!(part2_hashset(SAMPLE_5), Some(26));
     }
 
     #[test]
     fn test_part2_loops() {
-        assert_eq!(part2_loops(SAMPLE_1), 19);
-        assert_eq!(part2_loops(SAMPLE_2), 23);
-        assert_eq!(part2_loops(SAMPLE_3), 23);
-        assert_eq!(part2_loops(SAMPLE_4), 29);
-        assert_eq!(part2_loops(SAMPLE_5), 26);
+        assert_eq!(part2_loops(SAMPLE_1), Some(19));
+        assert_eq!(part2_loops(SAMPLE_2), Some(23));
+        assert_eq!(part2_loops(SAMPLE_3), Some(23));
+        assert_eq!(part2_loops(SAMPLE_4), Some(29));
+        assert_eq!(part2_loops(SAMPLE_5), Some(26));
+    }
+
+    #[test]
+    fn test_samples_agree() {
+        let year = crate::solutions();
+        for sample in SAMPLES {
+            assert_solutions_agree(year.day(6).unwrap(), sample);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_is_unique_agree(slice in prop::collection::vec(b'a'..=b'p', 0..16)) {
+            Implementations::new()
+                .with("is_unique_hashset", is_unique_hashset)
+                .with("is_unique_loops", is_unique_loops)
+                .assert_agree(&slice[..]);
+        }
+
+        /// Over few enough letters that markers are rare, ending with one so there always is one
+        #[test]
+        fn test_part2_agree(signal in "[a-p]{0,200}") {
+            let year = crate::solutions();
+            assert_solutions_agree(year.day(6).unwrap(), &(signal + "abcdefghijklmn"));
+        }
     }
 }
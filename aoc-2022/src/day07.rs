@@ -1,6 +1,11 @@
 use std::collections::HashMap;
 
-use itertools::Itertools;
+use aoc_common::input::InputError;
+
+const DAY: u8 = 7;
+
+const DISK_SPACE: u128 = 70_000_000;
+const SPACE_NEEDED: u128 = 30_000_000;
 
 type FilePath<'a> = Vec<&'a str>;
 
@@ -28,7 +33,7 @@ impl<'a> FileSystem<'a> {
     }
 }
 
-pub fn parse(s: &str) -> FileSystem {
+pub fn parse(s: &str) -> Result<FileSystem<'_>, InputError> {
     let mut fs = FileSystem {
         files: HashMap::new(),
         directories: HashMap::new(),
@@ -50,9 +55,7 @@ pub fn parse(s: &str) -> FileSystem {
                 cwd.push(*name);
                 fs.directories.insert(cwd.clone(), Directory);
             }
-            ["$", "ls"] => {
-                println!("listing /{}/", cwd.join("/"));
-            }
+            ["$", "ls"] => {}
             ["dir", name] => {
                 let mut path = cwd.clone();
                 path.push(*name);
@@ -63,18 +66,18 @@ pub fn parse(s: &str) -> FileSystem {
                 let mut path = cwd.clone();
                 path.push(*name);
 
-                fs.files.insert(
-                    path,
-                    File {
-                        size: size.parse().unwrap(),
-                    },
-                );
+                // Sizes fit in 64 bits so that no number of them can overflow a total
+                let size = size
+                    .parse::<u64>()
+                    .map_err(|err| InputError::at(DAY, s, line, err))?;
+
+                fs.files.insert(path, File { size: size.into() });
             }
-            _ => unreachable!(),
+            _ => return Err(InputError::at(DAY, s, line, "Unrecognised output")),
         }
     }
 
-    fs
+    Ok(fs)
 }
 
 pub fn part1(fs: &FileSystem) -> u128 {
@@ -85,17 +88,20 @@ pub fn part1(fs: &FileSystem) -> u128 {
         .sum()
 }
 
-pub fn part2(fs: &FileSystem) -> u128 {
-    let unused = 70_000_000 - fs.size(&vec![]);
-    let required = 30_000_000 - unused;
+pub fn part2(fs: &FileSystem) -> Result<u128, &'static str> {
+    let unused = DISK_SPACE
+        .checked_sub(fs.size(&vec![]))
+        .ok_or("The files don't fit on the disk")?;
+    let required = SPACE_NEEDED
+        .checked_sub(unused)
+        .ok_or("There is already enough space for the update")?;
 
     fs.directories
         .keys()
         .map(|d| fs.size(d))
         .filter(|size| *size > required)
-        .sorted()
-        .next()
-        .unwrap()
+        .min()
+        .ok_or("No directory is large enough to delete")
 }
 
 #[cfg(test)]
@@ -128,11 +134,25 @@ $ ls
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&parse(SAMPLE)), 95437);
+        assert_eq!(part1(&parse(SAMPLE).unwrap()), 95437);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&parse(SAMPLE)), 24933642);
+        assert_eq!(part2(&parse(SAMPLE).unwrap()), Ok(24933642));
+
+        assert_eq!(
+            part2(&parse("").unwrap()),
+            Err("There is already enough space for the update")
+        );
+        assert_eq!(
+            part2(&parse("$ cd /\n70000001 a").unwrap()),
+            Err("The files don't fit on the disk")
+        );
+        assert_eq!(
+            part2(&parse("50000000 a\n10000000 b").unwrap()),
+            Err("No directory is large enough to delete")
+        );
+        assert!(parse("$ cd /\n18446744073709551616 a").is_err());
     }
 }
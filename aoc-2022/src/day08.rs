@@ -1,36 +1,31 @@
-use grid::Grid;
+use aoc_common::grid::{Grid, Position, ORTHOGONAL};
+use aoc_common::input::InputError;
 use itertools::Itertools;
 
+const DAY: u8 = 8;
+
 type Forest = Grid<u32>;
 
 trait GridExt {
-    fn points(&self) -> Vec<(usize, usize)>;
-    fn views(&self, row: usize, col: usize) -> [Vec<&u32>; 4];
-    fn restricted_views(&self, row: usize, col: usize) -> [Vec<&u32>; 4];
-    fn is_externally_visible(&self, row: usize, col: usize) -> bool;
-    fn score(&self, row: usize, col: usize) -> usize;
+    fn views(&self, position: Position) -> [Vec<&u32>; 4];
+    fn restricted_views(&self, position: Position) -> [Vec<&u32>; 4];
+    fn is_externally_visible(&self, position: Position) -> bool;
+    fn score(&self, position: Position) -> usize;
 }
 
 impl GridExt for Forest {
-    fn points(&self) -> Vec<(usize, usize)> {
-        (0..self.rows())
-            .flat_map(|row| (0..self.cols()).map(move |col| (row, col)))
-            .collect()
-    }
-
-    fn views(&self, row: usize, col: usize) -> [Vec<&u32>; 4] {
-        [
-            self.iter_row(row).skip(col + 1).collect(),
-            self.iter_col(col).skip(row + 1).collect(),
-            self.iter_row(row).rev().skip(self.cols() - col).collect(),
-            self.iter_col(col).rev().skip(self.rows() - row).collect(),
-        ]
+    fn views(&self, position: Position) -> [Vec<&u32>; 4] {
+        ORTHOGONAL.map(|direction| {
+            self.ray(position, direction)
+                .map(|(_, tree)| tree)
+                .collect()
+        })
     }
 
-    fn restricted_views(&self, row: usize, col: usize) -> [Vec<&u32>; 4] {
-        let height = self.get(row, col).unwrap();
+    fn restricted_views(&self, position: Position) -> [Vec<&u32>; 4] {
+        let height = &self[position];
 
-        self.views(row, col).map(|view| {
+        self.views(position).map(|view| {
             let mut view = view.into_iter();
             let mut restricted_view = view
                 .take_while_ref(|elem| *elem < height)
@@ -44,52 +39,46 @@ impl GridExt for Forest {
         })
     }
 
-    fn score(&self, row: usize, col: usize) -> usize {
-        self.restricted_views(row, col)
+    fn score(&self, position: Position) -> usize {
+        self.restricted_views(position)
             .map(|view| view.len())
             .iter()
             .product()
     }
 
-    fn is_externally_visible(&self, row: usize, col: usize) -> bool {
-        let height = self.get(row, col).unwrap();
+    fn is_externally_visible(&self, position: Position) -> bool {
+        let height = &self[position];
 
-        self.views(row, col)
+        self.views(position)
             .iter()
             .any(|view| view.iter().all(|tree| *tree < height))
     }
 }
 
-pub fn parse(s: &str) -> Forest {
-    let mut forest = Forest::new(0, 0);
+pub fn parse(s: &str) -> Result<Forest, InputError> {
+    let forest: Forest =
+        Grid::parse(s, |c| c.to_digit(10)).map_err(|err| InputError::grid(DAY, s, err))?;
 
-    for line in s.lines() {
-        forest.push_row(
-            line.chars()
-                .map(|c| c.to_digit(10))
-                .collect::<Option<Vec<_>>>()
-                .unwrap(),
-        );
+    if forest.width() == 0 || forest.height() == 0 {
+        return Err(InputError::end(DAY, s, "Expected a map of trees"));
     }
 
-    forest
+    Ok(forest)
 }
 
 pub fn part1(forest: &Forest) -> usize {
     forest
-        .points()
-        .iter()
-        .filter(|(row, col)| forest.is_externally_visible(*row, *col))
+        .positions()
+        .filter(|position| forest.is_externally_visible(*position))
         .count()
 }
 
 pub fn part2(forest: &Forest) -> usize {
     forest
-        .points()
-        .iter()
-        .map(|(row, col)| forest.score(*row, *col))
+        .positions()
+        .map(|position| forest.score(position))
         .max()
-        .unwrap()
+        .expect("the forest has trees")
 }
 
 #[cfg(test)]
@@ -104,11 +93,16 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&parse(SAMPLE)), 21);
+        assert_eq!(part1(&parse(SAMPLE).unwrap()), 21);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&parse(SAMPLE)), 8);
+        assert_eq!(part2(&parse(SAMPLE).unwrap()), 8);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(parse("\n").unwrap_err().message, "Expected a map of trees");
     }
 }
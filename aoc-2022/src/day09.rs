@@ -1,89 +1,146 @@
+use std::io;
+
+use aoc_common::geometry::{Bounds, Direction, Point};
+use aoc_common::grid::Grid;
+use aoc_common::input::{parse_lines, InputError};
+use aoc_common::render::{Frames, Rgb};
 use itertools::Itertools;
 
-type Location = (i32, i32);
+const DAY: u8 = 9;
+
+type Location = Point<i32>;
+type Motion = (Direction, u32);
+
+/// The most steps the head takes in all, as every step of the tail is remembered
+const MAX_STEPS: u32 = 1 << 20;
+
+fn motion(line: &str) -> Result<Motion, String> {
+    let (direction, n) = line
+        .split_once(' ')
+        .ok_or("Expected a direction and a number of steps")?;
+    let direction =
+        self::direction(direction).ok_or_else(|| format!("Unknown direction {:?}", direction))?;
+
+    Ok((
+        direction,
+        n.parse()
+            .map_err(|_| format!("Expected a number, found {:?}", n))?,
+    ))
+}
+
+pub fn parse(s: &str) -> Result<Vec<Motion>, InputError> {
+    let mut steps = 0u32;
+
+    parse_lines(DAY, s, |line| {
+        let motion = motion(line)?;
+
+        steps = steps.saturating_add(motion.1);
+        if steps > MAX_STEPS {
+            return Err(format!("Expected at most {} steps in all", MAX_STEPS));
+        }
 
-pub fn parse(s: &str) -> Vec<&str> {
-    s.lines().collect()
+        Ok(motion)
+    })
 }
 
+/// Where the tail ends up once the head has moved: it stays put while still touching the
+/// head, otherwise it takes one step, diagonally if need be, towards it
 fn update(head: &Location, tail: Location) -> Location {
-    match (head.0 - tail.0, head.1 - tail.1) {
-        (0, 0) => tail,                                // overlap
-        (-1, 0) | (1, 0) => tail,                      // touching horizontally
-        (0, -1) | (0, 1) => tail,                      // touching vertically
-        (1, 1) | (1, -1) | (-1, 1) | (-1, -1) => tail, // touching diagonally
-        (2, 0) => (tail.0 + 1, tail.1),
-        (-2, 0) => (tail.0 - 1, tail.1),
-        (0, 2) => (tail.0, tail.1 + 1),
-        (0, -2) => (tail.0, tail.1 - 1),
-        (2, 1) | (1, 2) => (tail.0 + 1, tail.1 + 1),
-        (2, -1) | (1, -2) => (tail.0 + 1, tail.1 - 1),
-        (-2, -1) | (-1, -2) => (tail.0 - 1, tail.1 - 1),
-        (-2, 1) | (-1, 2) => (tail.0 - 1, tail.1 + 1),
-        // new diagonal motions
-        (2, 2) => (tail.0 + 1, tail.1 + 1),
-        (2, -2) => (tail.0 + 1, tail.1 - 1),
-        (-2, -2) => (tail.0 - 1, tail.1 - 1),
-        (-2, 2) => (tail.0 - 1, tail.1 + 1),
-        _ => unreachable!(),
+    let delta = *head - tail;
+
+    if delta.chebyshev() <= 1 {
+        tail
+    } else {
+        tail + delta.signum()
     }
 }
 
-pub fn part1(input: &[&str]) -> usize {
-    let mut tail_history: Vec<Location> = Vec::new();
-    let mut head = Location::default();
-    let mut tail = Location::default();
-
-    for line in input {
-        if let Some((direction, n)) = line.split_once(' ') {
-            for _ in 0..n.parse().unwrap() {
-                match direction {
-                    "L" => head.0 -= 1,
-                    "R" => head.0 += 1,
-                    "D" => head.1 -= 1,
-                    "U" => head.1 += 1,
-                    _ => unreachable!(),
-                }
-
-                tail = update(&head, tail);
-                tail_history.push(tail);
-            }
+fn direction(direction: &str) -> Option<Direction> {
+    match direction {
+        "L" => Some(Direction::Left),
+        "R" => Some(Direction::Right),
+        "D" => Some(Direction::Down),
+        "U" => Some(Direction::Up),
+        _ => None,
+    }
+}
+
+/// Draws each step of the rope, with the head in red, the other knots in white and everywhere
+/// the tail has been in grey. North is up, and every frame covers wherever the rope went.
+fn draw(frames: &mut Frames, ropes: &[Vec<Location>]) -> io::Result<()> {
+    let Some(bounds) = Bounds::from_points(ropes.iter().flatten()) else {
+        return Ok(());
+    };
+    let position = |knot: &Location| {
+        (
+            (knot.x - bounds.min.x) as usize,
+            (bounds.max.y - knot.y) as usize,
+        )
+    };
+
+    let mut visited = Grid::new(
+        bounds.width() as usize,
+        bounds.height() as usize,
+        Rgb::BLACK,
+    );
+    for rope in ropes {
+        if let Some(tail) = rope.last() {
+            visited[position(tail)] = Rgb::grey(96);
+        }
+
+        let mut image = visited.clone();
+        for knot in rope.iter().skip(1) {
+            image[position(knot)] = Rgb::WHITE;
         }
+        image[position(&rope[0])] = Rgb(255, 64, 64);
+
+        frames.push(&image)?;
     }
 
-    tail_history.into_iter().unique().count()
+    Ok(())
 }
 
-pub fn part2(input: &[&str]) -> usize {
-    const KNOTS: usize = 10;
-
+/// Pulls a rope of `knots` knots through the motions, and counts where its tail has been. When
+/// frames are being written, the rope is drawn after every step.
+fn simulate(input: &[Motion], knots: usize, frames: Option<&mut Frames>) -> io::Result<usize> {
     let mut tail_history: Vec<Location> = Vec::new();
-    let mut knots = [Location::default(); KNOTS];
-
-    for line in input {
-        if let Some((direction, n)) = line.split_once(' ') {
-            for _ in 0..n.parse().unwrap() {
-                // move head
-                match direction {
-                    "L" => knots.first_mut().unwrap().0 -= 1,
-                    "R" => knots.first_mut().unwrap().0 += 1,
-                    "D" => knots.first_mut().unwrap().1 -= 1,
-                    "U" => knots.first_mut().unwrap().1 += 1,
-                    _ => unreachable!(),
-                }
-
-                // move the rest of the rope
-                for i in 1..knots.len() {
-                    knots[i] = update(&knots[i - 1], knots[i]);
-                }
-
-                // track the motion of the last knot
-                tail_history.push(knots.last().unwrap().to_owned());
+    let mut rope = vec![Location::default(); knots];
+    let mut ropes = Vec::new();
+
+    for (direction, n) in input {
+        let step = direction.vector();
+
+        for _ in 0..*n {
+            // move head
+            rope[0] += step;
+
+            // move the rest of the rope
+            for i in 1..knots {
+                rope[i] = update(&rope[i - 1], rope[i]);
+            }
+
+            // track the motion of the last knot
+            tail_history.push(rope[knots - 1]);
+
+            if frames.is_some() {
+                ropes.push(rope.clone());
             }
         }
     }
 
-    tail_history.into_iter().unique().count()
+    if let Some(frames) = frames {
+        draw(frames, &ropes)?;
+    }
+
+    Ok(tail_history.into_iter().unique().count())
+}
+
+pub fn part1(input: &[Motion]) -> io::Result<usize> {
+    simulate(input, 2, Frames::for_part(2022, DAY, 1)?.as_mut())
+}
+
+pub fn part2(input: &[Motion]) -> io::Result<usize> {
+    simulate(input, 10, Frames::for_part(2022, DAY, 2)?.as_mut())
 }
 
 #[cfg(test)]
@@ -109,14 +166,37 @@ D 10
 L 25
 U 20";
 
+    #[test]
+    fn test_update() {
+        let tail = Location::new(0, 0);
+
+        assert_eq!(update(&Location::new(1, -1), tail), tail);
+        assert_eq!(update(&Location::new(-2, 0), tail), Location::new(-1, 0));
+        assert_eq!(update(&Location::new(2, 1), tail), Location::new(1, 1));
+        assert_eq!(update(&Location::new(-2, 2), tail), Location::new(-1, 1));
+    }
+
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&parse(SAMPLE_1)), 13);
+        assert_eq!(part1(&parse(SAMPLE_1).unwrap()).unwrap(), 13);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&parse(SAMPLE_1)), 1);
-        assert_eq!(part2(&parse(SAMPLE_2)), 36);
+        assert_eq!(part2(&parse(SAMPLE_1).unwrap()).unwrap(), 1);
+        assert_eq!(part2(&parse(SAMPLE_2).unwrap()).unwrap(), 36);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse("R 4\nX 4").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "X 4"));
+        assert_eq!(err.message, "Unknown direction \"X\"");
+
+        let err = parse("R 1048576\nL 1").unwrap_err();
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (2, "Expected at most 1048576 steps in all")
+        );
     }
 }
@@ -1,30 +1,19 @@
-use std::fmt::{Display, Write};
-
-use grid::Grid;
+use aoc_common::grid::Grid;
+use aoc_common::input::InputError;
+use aoc_common::ocr::{self, OcrError};
 use itertools::Itertools;
 
+const DAY: u8 = 10;
+
 struct Screen(Grid<bool>);
 
 impl Screen {
     fn new() -> Screen {
-        Screen(Grid::new(6, 40))
+        Screen(Grid::new(40, 6, false))
     }
 }
 
-impl Display for Screen {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for row in 0..self.0.rows() {
-            for pixel in self.0.iter_row(row) {
-                f.write_char(if *pixel { '#' } else { '.' })?;
-            }
-            f.write_char('\n')?;
-        }
-
-        Ok(())
-    }
-}
-
-pub fn parse(s: &str) -> Vec<(u32, i32)> {
+pub fn parse(s: &str) -> Result<Vec<(u32, i32)>, InputError> {
     let instructions = s.lines().collect_vec();
 
     let mut instruction_counter = (0usize, 0u8);
@@ -42,42 +31,55 @@ pub fn parse(s: &str) -> Vec<(u32, i32)> {
                     if instruction_counter.1 == 0 {
                         instruction_counter.1 = 1;
                     } else {
-                        x += n.parse::<i32>().unwrap();
+                        let n = n
+                            .parse::<i32>()
+                            .map_err(|err| InputError::at(DAY, s, n, err))?;
+                        x = x.checked_add(n).ok_or_else(|| {
+                            InputError::at(DAY, s, instruction, "The X register overflows")
+                        })?;
                         instruction_counter = (instruction_counter.0 + 1, 0);
                     }
                 }
-                _ => unreachable!(),
+                _ => return Err(InputError::at(DAY, s, instruction, "Unknown instruction")),
             }
         } else {
             break;
         }
     }
 
-    states
+    Ok(states)
 }
 
-pub fn part1(input: &[(u32, i32)]) -> i32 {
+pub fn part1(input: &[(u32, i32)]) -> i64 {
     input
         .iter()
         .filter(|(cycle, _)| (cycle + 20) % 40 == 0)
-        .map(|(cycle, x)| x * (*cycle as i32))
+        .map(|(cycle, x)| i64::from(*x) * i64::from(*cycle))
         .sum()
 }
 
-pub fn part2(input: &[(u32, i32)]) -> String {
+/// Draws a pixel wherever the sprite covers the one being drawn during that cycle
+fn render(input: &[(u32, i32)]) -> Screen {
     let mut screen = Screen::new();
 
     for (cycle, x) in input {
-        let row = cycle / 40;
+        let row = (cycle - 1) / 40;
         let col = (cycle - 1) % 40;
-        let sprite_range = (x - 1)..=(x + 1);
+        let sprite_range = (i64::from(*x) - 1)..=(i64::from(*x) + 1);
 
-        if sprite_range.contains(&(col as i32)) {
-            *screen.0.get_mut(row as usize, col as usize).unwrap() = true;
+        if sprite_range.contains(&i64::from(col)) {
+            // The state after the last instruction is off the bottom of the screen
+            if let Some(pixel) = screen.0.get_mut((col as usize, row as usize)) {
+                *pixel = true;
+            }
         }
     }
 
-    screen.to_string()
+    screen
+}
+
+pub fn part2(input: &[(u32, i32)]) -> Result<String, OcrError> {
+    ocr::answer(&render(input).0)
 }
 
 #[cfg(test)]
@@ -233,21 +235,45 @@ noop";
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&parse(SAMPLE)), 13140);
+        assert_eq!(part1(&parse(SAMPLE).unwrap()), 13140);
+
+        let input = format!("{}addx 2147483646", "noop\n".repeat(17));
+        assert_eq!(part1(&parse(&input).unwrap()), 2147483647 * 20);
     }
 
     #[test]
     fn test_part2() {
-        // This is lightly different from what AoC provides,
-        // but its close enough, so...
         const SOLUTION: &str = "##..##..##..##..##..##..##..##..##..##..
 ###...###...###...###...###...###...###.
 ####....####....####....####....####....
 #####.....#####.....#####.....#####.....
-######......######......######......###.
-#######.......#######.......#######....#
+######......######......######......####
+#######.......#######.......#######.....
 ";
 
-        assert_eq!(part2(&parse(SAMPLE)), SOLUTION);
+        let screen = render(&parse(SAMPLE).unwrap());
+        assert_eq!(ocr::art(&screen.0), SOLUTION);
+
+        // The sample draws stripes, not letters
+        assert!(matches!(
+            part2(&parse(SAMPLE).unwrap()),
+            Err(OcrError::Unknown { index: 0, .. })
+        ));
+
+        // A sprite at the edge of the register's range is nowhere near the screen
+        let screen = render(&parse("addx 2147483646\nnoop").unwrap());
+        assert_eq!(screen.0.positions().filter(|p| screen.0[*p]).count(), 2);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse("noop\naddx 2147483647").unwrap_err();
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (2, "The X register overflows")
+        );
+
+        let err = parse("addx 1\njump 2").unwrap_err();
+        assert_eq!((err.line, err.message.as_str()), (2, "Unknown instruction"));
     }
 }
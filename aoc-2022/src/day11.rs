@@ -1,11 +1,34 @@
 use std::{cell::RefCell, collections::VecDeque, fmt::Debug};
 
+use aoc_common::input::InputError;
 use itertools::Itertools;
 
+const DAY: u8 = 11;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operation {
+    Add(u128),
+    Multiply(u128),
+    Double,
+    Square,
+}
+
+impl Operation {
+    fn apply(self, old: u128) -> Result<u128, &'static str> {
+        match self {
+            Operation::Add(value) => old.checked_add(value),
+            Operation::Multiply(value) => old.checked_mul(value),
+            Operation::Double => old.checked_add(old),
+            Operation::Square => old.checked_mul(old),
+        }
+        .ok_or("The worry level overflows a 128-bit number")
+    }
+}
+
 #[derive(Clone)]
 pub struct Monkey {
     items: RefCell<VecDeque<u128>>,
-    operation: fn(u128) -> u128,
+    operation: Operation,
     modulo: u128,
     dst: (usize, usize),
 }
@@ -16,68 +39,104 @@ impl Debug for Monkey {
     }
 }
 
-pub fn generate(_: &str) -> [Monkey; 8] {
-    [
-        // Monkey 0
-        Monkey {
-            items: RefCell::new(VecDeque::from([91, 66])),
-            operation: |old| old * 13,
-            modulo: 19,
-            dst: (6, 2),
-        },
-        // Monkey 1
-        Monkey {
-            items: RefCell::new(VecDeque::from([78, 97, 59])),
-            operation: |old| old + 7,
-            modulo: 5,
-            dst: (0, 3),
-        },
-        // Monkey 2
-        Monkey {
-            items: RefCell::new(VecDeque::from([57, 59, 97, 84, 72, 83, 56, 76])),
-            operation: |old| old + 6,
-            modulo: 11,
-            dst: (5, 7),
-        },
-        // Monkey 3
-        Monkey {
-            items: RefCell::new(VecDeque::from([81, 78, 70, 58, 84])),
-            operation: |old| old + 5,
-            modulo: 17,
-            dst: (6, 0),
-        },
-        // Monkey 4
-        Monkey {
-            items: RefCell::new(VecDeque::from([60])),
-            operation: |old| old + 8,
-            modulo: 7,
-            dst: (1, 3),
-        },
-        // Monkey 5
-        Monkey {
-            items: RefCell::new(VecDeque::from([57, 69, 63, 75, 62, 77, 72])),
-            operation: |old| old * 5,
-            modulo: 13,
-            dst: (7, 4),
-        },
-        // Monkey 6
-        Monkey {
-            items: RefCell::new(VecDeque::from([73, 66, 86, 79, 98, 87])),
-            operation: |old| old * old,
-            modulo: 3,
-            dst: (5, 2),
-        },
-        //Monkey 7
-        Monkey {
-            items: RefCell::new(VecDeque::from([95, 89, 63, 67])),
-            operation: |old| old + 2,
-            modulo: 2,
-            dst: (1, 4),
-        },
-    ]
+/// Parses the `index`th of `count` monkeys from `block`, a slice of `input`
+fn monkey(input: &str, block: &str, index: usize, count: usize) -> Result<Monkey, InputError> {
+    let error = |fragment: &str, message: String| InputError::at(DAY, input, fragment, message);
+    let mut lines = block.lines().map(str::trim);
+
+    let mut field = |prefix: &str| {
+        let expected = || format!("Expected {:?}", prefix.trim_end());
+        let line = lines
+            .next()
+            .ok_or_else(|| error(&block[block.len()..], expected()))?;
+        line.strip_prefix(prefix)
+            .map(str::trim)
+            .ok_or_else(|| error(line, expected()))
+    };
+    let number = |text: &str| {
+        text.parse::<u128>()
+            .map_err(|_| error(text, format!("Expected a number, found {:?}", text)))
+    };
+
+    let header = field("Monkey ")?;
+    if header != format!("{}:", index) {
+        return Err(error(header, format!("Expected monkey {}", index)));
+    }
+
+    let items = field("Starting items:")?;
+    let items = if items.is_empty() {
+        VecDeque::new()
+    } else {
+        items
+            .split(',')
+            .map(|item| number(item.trim()))
+            .collect::<Result<_, _>>()?
+    };
+
+    let expression = field("Operation: new = old ")?;
+    let operation = match expression.split_once(' ') {
+        Some(("+", "old")) => Operation::Double,
+        Some(("*", "old")) => Operation::Square,
+        Some(("+", value)) => Operation::Add(number(value)?),
+        Some(("*", value)) => Operation::Multiply(number(value)?),
+        _ => {
+            return Err(error(
+                expression,
+                String::from("Expected + or * and a number or old"),
+            ))
+        }
+    };
+
+    let divisor = field("Test: divisible by ")?;
+    let modulo = number(divisor)?;
+    if modulo == 0 {
+        return Err(error(
+            divisor,
+            String::from("Expected a divisor other than 0"),
+        ));
+    }
+
+    let mut destination = |prefix| {
+        let text = field(prefix)?;
+        match text.parse() {
+            Ok(destination) if destination == index => {
+                Err(error(text, String::from("A monkey can't throw to itself")))
+            }
+            Ok(destination) if destination < count => Ok(destination),
+            _ => Err(error(
+                text,
+                format!("Expected a monkey from 0 to {}", count - 1),
+            )),
+        }
+    };
+    let dst = (
+        destination("If true: throw to monkey ")?,
+        destination("If false: throw to monkey ")?,
+    );
+
+    if let Some(line) = lines.next() {
+        return Err(error(line, String::from("Unexpected trailing input")));
+    }
+
+    Ok(Monkey {
+        items: RefCell::new(items),
+        operation,
+        modulo,
+        dst,
+    })
+}
+
+pub fn generate(input: &str) -> Result<Vec<Monkey>, InputError> {
+    let blocks = input.split("\n\n").collect::<Vec<_>>();
+
+    blocks
+        .iter()
+        .enumerate()
+        .map(|(index, block)| monkey(input, block, index, blocks.len()))
+        .collect()
 }
 
-pub fn part1(input: &[Monkey]) -> u128 {
+pub fn part1(input: &[Monkey]) -> Result<u128, &'static str> {
     let monkeys = input.to_vec();
     let mut activity = vec![0u128; monkeys.len()];
 
@@ -86,119 +145,163 @@ pub fn part1(input: &[Monkey]) -> u128 {
             while let Some(item) = monkey.items.borrow_mut().pop_front() {
                 activity[idx] += 1;
 
-                let new = (monkey.operation)(item) / 3;
-                let new_monkey = if new % monkey.modulo == 0 {
+                let new = monkey.operation.apply(item)? / 3;
+                let new_monkey = if new.is_multiple_of(monkey.modulo) {
                     monkey.dst.0
                 } else {
                     monkey.dst.1
                 };
                 monkeys[new_monkey].items.borrow_mut().push_back(new);
-
-                println!("throwing item with worry level {} to {}", new, new_monkey);
             }
         }
     }
 
-    activity.iter().sorted().rev().take(2).product()
+    Ok(activity.iter().sorted().rev().take(2).product())
 }
 
-pub fn part2(input: &[Monkey]) -> u128 {
+pub fn part2(input: &[Monkey]) -> Result<u128, &'static str> {
     let monkeys = input.to_vec();
     let mut activity = vec![0u128; monkeys.len()];
-    let large_modulo: u128 = monkeys
-        .clone()
-        .into_iter()
-        .map(|monkey| monkey.modulo)
-        .product();
+    let large_modulo = monkeys
+        .iter()
+        .try_fold(1u128, |product, monkey| product.checked_mul(monkey.modulo))
+        .ok_or("The product of the divisors overflows a 128-bit number")?;
 
     for _ in 1..=10_000 {
         for (idx, monkey) in monkeys.iter().enumerate() {
             while let Some(item) = monkey.items.borrow_mut().pop_front() {
                 activity[idx] += 1;
 
-                let new = (monkey.operation)(item) % large_modulo;
-                let new_monkey = if new % monkey.modulo == 0 {
+                let new = monkey.operation.apply(item)? % large_modulo;
+                let new_monkey = if new.is_multiple_of(monkey.modulo) {
                     monkey.dst.0
                 } else {
                     monkey.dst.1
                 };
                 monkeys[new_monkey].items.borrow_mut().push_back(new);
-
-                //println!("throwing item with worry level {} to {}", new, new_monkey);
             }
         }
     }
 
-    dbg!(&activity);
-
-    activity.iter().sorted().rev().take(2).product()
+    Ok(activity.iter().sorted().rev().take(2).product())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const SAMPLE: &str = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1";
+
+    const OVERFLOW: &str = "Monkey 0:
+  Starting items: 18446744073709551616
+  Operation: new = old * old
+  Test: divisible by 2
+    If true: throw to monkey 1
+    If false: throw to monkey 1
+
+Monkey 1:
+  Starting items:
+  Operation: new = old + 1
+  Test: divisible by 2
+    If true: throw to monkey 0
+    If false: throw to monkey 0";
+
+    #[test]
+    fn test_generate() {
+        let monkeys = generate(SAMPLE).unwrap();
+        assert_eq!(monkeys.len(), 4);
+        assert_eq!(monkeys[1].items.borrow().iter().sum::<u128>(), 268);
+        assert_eq!(monkeys[2].operation, Operation::Square);
+        assert_eq!(monkeys[3].operation, Operation::Add(3));
+        assert_eq!((monkeys[3].modulo, monkeys[3].dst), (17, (0, 1)));
+    }
+
+    #[test]
+    fn test_generate_errors() {
+        let error = |from: &str, to: &str| {
+            let err = generate(&SAMPLE.replacen(from, to, 1)).unwrap_err();
+            (err.line, err.message)
+        };
+
+        assert_eq!(
+            error("Monkey 1", "Monkey 4"),
+            (8, String::from("Expected monkey 1"))
+        );
+        assert_eq!(
+            error("79, 98", "79, x"),
+            (2, String::from("Expected a number, found \"x\""))
+        );
+        assert_eq!(
+            error("old * 19", "old / 19"),
+            (3, String::from("Expected + or * and a number or old"))
+        );
+        assert_eq!(
+            error("by 23", "by 0"),
+            (4, String::from("Expected a divisor other than 0"))
+        );
+        assert_eq!(
+            error("monkey 2", "monkey 0"),
+            (5, String::from("A monkey can't throw to itself"))
+        );
+        assert_eq!(
+            error("monkey 3", "monkey 4"),
+            (6, String::from("Expected a monkey from 0 to 3"))
+        );
+        assert_eq!(
+            error("  Test: divisible by 23\n", ""),
+            (4, String::from("Expected \"Test: divisible by\""))
+        );
+        assert_eq!(
+            error(
+                "false: throw to monkey 1",
+                "false: throw to monkey 1\n  Extra"
+            ),
+            (28, String::from("Unexpected trailing input"))
+        );
+        assert_eq!(generate("Monkey 0:").unwrap_err().line, 1);
+    }
+
     #[test]
     fn test_part1() {
-        let sample_monkeys: [Monkey; 4] = [
-            Monkey {
-                items: RefCell::new(VecDeque::from([79, 98])),
-                operation: |old| old * 19,
-                modulo: 23,
-                dst: (2, 3),
-            },
-            Monkey {
-                items: RefCell::new(VecDeque::from([54, 65, 75, 74])),
-                operation: |old| old + 6,
-                modulo: 19,
-                dst: (2, 0),
-            },
-            Monkey {
-                items: RefCell::new(VecDeque::from([79, 60, 97])),
-                operation: |old| old * old,
-                modulo: 13,
-                dst: (1, 3),
-            },
-            Monkey {
-                items: RefCell::new(VecDeque::from([74])),
-                operation: |old| old + 3,
-                modulo: 17,
-                dst: (0, 1),
-            },
-        ];
-
-        assert_eq!(part1(&sample_monkeys), 10605);
+        assert_eq!(part1(&generate(SAMPLE).unwrap()), Ok(10605));
+        assert_eq!(
+            part1(&generate(OVERFLOW).unwrap()),
+            Err("The worry level overflows a 128-bit number")
+        );
     }
 
     #[test]
     fn test_part2() {
-        let sample_monkeys: [Monkey; 4] = [
-            Monkey {
-                items: RefCell::new(VecDeque::from([79, 98])),
-                operation: |old| old * 19,
-                modulo: 23,
-                dst: (2, 3),
-            },
-            Monkey {
-                items: RefCell::new(VecDeque::from([54, 65, 75, 74])),
-                operation: |old| old + 6,
-                modulo: 19,
-                dst: (2, 0),
-            },
-            Monkey {
-                items: RefCell::new(VecDeque::from([79, 60, 97])),
-                operation: |old| old * old,
-                modulo: 13,
-                dst: (1, 3),
-            },
-            Monkey {
-                items: RefCell::new(VecDeque::from([74])),
-                operation: |old| old + 3,
-                modulo: 17,
-                dst: (0, 1),
-            },
-        ];
-
-        assert_eq!(part2(&sample_monkeys), 2713310158);
+        assert_eq!(part2(&generate(SAMPLE).unwrap()), Ok(2713310158));
+        assert_eq!(
+            part2(&generate(OVERFLOW).unwrap()),
+            Err("The worry level overflows a 128-bit number")
+        );
     }
 }
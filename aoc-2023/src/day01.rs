@@ -1,32 +1,45 @@
-fn generator(input: &str) -> Vec<&str> {
-    input.lines().collect()
+use aoc_common::input::{parse_lines, InputError};
+
+const DAY: u8 = 1;
+
+fn calibration_line(line: &str) -> Result<&str, &'static str> {
+    if line.is_empty() {
+        Err("Expected a calibration value")
+    } else if !line
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+    {
+        Err("Expected only letters and digits")
+    } else {
+        Ok(line)
+    }
+}
+
+pub fn generator(input: &str) -> Result<Vec<&str>, InputError> {
+    parse_lines(DAY, input, calibration_line)
+}
+
+/// Lines of only letters are valid input, but a line needs a digit to have a calibration value
+fn no_digit(line: &str) -> String {
+    format!("Expected a digit in {}", line)
 }
 
-pub fn part1(input: &str) -> u32 {
-    generator(input)
-        .into_iter()
+pub fn part1(input: &[&str]) -> Result<u32, String> {
+    input
+        .iter()
         .map(|line| {
-            let first_digit = line
-                .chars()
-                .find(char::is_ascii_digit)
-                .unwrap()
-                .to_digit(10)
-                .unwrap();
-            let last_digit = line
-                .chars()
-                .rfind(char::is_ascii_digit)
-                .unwrap()
-                .to_digit(10)
-                .unwrap();
+            let mut digits = line.chars().filter_map(|c| c.to_digit(10));
+            let first_digit = digits.next().ok_or_else(|| no_digit(line))?;
+            let last_digit = digits.next_back().unwrap_or(first_digit);
 
-            (first_digit * 10) + last_digit
+            Ok((first_digit * 10) + last_digit)
         })
         .sum()
 }
 
-pub fn part2(input: &str) -> u32 {
-    generator(input)
-        .into_iter()
+pub fn part2(input: &[&str]) -> Result<u32, String> {
+    input
+        .iter()
         .map(|line| {
             let needles = [
                 ("0", 0),
@@ -54,16 +67,16 @@ pub fn part2(input: &str) -> u32 {
                 .iter()
                 .filter_map(|needle| line.find(needle.0).map(|idx| (idx, needle.1)))
                 .min_by(|a, b| a.0.cmp(&b.0))
-                .unwrap()
+                .ok_or_else(|| no_digit(line))?
                 .1;
             let last_digit = needles
                 .iter()
                 .filter_map(|needle| line.rfind(needle.0).map(|idx| (idx, needle.1)))
                 .max_by(|a, b| a.0.cmp(&b.0))
-                .unwrap()
+                .ok_or_else(|| no_digit(line))?
                 .1;
 
-            (first_digit * 10) + last_digit
+            Ok((first_digit * 10) + last_digit)
         })
         .sum()
 }
@@ -89,12 +102,17 @@ zoneight234
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(SAMPLE_1), 142);
+        assert_eq!(part1(&generator(SAMPLE_1).unwrap()), Ok(142));
+        assert_eq!(
+            part1(&generator("1abc2\nabc").unwrap()),
+            Err(String::from("Expected a digit in abc"))
+        );
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(SAMPLE_2), 281);
-        assert_eq!(part2(SAMPLE_3), 11);
+        assert_eq!(part2(&generator(SAMPLE_2).unwrap()), Ok(281));
+        assert_eq!(part2(&generator(SAMPLE_3).unwrap()), Ok(11));
+        assert!(part2(&generator("abc").unwrap()).is_err());
     }
 }
@@ -4,9 +4,13 @@ use nom::combinator::recognize;
 use nom::sequence::separated_pair;
 use nom::{
     bytes::complete::tag, character::complete::digit1, combinator::map_res, multi::separated_list1,
-    Finish, IResult,
+    IResult,
 };
 
+use aoc_common::input::{parse_all, InputError};
+
+const DAY: u8 = 2;
+
 #[derive(Debug)]
 struct CubeSet {
     red: u32,
@@ -62,7 +66,7 @@ impl CubeSet {
 }
 
 #[derive(Debug)]
-struct Game {
+pub struct Game {
     id: u32,
     selections: Vec<CubeSet>,
 }
@@ -86,17 +90,16 @@ impl Game {
     }
 }
 
-fn generator(input: &str) -> Result<Vec<Game>, nom::error::Error<&str>> {
+pub fn generator(input: &str) -> Result<Vec<Game>, InputError> {
     input
         .lines()
-        .map(|l| Game::parse(l).finish().map(|(_, game)| game))
+        .map(|line| parse_all(DAY, input, line, Game::parse))
         .collect()
 }
 
-pub fn part1(input: &str) -> u32 {
-    generator(input)
-        .unwrap()
-        .into_iter()
+pub fn part1(input: &[Game]) -> u32 {
+    input
+        .iter()
         .filter(|game| {
             game.selections.iter().all(|selection| {
                 selection.red <= 12 && selection.green <= 13 && selection.blue <= 14
@@ -106,12 +109,8 @@ pub fn part1(input: &str) -> u32 {
         .sum()
 }
 
-pub fn part2(input: &str) -> u64 {
-    generator(input)
-        .unwrap()
-        .into_iter()
-        .map(|game| game.minimal().power())
-        .sum()
+pub fn part2(input: &[Game]) -> u64 {
+    input.iter().map(|game| game.minimal().power()).sum()
 }
 
 #[cfg(test)]
@@ -126,11 +125,11 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(SAMPLE_1), 8);
+        assert_eq!(part1(&generator(SAMPLE_1).unwrap()), 8);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(SAMPLE_1), 2286);
+        assert_eq!(part2(&generator(SAMPLE_1).unwrap()), 2286);
     }
 }
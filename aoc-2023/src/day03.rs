@@ -1,114 +1,90 @@
-use std::collections::hash_map::Entry;
+use aoc_common::grid::{Grid, Position};
+use aoc_common::input::InputError;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
-pub fn part1(input: &str) -> u64 {
-    let columns = input.char_indices().find(|(_, c)| *c == '\n').unwrap().0;
-    let rows = input.lines().count();
-
-    let mut part_numbers: Vec<u64> = Vec::new();
-    let mut number: String = String::new();
-    let mut part_number = false;
-    for (i, c) in input.char_indices() {
-        match c {
-            '0'..='9' => {
-                number.push(c);
-
-                for dx in [-1, 0, 1] {
-                    for dy in [-1, 0, 1] {
-                        let x =
-                            Ord::clamp((i % (columns + 1)) as i32 + dx, 0, columns as i32) as usize;
-                        let y =
-                            Ord::clamp((i / (columns + 1)) as i32 + dy, 0, rows as i32) as usize;
-
-                        // +1 to include the newline character
-                        if let Some(value) = input.chars().nth(y * (columns + 1) + x) {
-                            if value.is_ascii_punctuation() && value != '.' {
-                                part_number = true;
-                            }
-                        }
-                    }
-                }
-            }
-            _ => {
-                if !number.is_empty() && part_number {
-                    part_numbers.push(number.parse().unwrap())
-                }
+const DAY: u8 = 3;
+
+/// An engine schematic, and every number in it with the positions of the cells surrounding it
+pub struct Schematic {
+    grid: Grid<char>,
+    numbers: Vec<(u64, HashSet<Position>)>,
+}
 
+fn numbers(input: &str, grid: &Grid<char>) -> Result<Vec<(u64, HashSet<Position>)>, InputError> {
+    let mut numbers = Vec::new();
+
+    for y in 0..grid.height() {
+        let mut number = String::new();
+        let mut surroundings = HashSet::new();
+
+        // Chaining a non-digit ends any number running up to the edge of the row
+        for (x, c) in grid.row(y).chain(['.'].iter()).enumerate() {
+            if c.is_ascii_digit() {
+                number.push(*c);
+                surroundings.extend(grid.all_neighbours((x, y)));
+            } else if !number.is_empty() {
+                let value = number.parse().map_err(|err| {
+                    let line = input.lines().nth(y).unwrap_or_default();
+                    let offset = line
+                        .char_indices()
+                        .nth(x - number.len())
+                        .map_or(line.len(), |(offset, _)| offset);
+                    InputError::at(DAY, input, &line[offset..], err)
+                })?;
+                numbers.push((value, surroundings.clone()));
                 number.clear();
-                part_number = false;
+                surroundings.clear();
             }
         }
     }
 
-    if !number.is_empty() && part_number {
-        part_numbers.push(number.parse().unwrap())
-    }
-
-    part_numbers.into_iter().sum()
+    Ok(numbers)
 }
 
-pub fn part2(input: &str) -> u64 {
-    let columns = input.char_indices().find(|(_, c)| *c == '\n').unwrap().0;
-    let rows = input.lines().count();
-
-    let mut part_numbers: Vec<(u64, HashSet<usize>)> = Vec::new();
-    let mut number: String = String::new();
-    let mut gears: HashSet<usize> = HashSet::new();
-    for (i, c) in input.char_indices() {
-        match c {
-            '0'..='9' => {
-                number.push(c);
-
-                for dx in [-1, 0, 1] {
-                    for dy in [-1, 0, 1] {
-                        let x =
-                            Ord::clamp((i % (columns + 1)) as i32 + dx, 0, columns as i32) as usize;
-                        let y =
-                            Ord::clamp((i / (columns + 1)) as i32 + dy, 0, rows as i32) as usize;
-
-                        // +1 to include the newline character
-                        let index = y * (columns + 1) + x;
-                        if let Some(value) = input.chars().nth(index) {
-                            if value == '*' {
-                                gears.insert(index);
-                            }
-                        }
-                    }
-                }
-            }
-            _ => {
-                if !number.is_empty() && !gears.is_empty() {
-                    part_numbers.push((number.parse().unwrap(), gears.clone()));
-                }
+pub fn parse(input: &str) -> Result<Schematic, InputError> {
+    let grid = input
+        .parse()
+        .map_err(|err| InputError::grid(DAY, input, err))?;
+    let numbers = numbers(input, &grid)?;
 
-                number.clear();
-                gears.clear();
-            }
-        }
-    }
+    Ok(Schematic { grid, numbers })
+}
 
-    if !number.is_empty() && !gears.is_empty() {
-        part_numbers.push((number.parse().unwrap(), gears.clone()));
-    }
+const OVERFLOW: &str = "The answer overflows a 64-bit number";
+
+pub fn part1(schematic: &Schematic) -> Result<u64, &'static str> {
+    schematic
+        .numbers
+        .iter()
+        .filter(|(_, surroundings)| {
+            surroundings.iter().any(|position| {
+                let value = schematic.grid[*position];
+                value.is_ascii_punctuation() && value != '.'
+            })
+        })
+        .try_fold(0u64, |sum, (number, _)| sum.checked_add(*number))
+        .ok_or(OVERFLOW)
+}
 
-    let mut gear_numbers: HashMap<usize, Vec<u64>> = HashMap::new();
-    for (part_number, gears) in part_numbers {
-        for gear in gears {
-            match gear_numbers.entry(gear) {
-                Entry::Occupied(mut e) => e.get_mut().push(part_number),
-                Entry::Vacant(e) => {
-                    e.insert(vec![part_number]);
-                }
-            }
+pub fn part2(schematic: &Schematic) -> Result<u64, &'static str> {
+    let mut gear_numbers: HashMap<Position, Vec<u64>> = HashMap::new();
+    for (part_number, surroundings) in &schematic.numbers {
+        for gear in surroundings
+            .iter()
+            .filter(|position| schematic.grid[**position] == '*')
+        {
+            gear_numbers.entry(*gear).or_default().push(*part_number);
         }
     }
 
     gear_numbers
         .into_iter()
         .filter(|e| e.1.len() == 2)
-        .map(|e| e.1.into_iter().product::<u64>())
-        .sum()
+        .try_fold(0u64, |sum, (_, numbers)| {
+            sum.checked_add(numbers[0].checked_mul(numbers[1])?)
+        })
+        .ok_or(OVERFLOW)
 }
 
 #[cfg(test)]
@@ -128,11 +104,28 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(SAMPLE_1), 4361);
+        assert_eq!(part1(&parse(SAMPLE_1).unwrap()), Ok(4361));
+        assert_eq!(
+            part1(&parse("18446744073709551615#1").unwrap()),
+            Err(OVERFLOW)
+        );
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(SAMPLE_1), 467835);
+        assert_eq!(part2(&parse(SAMPLE_1).unwrap()), Ok(467835));
+        assert_eq!(
+            part2(&parse("4294967296*4294967296").unwrap()),
+            Err(OVERFLOW)
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let input = "467..114..............\n...*..................\n.123456789012345678901";
+        let err = parse(input).err().unwrap();
+        assert_eq!((err.line, err.column), (3, 2));
+        assert_eq!(err.text, "123456789012345678901");
+        assert_eq!(err.message, "number too large to fit in target type");
     }
 }
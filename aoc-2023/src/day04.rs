@@ -6,12 +6,17 @@ use nom::sequence::separated_pair;
 use nom::sequence::tuple;
 use nom::{
     bytes::complete::tag, character::complete::digit1, combinator::map_res, multi::separated_list1,
-    Finish, IResult,
+    IResult,
 };
 use std::collections::HashSet;
+use std::fmt;
 
-#[derive(Debug)]
-struct Card<'a> {
+use aoc_common::input::{parse_all, InputError};
+
+const DAY: u8 = 4;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Card<'a> {
     id: u32,
     winners: HashSet<&'a str>,
     numbers: HashSet<&'a str>,
@@ -43,8 +48,7 @@ impl<'a> Card<'a> {
     }
 
     fn matches(&self) -> u32 {
-        self
-            .numbers
+        self.numbers
             .iter()
             .filter(|number| self.winners.contains(*number))
             .count() as u32
@@ -59,29 +63,59 @@ impl<'a> Card<'a> {
     }
 }
 
-fn generator(input: &str) -> Result<Vec<Card>, nom::error::Error<&str>> {
+/// Numbers right-aligned in columns, as in the puzzle input
+impl fmt::Display for Card<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let column = |numbers: &HashSet<&str>| {
+            let mut numbers = numbers.iter().collect::<Vec<_>>();
+            numbers.sort_by_key(|number| (number.len(), **number));
+            numbers
+                .iter()
+                .map(|number| format!("{:>2}", number))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        write!(
+            f,
+            "Card {:>3}: {} | {}",
+            self.id,
+            column(&self.winners),
+            column(&self.numbers)
+        )
+    }
+}
+
+pub fn generator(input: &str) -> Result<Vec<Card<'_>>, InputError> {
     input
         .lines()
-        .map(|l| Card::parse(l).finish().map(|(_, card)| card))
+        .map(|line| parse_all(DAY, input, line, Card::parse))
         .collect()
 }
 
-pub fn part1(input: &str) -> u32 {
-    generator(input).unwrap().into_iter().map(|c| c.score()).sum()
+pub fn part1(cards: &[Card]) -> u32 {
+    cards.iter().map(|c| c.score()).sum()
 }
 
-pub fn part2(input: &str) -> u32 {
-    let cards = generator(input).unwrap();
+pub fn part2(cards: &[Card]) -> u32 {
+    let mut copies = vec![1; cards.len()];
 
-    let card_indices: Vec<_> = cards.iter().map(|card| card.id..(card.id+card.matches())).collect();
+    for (idx, card) in cards.iter().enumerate() {
+        // Cards never win copies of cards past the end of the table
+        for won in (idx + 1..cards.len()).take(card.matches() as usize) {
+            copies[won] += copies[idx];
+        }
+    }
 
-    dbg!(card_indices);
-    todo!()
+    copies.into_iter().sum()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::strategy::{garbage, lines, malformed};
+    use proptest::collection::hash_set;
+    use proptest::prelude::*;
 
     const SAMPLE_1: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
 Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
@@ -92,11 +126,57 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(SAMPLE_1), 13);
+        assert_eq!(part1(&generator(SAMPLE_1).unwrap()), 13);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(SAMPLE_1), 30);
+        assert_eq!(part2(&generator(SAMPLE_1).unwrap()), 30);
+        assert_eq!(part2(&generator("Card 1: 1 2 | 1 2").unwrap()), 1);
+    }
+
+    /// A card as it would be written in the input, and its numbers
+    fn card() -> impl Strategy<Value = (String, u32, HashSet<String>, HashSet<String>)> {
+        let numbers = || hash_set((1..100_u32).prop_map(|n| n.to_string()), 1..10);
+
+        (1..1000_u32, numbers(), numbers()).prop_map(|(id, winners, numbers)| {
+            let card = Card {
+                id,
+                winners: winners.iter().map(String::as_str).collect(),
+                numbers: numbers.iter().map(String::as_str).collect(),
+            };
+
+            (card.to_string(), id, winners, numbers)
+        })
+    }
+
+    #[test]
+    fn test_display() {
+        for line in SAMPLE_1.lines() {
+            let (_, card) = Card::parse(line).unwrap();
+            assert_eq!(Card::parse(&card.to_string()), Ok(("", card)));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_card_round_trip((line, id, winners, numbers) in card()) {
+            let (rest, card) = Card::parse(&line)?;
+
+            prop_assert_eq!(rest, "");
+            prop_assert_eq!(card.id, id);
+            prop_assert_eq!(card.winners, winners.iter().map(String::as_str).collect());
+            prop_assert_eq!(card.numbers, numbers.iter().map(String::as_str).collect());
+        }
+
+        #[test]
+        fn test_generator_garbage(
+            input in prop_oneof![malformed(lines(card().prop_map(|(line, ..)| line), 1..10)), garbage()]
+        ) {
+            if let Ok(cards) = generator(&input) {
+                part1(&cards);
+                part2(&cards);
+            }
+        }
     }
 }
@@ -0,0 +1,12 @@
+mod day01;
+mod day02;
+mod day03;
+mod day04;
+
+aoc_common::solutions! {
+  year 2023;
+  day01 :generator? => part1?, part2?;
+  day02 :generator? => part1, part2;
+  day03 :parse?     => part1?, part2?;
+  day04 :generator? => part1, part2;
+}
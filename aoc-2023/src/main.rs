@@ -1,12 +1,3 @@
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-
-aoc_main::main! {
-  year 2023;
-  day01             => part1, part2;
-  day02             => part1, part2;
-  day03             => part1, part2;
-  day04             => part1;
+fn main() {
+    aoc_2023::cli::run()
 }
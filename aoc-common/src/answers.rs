@@ -0,0 +1,321 @@
+//! Real puzzle inputs and their accepted answers, kept out of version control, so every
+//! registered solution can be checked against them.
+//!
+//! Inputs live where `aoc_main` caches them, as `<dir>/<year>/day<day>.txt`, and each accepted
+//! answer alongside as `<dir>/<year>/day<day>.part<part>.txt`. The directory is `input/` at the
+//! root of the workspace, unless `AOC_INPUT_DIR` says otherwise.
+use crate::differential::{disagreements, has_alternatives};
+use crate::runner::{part_number, Day, Report, Year};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// Overrides the directory inputs and answers are kept in
+pub const INPUT_DIR_VARIABLE: &str = "AOC_INPUT_DIR";
+
+/// The directory of inputs for a crate in the workspace
+pub fn input_dir(manifest_dir: &str) -> PathBuf {
+    match std::env::var_os(INPUT_DIR_VARIABLE) {
+        Some(dir) => dir.into(),
+        None => Path::new(manifest_dir).join("..").join("input"),
+    }
+}
+
+pub fn input_path(dir: &Path, year: u16, day: u8) -> PathBuf {
+    dir.join(year.to_string()).join(format!("day{}.txt", day))
+}
+
+pub fn answer_path(dir: &Path, year: u16, day: u8, part: u8) -> PathBuf {
+    dir.join(year.to_string())
+        .join(format!("day{}.part{}.txt", day, part))
+}
+
+/// A day's input, and the accepted answer for each part
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recorded {
+    pub input: String,
+    pub answers: BTreeMap<u8, String>,
+}
+
+fn read_if_present(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(Some(text)),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Answers are compared without trailing whitespace, so they can be edited by hand
+fn normalise(answer: &str) -> &str {
+    answer.trim_end()
+}
+
+impl Recorded {
+    /// The recorded input and answers for a day, or `None` if there is no input
+    pub fn load(dir: &Path, day: &Day) -> io::Result<Option<Self>> {
+        let Some(mut input) = read_if_present(&input_path(dir, day.year, day.day))? else {
+            return Ok(None);
+        };
+
+        // As `aoc_main` does when it reads a cached input
+        if input.ends_with('\n') {
+            input.pop();
+        }
+
+        let mut answers = BTreeMap::new();
+        for part in day.solutions.iter().filter_map(|name| part_number(name)) {
+            if let Some(answer) = read_if_present(&answer_path(dir, day.year, day.day, part))? {
+                answers.insert(part, answer);
+            }
+        }
+
+        Ok(Some(Recorded { input, answers }))
+    }
+
+    /// Saves the input, unless it is already there, and the answer to each part in `report`.
+    /// Solutions which failed are left out, as is any part whose solutions disagree.
+    pub fn save(dir: &Path, day: &Day, input: &str, report: &Report) -> io::Result<Self> {
+        let path = input_path(dir, day.year, day.day);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if !path.exists() {
+            fs::write(&path, input)?;
+        }
+
+        let mut answers = BTreeMap::new();
+        let mut disputed = Vec::new();
+        for outcome in &report.solutions {
+            let (Some(part), Ok(answer)) = (part_number(outcome.name), &outcome.result) else {
+                continue;
+            };
+
+            match answers.insert(part, answer.clone()) {
+                Some(previous) if normalise(&previous) != normalise(answer) => disputed.push(part),
+                _ => {}
+            }
+        }
+        for part in disputed {
+            answers.remove(&part);
+        }
+
+        for (part, answer) in &answers {
+            fs::write(answer_path(dir, day.year, day.day, *part), answer)?;
+        }
+
+        Ok(Recorded {
+            input: input.to_string(),
+            answers,
+        })
+    }
+
+    /// A description of every solution in `report` whose answer differs from the recorded one,
+    /// and of every part without a recorded answer whose solutions disagree
+    pub fn mismatches(&self, day: &Day, report: &Report) -> Vec<String> {
+        let name = format!("{} day {}", day.year, day.day);
+        let mut mismatches = Vec::new();
+
+        if let Some(Err(message)) = report.generator.as_ref().map(|outcome| &outcome.result) {
+            mismatches.push(format!("{}: generator failed: {}", name, message));
+        }
+
+        for outcome in &report.solutions {
+            let Some(expected) = part_number(outcome.name).and_then(|part| self.answers.get(&part))
+            else {
+                continue;
+            };
+
+            match &outcome.result {
+                Ok(answer) if normalise(answer) == normalise(expected) => {}
+                Ok(answer) => mismatches.push(format!(
+                    "{}: {} answered {:?}, but {:?} was accepted",
+                    name,
+                    outcome.name,
+                    normalise(answer),
+                    normalise(expected)
+                )),
+                Err(message) => {
+                    mismatches.push(format!("{}: {} failed: {}", name, outcome.name, message))
+                }
+            }
+        }
+
+        let unanswered = Report {
+            generator: None,
+            solutions: report
+                .solutions
+                .iter()
+                .filter(|outcome| {
+                    part_number(outcome.name).is_some_and(|part| !self.answers.contains_key(&part))
+                })
+                .cloned()
+                .collect(),
+        };
+        mismatches.extend(disagreements(day, &unanswered));
+
+        mismatches
+    }
+}
+
+/// What came of checking a year against its recorded answers
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Verification {
+    /// The number of days run on their recorded input
+    pub checked: usize,
+    /// Days without an input or answers, and why
+    pub skipped: Vec<String>,
+    pub mismatches: Vec<String>,
+}
+
+/// Runs every day with a recorded input, and compares its answers to the accepted ones. Days
+/// without answers are still run if they have alternative solutions, which should agree.
+pub fn verify(dir: &Path, year: &Year) -> io::Result<Verification> {
+    let mut verification = Verification::default();
+
+    for day in &year.days {
+        let name = format!("{} day {}", day.year, day.day);
+
+        match Recorded::load(dir, day)? {
+            None => verification.skipped.push(format!("{}: no input", name)),
+            Some(recorded) if recorded.answers.is_empty() && !has_alternatives(day) => {
+                verification.skipped.push(format!("{}: no answers", name))
+            }
+            Some(recorded) => {
+                let report = day.run_all(&recorded.input);
+                verification
+                    .mismatches
+                    .extend(recorded.mismatches(day, &report));
+                verification.checked += 1;
+            }
+        }
+    }
+
+    Ok(verification)
+}
+
+/// The regression test generated for each year by [`solutions!`](crate::solutions)
+pub fn assert_recorded(year: &Year, manifest_dir: &str) {
+    let dir = input_dir(manifest_dir);
+    let verification = verify(&dir, year)
+        .unwrap_or_else(|err| panic!("Could not read {}: {}", dir.display(), err));
+
+    for skipped in &verification.skipped {
+        eprintln!("Skipped {}", skipped);
+    }
+
+    assert!(
+        verification.mismatches.is_empty(),
+        "Answers differ from those recorded, across {} checked days:\n{}",
+        verification.checked,
+        verification.mismatches.join("\n")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+
+    mod day02 {
+        pub fn part1(input: &str) -> usize {
+            input.len()
+        }
+
+        pub fn part2_fast(input: &str) -> usize {
+            input.lines().count()
+        }
+
+        pub fn part2_slow(input: &str) -> usize {
+            input.split('\n').count()
+        }
+    }
+
+    mod day05 {
+        pub fn part1(input: &str) -> Result<u32, std::num::ParseIntError> {
+            input.parse()
+        }
+    }
+
+    crate::solutions! {
+        year 2016;
+        day02 => part1, part2_fast, part2_slow;
+        day05 => part1?;
+    }
+
+    /// A fresh directory for each test
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-answers-{}-{}", process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_record_and_verify() -> io::Result<()> {
+        let dir = scratch("record");
+        let year = solutions();
+        let day = year.day(2).unwrap();
+
+        assert_eq!(Recorded::load(&dir, day)?, None);
+
+        let input = "ab\ncd";
+        let recorded = Recorded::save(&dir, day, input, &day.run_all(input))?;
+        assert_eq!(
+            recorded.answers,
+            BTreeMap::from([(1, String::from("5")), (2, String::from("2"))])
+        );
+        assert_eq!(Recorded::load(&dir, day)?, Some(recorded));
+
+        let verification = verify(&dir, &year)?;
+        assert_eq!(verification.checked, 1);
+        assert_eq!(verification.skipped, vec!["2016 day 5: no input"]);
+        assert!(verification.mismatches.is_empty());
+
+        // A newline added by an editor is not a different answer
+        fs::write(answer_path(&dir, 2016, 2, 1), "5\n")?;
+        fs::write(answer_path(&dir, 2016, 2, 2), "3")?;
+        assert_eq!(
+            verify(&dir, &year)?.mismatches,
+            vec![
+                "2016 day 2: part2_fast answered \"2\", but \"3\" was accepted",
+                "2016 day 2: part2_slow answered \"2\", but \"3\" was accepted"
+            ]
+        );
+
+        fs::remove_dir_all(&dir)
+    }
+
+    #[test]
+    fn test_disputed_and_failed() -> io::Result<()> {
+        let dir = scratch("disputed");
+        let year = solutions();
+
+        // The two part 2 solutions disagree when the input ends with a newline
+        let day = year.day(2).unwrap();
+        let input = "ab\n";
+        let recorded = Recorded::save(&dir, day, input, &day.run_all(input))?;
+        assert_eq!(recorded.answers.keys().collect::<Vec<_>>(), vec![&1]);
+        assert_eq!(
+            recorded.mismatches(day, &day.run_all(input)),
+            vec![
+                "2016 day 2: part 2 solutions disagree: part2_fast answered \"1\", \
+                 part2_slow answered \"2\""
+            ]
+        );
+
+        let day = year.day(5).unwrap();
+        fs::create_dir_all(dir.join("2016"))?;
+        fs::write(input_path(&dir, 2016, 5), "x\n")?;
+        assert_eq!(verify(&dir, &year)?.skipped, vec!["2016 day 5: no answers"]);
+
+        fs::write(answer_path(&dir, 2016, 5, 1), "7")?;
+        let recorded = Recorded::load(&dir, day)?.unwrap();
+        assert_eq!(recorded.input, "x");
+        assert_eq!(
+            recorded.mismatches(day, &day.run_all(&recorded.input)),
+            vec!["2016 day 5: part1 failed: invalid digit found in string"]
+        );
+
+        fs::remove_dir_all(&dir)
+    }
+}
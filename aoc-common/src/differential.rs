@@ -0,0 +1,204 @@
+//! Checking that different implementations of the same thing give the same answers.
+//!
+//! Alternative solutions to a part are registered with [`solutions!`](crate::solutions) under
+//! names like `part2_hashset` and `part2_loops`; [`disagreements`] compares what they answered,
+//! which is done for every recorded input by [`verify`](crate::answers::verify). Helpers which
+//! aren't solutions themselves are compared with [`Implementations`], on samples or on inputs
+//! generated by a property test.
+use crate::runner::{part_number, Day, Report};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use thiserror::Error;
+
+/// What each implementation answered, when they didn't all answer the same
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+#[error("Implementations disagree on {input}:\n{}", answers.join("\n"))]
+pub struct Disagreement {
+    /// The input, as `Debug` would show it
+    pub input: String,
+    /// An `<implementation> answered <answer>` line for each implementation
+    pub answers: Vec<String>,
+}
+
+type Implementation<'a, I, O> = Box<dyn Fn(&I) -> O + 'a>;
+
+/// Named implementations of the same function, built up with [`with`](Self::with)
+pub struct Implementations<'a, I: ?Sized, O> {
+    implementations: Vec<(&'static str, Implementation<'a, I, O>)>,
+}
+
+impl<'a, I: ?Sized, O> Default for Implementations<'a, I, O> {
+    fn default() -> Self {
+        Implementations {
+            implementations: Vec::new(),
+        }
+    }
+}
+
+impl<'a, I: Debug + ?Sized, O: Debug + PartialEq> Implementations<'a, I, O> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, name: &'static str, implementation: impl Fn(&I) -> O + 'a) -> Self {
+        self.implementations.push((name, Box::new(implementation)));
+        self
+    }
+
+    /// Runs every implementation on `input`, returning the answer they agree on
+    pub fn check(&self, input: &I) -> Result<O, Disagreement> {
+        let mut answers = self
+            .implementations
+            .iter()
+            .map(|(name, implementation)| (*name, implementation(input)))
+            .collect::<Vec<_>>();
+
+        if answers.iter().all(|(_, answer)| *answer == answers[0].1) {
+            let (_, answer) = answers.swap_remove(0);
+            return Ok(answer);
+        }
+
+        Err(Disagreement {
+            input: format!("{:?}", input),
+            answers: answers
+                .iter()
+                .map(|(name, answer)| format!("{} answered {:?}", name, answer))
+                .collect(),
+        })
+    }
+
+    /// As [`check`](Self::check), panicking with what each implementation answered if they
+    /// disagree
+    pub fn assert_agree(&self, input: &I) -> O {
+        self.check(input)
+            .unwrap_or_else(|disagreement| panic!("{}", disagreement))
+    }
+}
+
+/// A description of each part in `report` whose solutions gave different answers. Solutions which
+/// failed are compared by their error, so one failing where another succeeds is a disagreement.
+pub fn disagreements(day: &Day, report: &Report) -> Vec<String> {
+    let mut parts = BTreeMap::<_, Vec<_>>::new();
+    for outcome in &report.solutions {
+        if let Some(part) = part_number(outcome.name) {
+            parts.entry(part).or_default().push(outcome);
+        }
+    }
+
+    parts
+        .into_iter()
+        .filter(|(_, outcomes)| {
+            outcomes
+                .iter()
+                .any(|outcome| outcome.result != outcomes[0].result)
+        })
+        .map(|(part, outcomes)| {
+            let answers = outcomes
+                .iter()
+                .map(|outcome| match &outcome.result {
+                    Ok(answer) => format!("{} answered {:?}", outcome.name, answer),
+                    Err(message) => format!("{} failed: {}", outcome.name, message),
+                })
+                .collect::<Vec<_>>();
+
+            format!(
+                "{} day {}: part {} solutions disagree: {}",
+                day.year,
+                day.day,
+                part,
+                answers.join(", ")
+            )
+        })
+        .collect()
+}
+
+/// Runs every solution for `day` on `input`, and panics if any for the same part disagree
+pub fn assert_solutions_agree(day: &Day, input: &str) {
+    let disagreements = disagreements(day, &day.run_all(input));
+
+    assert!(disagreements.is_empty(), "{}", disagreements.join("\n"));
+}
+
+/// Whether a day has more than one solution to any part, so there is something to compare
+pub fn has_alternatives(day: &Day) -> bool {
+    let mut parts = day.solutions.iter().filter_map(|name| part_number(name));
+    let mut seen = Vec::new();
+
+    parts.any(|part| {
+        let repeated = seen.contains(&part);
+        seen.push(part);
+        repeated
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod day07 {
+        pub fn part1(input: &str) -> usize {
+            input.len()
+        }
+
+        pub fn part2_chars(input: &str) -> usize {
+            input.chars().filter(char::is_ascii_digit).count()
+        }
+
+        pub fn part2_bytes(input: &str) -> usize {
+            input.bytes().filter(u8::is_ascii_digit).count()
+        }
+
+        pub fn part2_wrong(input: &str) -> usize {
+            input.len()
+        }
+    }
+
+    mod day08 {
+        pub use super::day07::{part2_bytes, part2_chars};
+    }
+
+    crate::solutions! {
+        year 2017;
+        day07 => part1, part2_chars, part2_bytes, part2_wrong;
+        day08 => part2_chars, part2_bytes;
+    }
+
+    #[test]
+    fn test_implementations() {
+        let maximums = Implementations::new()
+            .with("max", |numbers: &[u32]| numbers.iter().max().copied())
+            .with("reduce", |numbers: &[u32]| {
+                numbers.iter().copied().reduce(u32::max)
+            });
+        assert_eq!(maximums.assert_agree(&[1, 3, 2][..]), Some(3));
+
+        let lengths = Implementations::new()
+            .with("bytes", |s: &str| s.len())
+            .with("chars", |s: &str| s.chars().count());
+        assert_eq!(lengths.check("abc"), Ok(3));
+        assert_eq!(
+            lengths.check("é").unwrap_err().to_string(),
+            "Implementations disagree on \"é\":\nbytes answered 2\nchars answered 1"
+        );
+    }
+
+    #[test]
+    fn test_disagreements() {
+        let year = solutions();
+        let day = year.day(7).unwrap();
+
+        assert!(has_alternatives(day));
+        assert!(disagreements(day, &day.run_all("")).is_empty());
+        assert_eq!(
+            disagreements(day, &day.run_all("a1")),
+            vec![
+                "2017 day 7: part 2 solutions disagree: part2_chars answered \"1\", \
+                  part2_bytes answered \"1\", part2_wrong answered \"2\""
+            ]
+        );
+
+        let day = year.day(8).unwrap();
+        assert!(has_alternatives(day));
+        assert_solutions_agree(day, "a1b22");
+    }
+}
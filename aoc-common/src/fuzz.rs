@@ -0,0 +1,159 @@
+//! Feeding arbitrary bytes to each day's generator, which should reject what it can't parse
+//! rather than panic, and then to its solutions, which should fail with an error on whatever the
+//! generator lets through.
+//!
+//! The fuzz targets in `fuzz/`, one for each year, call [`run`]. The first byte of the data is
+//! the day, and the rest is its input. Their corpus, under `fuzz/corpus/<target>/`, is seeded
+//! from the samples in each day's tests, and is replayed by a test generated by
+//! [`solutions!`](crate::solutions), so inputs which once panicked keep being checked without
+//! a fuzzer.
+use crate::runner::Year;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::str;
+
+/// The name of the fuzz target for a year
+pub fn target(year: u16) -> String {
+    format!("aoc_{}", year)
+}
+
+/// Fuzz data which gives `input` to a day
+pub fn data(day: u8, input: &str) -> Vec<u8> {
+    let mut data = vec![day];
+    data.extend_from_slice(input.as_bytes());
+    data
+}
+
+/// Runs the day picked by the first byte on the rest, if it is text: its generator, then each
+/// of its solutions if the input parsed. Data for days which aren't solved is ignored.
+pub fn run(year: &Year, data: &[u8]) {
+    let Some((day, input)) = data.split_first() else {
+        return;
+    };
+
+    if let (Some(day), Ok(input)) = (year.day(*day), str::from_utf8(input)) {
+        let report = day.run_all(input);
+
+        // The runner reports panics as errors, but here they are what is being looked for
+        if let Some(outcome) = report.panic() {
+            panic!("{} {}", outcome.name, outcome.result.as_ref().unwrap_err());
+        }
+    }
+}
+
+/// The corpus directory of a year's fuzz target, for a crate in the workspace
+pub fn corpus_dir(manifest_dir: &str, year: u16) -> PathBuf {
+    Path::new(manifest_dir)
+        .join("..")
+        .join("fuzz")
+        .join("corpus")
+        .join(target(year))
+}
+
+/// Every file in a corpus, in order of name, or none if there is no corpus
+pub fn corpus(dir: &Path) -> io::Result<Vec<(PathBuf, Vec<u8>)>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    let mut paths = entries
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| fs::read(&path).map(|data| (path, data)))
+        .collect()
+}
+
+/// The corpus test generated for each year by [`solutions!`](crate::solutions). A panic names
+/// the file which caused it.
+pub fn assert_corpus(year: &Year, manifest_dir: &str) {
+    let dir = corpus_dir(manifest_dir, year.year);
+    let corpus =
+        corpus(&dir).unwrap_or_else(|err| panic!("Could not read {}: {}", dir.display(), err));
+
+    for (path, data) in corpus {
+        eprintln!("Running {}", path.display());
+        run(year, &data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+
+    mod day03 {
+        pub fn part1(input: &str) -> usize {
+            match input {
+                "panic" => panic!("Unexpected {:?}", input),
+                _ => input.len(),
+            }
+        }
+    }
+
+    mod day04 {
+        pub fn parse(input: &str) -> Result<Vec<u8>, String> {
+            match input {
+                "panic" => panic!("Unexpected {:?}", input),
+                _ => Ok(input.bytes().collect()),
+            }
+        }
+
+        pub fn part1(input: &[u8]) -> u8 {
+            input[0]
+        }
+    }
+
+    crate::solutions! {
+        year 2018;
+        day03         => part1;
+        day04 :parse? => part1;
+    }
+
+    #[test]
+    fn test_run() {
+        let year = solutions();
+
+        run(&year, &[]);
+        run(&year, &data(4, "ok"));
+        run(&year, &data(5, "panic"));
+        run(&year, &[4, 0xff, b'p']);
+        run(&year, &data(3, "ok"));
+
+        assert!(std::panic::catch_unwind(|| run(&year, &data(4, "panic"))).is_err());
+        assert!(std::panic::catch_unwind(|| run(&year, &data(3, "panic"))).is_err());
+        // The generator accepts no bytes at all, but the solution doesn't
+        assert!(std::panic::catch_unwind(|| run(&year, &data(4, ""))).is_err());
+    }
+
+    #[test]
+    fn test_corpus() -> io::Result<()> {
+        let dir = std::env::temp_dir().join(format!("aoc-fuzz-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(corpus(&dir)?, vec![]);
+
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join("b"), data(4, "two"))?;
+        fs::write(dir.join("a"), data(4, "one"))?;
+        assert_eq!(
+            corpus(&dir)?,
+            vec![
+                (dir.join("a"), b"\x04one".to_vec()),
+                (dir.join("b"), b"\x04two".to_vec())
+            ]
+        );
+
+        assert_eq!(
+            corpus_dir("/aoc/aoc-2018", 2018),
+            Path::new("/aoc/aoc-2018/../fuzz/corpus/aoc_2018")
+        );
+
+        fs::remove_dir_all(&dir)
+    }
+}
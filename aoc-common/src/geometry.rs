@@ -0,0 +1,466 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+/// The signed integer types coordinates can be made of
+pub trait Scalar:
+    Copy
+    + Ord
+    + Default
+    + fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs(self) -> Self;
+    fn signum(self) -> Self;
+}
+
+macro_rules! scalar {
+    ($($t:ty),*) => {
+        $(
+            impl Scalar for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+
+                fn signum(self) -> Self {
+                    <$t>::signum(self)
+                }
+            }
+        )*
+    };
+}
+
+scalar!(i8, i16, i32, i64, i128, isize);
+
+/// A location on a 2D plane
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A displacement between two [`Point`]s
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vector<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point { x, y }
+    }
+}
+
+impl<T: Scalar> Point<T> {
+    pub fn origin() -> Self {
+        Point::new(T::ZERO, T::ZERO)
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        (*other - *self).manhattan()
+    }
+
+    /// The number of king's moves between two points
+    pub fn chebyshev_distance(&self, other: &Self) -> T {
+        (*other - *self).chebyshev()
+    }
+
+    /// The 4 orthogonally adjacent points
+    pub fn neighbours(&self) -> [Point<T>; 4] {
+        Direction::ALL.map(|direction| *self + direction.vector())
+    }
+}
+
+impl<T> Vector<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Vector { x, y }
+    }
+}
+
+impl<T: Scalar> Vector<T> {
+    pub fn manhattan(&self) -> T {
+        self.x.abs() + self.y.abs()
+    }
+
+    pub fn chebyshev(&self) -> T {
+        self.x.abs().max(self.y.abs())
+    }
+
+    /// The vector with each component clamped to -1, 0 or 1
+    pub fn signum(&self) -> Self {
+        Vector::new(self.x.signum(), self.y.signum())
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<T: Add<Output = T>> Add<Vector<T>> for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, rhs: Vector<T>) -> Self::Output {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub<Vector<T>> for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, rhs: Vector<T>) -> Self::Output {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Vector<T>;
+
+    fn sub(self, rhs: Point<T>) -> Self::Output {
+        Vector::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Copy + Add<Output = T>> AddAssign<Vector<T>> for Point<T> {
+    fn add_assign(&mut self, rhs: Vector<T>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Copy + Sub<Output = T>> SubAssign<Vector<T>> for Point<T> {
+    fn sub_assign(&mut self, rhs: Vector<T>) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Add<Output = T>> Add for Vector<T> {
+    type Output = Vector<T>;
+
+    fn add(self, rhs: Vector<T>) -> Self::Output {
+        Vector::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Vector<T> {
+    type Output = Vector<T>;
+
+    fn sub(self, rhs: Vector<T>) -> Self::Output {
+        Vector::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Copy + Add<Output = T>> AddAssign for Vector<T> {
+    fn add_assign(&mut self, rhs: Vector<T>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Vector<T> {
+    type Output = Vector<T>;
+
+    fn neg(self) -> Self::Output {
+        Vector::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for Vector<T> {
+    type Output = Vector<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Vector::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: Copy + Div<Output = T>> Div<T> for Vector<T> {
+    type Output = Vector<T>;
+
+    fn div(self, rhs: T) -> Self::Output {
+        Vector::new(self.x / rhs, self.y / rhs)
+    }
+}
+
+/// One of the four orthogonal directions, with `y` increasing upwards
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Every direction, clockwise from up
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// A unit vector pointing in the direction
+    pub fn vector<T: Scalar>(&self) -> Vector<T> {
+        match self {
+            Direction::Up => Vector::new(T::ZERO, T::ONE),
+            Direction::Right => Vector::new(T::ONE, T::ZERO),
+            Direction::Down => Vector::new(T::ZERO, -T::ONE),
+            Direction::Left => Vector::new(-T::ONE, T::ZERO),
+        }
+    }
+
+    pub fn turn_right(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn turn_left(&self) -> Direction {
+        self.turn_right().turn_right().turn_right()
+    }
+
+    pub fn reverse(&self) -> Direction {
+        self.turn_right().turn_right()
+    }
+}
+
+/// Reads `U`, `R`, `D` and `L`, or compass points `N`, `E`, `S` and `W`
+impl TryFrom<char> for Direction {
+    type Error = char;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'U' | 'N' => Ok(Direction::Up),
+            'R' | 'E' => Ok(Direction::Right),
+            'D' | 'S' => Ok(Direction::Down),
+            'L' | 'W' => Ok(Direction::Left),
+            _ => Err(value),
+        }
+    }
+}
+
+/// The smallest axis-aligned rectangle containing a set of points, including its edges
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bounds<T> {
+    pub min: Point<T>,
+    pub max: Point<T>,
+}
+
+impl<T: Scalar> Bounds<T> {
+    pub fn new(min: Point<T>, max: Point<T>) -> Self {
+        Bounds { min, max }
+    }
+
+    /// The bounds of every point given, or `None` if there are none
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Point<T>>) -> Option<Self>
+    where
+        T: 'a,
+    {
+        let mut points = points.into_iter();
+        let first = *points.next()?;
+
+        Some(points.fold(Bounds::new(first, first), |mut bounds, point| {
+            bounds.extend(point);
+            bounds
+        }))
+    }
+
+    /// Grows the bounds to include `point`
+    pub fn extend(&mut self, point: &Point<T>) {
+        self.min = Point::new(self.min.x.min(point.x), self.min.y.min(point.y));
+        self.max = Point::new(self.max.x.max(point.x), self.max.y.max(point.y));
+    }
+
+    pub fn contains(&self, point: &Point<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x + T::ONE
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y + T::ONE
+    }
+
+    /// Every point inside the bounds, row by row from `min.y`, then left to right
+    pub fn points(&self) -> impl Iterator<Item = Point<T>> {
+        let Bounds { min, max } = *self;
+
+        std::iter::successors(Some(min.y), move |y| Some(*y + T::ONE))
+            .take_while(move |y| *y <= max.y)
+            .flat_map(move |y| {
+                std::iter::successors(Some(min.x), |x| Some(*x + T::ONE))
+                    .take_while(move |x| *x <= max.x)
+                    .map(move |x| Point::new(x, y))
+            })
+    }
+}
+
+/// A location in 3D space
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+/// A displacement between two [`Point3`]s
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vector3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: Scalar> Point3<T> {
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        (*other - *self).manhattan()
+    }
+
+    pub fn chebyshev_distance(&self, other: &Self) -> T {
+        (*other - *self).chebyshev()
+    }
+}
+
+impl<T> Vector3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Vector3 { x, y, z }
+    }
+}
+
+impl<T: Scalar> Vector3<T> {
+    pub fn manhattan(&self) -> T {
+        self.x.abs() + self.y.abs() + self.z.abs()
+    }
+
+    pub fn chebyshev(&self) -> T {
+        self.x.abs().max(self.y.abs()).max(self.z.abs())
+    }
+
+    pub fn signum(&self) -> Self {
+        Vector3::new(self.x.signum(), self.y.signum(), self.z.signum())
+    }
+}
+
+impl<T: Add<Output = T>> Add<Vector3<T>> for Point3<T> {
+    type Output = Point3<T>;
+
+    fn add(self, rhs: Vector3<T>) -> Self::Output {
+        Point3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Vector3<T>;
+
+    fn sub(self, rhs: Point3<T>) -> Self::Output {
+        Vector3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Vector3<T> {
+    type Output = Vector3<T>;
+
+    fn add(self, rhs: Vector3<T>) -> Self::Output {
+        Vector3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for Vector3<T> {
+    type Output = Vector3<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Vector3::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point::new(1, 2);
+        let b = Point::new(4, -2);
+
+        assert_eq!(b - a, Vector::new(3, -4));
+        assert_eq!(a + (b - a), b);
+        assert_eq!(a - Vector::new(1, 1), Point::new(0, 1));
+        assert_eq!(Vector::new(3, -4) * 2, Vector::new(6, -8));
+        assert_eq!(Vector::new(6, -8) / 2, Vector::new(3, -4));
+        assert_eq!(-Vector::new(3, -4), Vector::new(-3, 4));
+        assert_eq!(Vector::new(3, -4).signum(), Vector::new(1, -1));
+
+        let mut c = a;
+        c += Vector::new(1, 0);
+        assert_eq!(c, Point::new(2, 2));
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point::new(1i64, 2);
+        let b = Point::new(4, -2);
+
+        assert_eq!(a.manhattan_distance(&b), 7);
+        assert_eq!(a.chebyshev_distance(&b), 4);
+        assert_eq!(Point::origin().manhattan_distance(&a), 3);
+
+        let c = Point3::new(1, 2, 3);
+        let d = Point3::new(-1, 2, 8);
+        assert_eq!(c.manhattan_distance(&d), 7);
+        assert_eq!(c.chebyshev_distance(&d), 5);
+        assert_eq!(c + (d - c), d);
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.reverse(), Direction::Right);
+        assert_eq!(Direction::try_from('D'), Ok(Direction::Down));
+        assert_eq!(Direction::try_from('X'), Err('X'));
+        assert_eq!(Direction::Down.vector::<i32>(), Vector::new(0, -1));
+        assert_eq!(
+            Point::new(0, 0).neighbours(),
+            [
+                Point::new(0, 1),
+                Point::new(1, 0),
+                Point::new(0, -1),
+                Point::new(-1, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bounds() {
+        let points = [Point::new(1, 5), Point::new(-2, 3), Point::new(0, 4)];
+        let bounds = Bounds::from_points(&points).unwrap();
+
+        assert_eq!(bounds, Bounds::new(Point::new(-2, 3), Point::new(1, 5)));
+        assert_eq!((bounds.width(), bounds.height()), (4, 3));
+        assert!(bounds.contains(&Point::new(0, 3)));
+        assert!(!bounds.contains(&Point::new(2, 3)));
+        assert_eq!(bounds.points().count(), 12);
+        assert_eq!(bounds.points().nth(4), Some(Point::new(-2, 4)));
+        assert_eq!(Bounds::<i32>::from_points(&[]), None);
+    }
+}
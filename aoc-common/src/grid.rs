@@ -0,0 +1,358 @@
+use std::fmt::{self, Write};
+use std::iter;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// A cell's `(x, y)` coordinates, where `x` is the column and `y` the row, counting from the top left
+pub type Position = (usize, usize);
+
+/// The four orthogonal steps as `(dx, dy)`, clockwise from up
+pub const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// The eight orthogonal and diagonal steps as `(dx, dy)`, clockwise from up
+pub const ALL_DIRECTIONS: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ParseGridError {
+    #[error("Line {line} has {found} cells, but the first line has {expected}")]
+    Ragged {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("Unexpected {found:?} at line {line}, column {column}")]
+    InvalidCell {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+}
+
+/// A rectangular grid of cells, stored row by row
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid {
+            cells: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its rows, which must all be the same length
+    pub fn from_rows(rows: impl IntoIterator<Item = Vec<T>>) -> Result<Self, ParseGridError> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for (index, row) in rows.into_iter().enumerate() {
+            let expected = *width.get_or_insert(row.len());
+            if row.len() != expected {
+                return Err(ParseGridError::Ragged {
+                    line: index + 1,
+                    expected,
+                    found: row.len(),
+                });
+            }
+
+            cells.extend(row);
+            height += 1;
+        }
+
+        Ok(Grid {
+            cells,
+            width: width.unwrap_or_default(),
+            height,
+        })
+    }
+
+    /// Parses one row per line of `text`, converting each character with `cell`
+    pub fn parse(
+        text: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseGridError> {
+        let rows = text
+            .lines()
+            .enumerate()
+            .map(|(line, row)| {
+                row.chars()
+                    .enumerate()
+                    .map(|(column, found)| {
+                        cell(found).ok_or(ParseGridError::InvalidCell {
+                            line: line + 1,
+                            column: column + 1,
+                            found,
+                        })
+                    })
+                    .collect()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Grid::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Position) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.index_of(position).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.index_of(position).map(|index| &mut self.cells[index])
+    }
+
+    /// Every position in the grid, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// Every cell in the grid alongside its position, row by row
+    pub fn enumerate(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// The position one step of `(dx, dy)` away, if it is inside the grid
+    pub fn step(&self, (x, y): Position, (dx, dy): (isize, isize)) -> Option<Position> {
+        let next = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.contains(next).then_some(next)
+    }
+
+    /// The orthogonally adjacent positions inside the grid
+    pub fn neighbours(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        ORTHOGONAL
+            .into_iter()
+            .filter_map(move |direction| self.step(position, direction))
+    }
+
+    /// The orthogonally and diagonally adjacent positions inside the grid
+    pub fn all_neighbours(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        ALL_DIRECTIONS
+            .into_iter()
+            .filter_map(move |direction| self.step(position, direction))
+    }
+
+    /// The cells of row `y`, from left to right
+    pub fn row(&self, y: usize) -> impl DoubleEndedIterator<Item = &T> {
+        let cells = if y < self.height {
+            &self.cells[y * self.width..(y + 1) * self.width]
+        } else {
+            &[]
+        };
+
+        cells.iter()
+    }
+
+    /// The cells of column `x`, from top to bottom
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        let cells = if x < self.width {
+            &self.cells[x..]
+        } else {
+            &[]
+        };
+
+        cells.iter().step_by(self.width.max(1))
+    }
+
+    /// The cells met by repeatedly stepping `(dx, dy)` away from `position`, up to the edge of the
+    /// grid. The starting cell itself is not included.
+    pub fn ray(
+        &self,
+        position: Position,
+        direction: (isize, isize),
+    ) -> impl Iterator<Item = (Position, &T)> {
+        iter::successors(self.step(position, direction), move |position| {
+            self.step(*position, direction)
+        })
+        .map(|position| (position, &self[position]))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn index_of(&self, position: Position) -> Option<usize> {
+        self.contains(position)
+            .then(|| position.1 * self.width + position.0)
+    }
+}
+
+impl Grid<u8> {
+    /// Parses a grid of single decimal digits
+    pub fn digits(text: &str) -> Result<Self, ParseGridError> {
+        Grid::parse(text, |c| c.to_digit(10).map(|digit| digit as u8))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = ParseGridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::parse(s, Some)
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &Self::Output {
+        match self.index_of(position) {
+            Some(index) => &self.cells[index],
+            None => panic!(
+                "Position {:?} is outside of a {}x{} grid",
+                position, self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut Self::Output {
+        match self.index_of(position) {
+            Some(index) => &mut self.cells[index],
+            None => panic!(
+                "Position {:?} is outside of a {}x{} grid",
+                position, self.width, self.height
+            ),
+        }
+    }
+}
+
+/// Writes each row on its own line. Any width or alignment given is applied to every cell.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..self.height {
+            for cell in self.row(y) {
+                cell.fmt(f)?;
+            }
+
+            f.write_char('\n')?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "123\n456";
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::digits(SAMPLE).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get((2, 1)), Some(&6));
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid[(0, 1)], 4);
+
+        assert_eq!(
+            Grid::digits("12\n3"),
+            Err(ParseGridError::Ragged {
+                line: 2,
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            Grid::digits("12\n3x"),
+            Err(ParseGridError::InvalidCell {
+                line: 2,
+                column: 2,
+                found: 'x'
+            })
+        );
+        assert_eq!("".parse::<Grid<char>>().map(|grid| grid.height()), Ok(0));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::digits(SAMPLE).unwrap();
+
+        assert_eq!(
+            grid.neighbours((0, 0)).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(
+            grid.neighbours((1, 1)).collect::<Vec<_>>(),
+            vec![(1, 0), (2, 1), (0, 1)]
+        );
+        assert_eq!(
+            grid.all_neighbours((1, 1)).collect::<Vec<_>>(),
+            vec![(1, 0), (2, 0), (2, 1), (0, 1), (0, 0)]
+        );
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = Grid::digits(SAMPLE).unwrap();
+
+        assert_eq!(grid.row(1).collect::<Vec<_>>(), vec![&4, &5, &6]);
+        assert_eq!(grid.row(1).rev().collect::<Vec<_>>(), vec![&6, &5, &4]);
+        assert_eq!(grid.column(2).collect::<Vec<_>>(), vec![&3, &6]);
+        assert_eq!(grid.column(3).count(), 0);
+        assert_eq!(
+            grid.ray((0, 0), (1, 0)).collect::<Vec<_>>(),
+            vec![((1, 0), &2), ((2, 0), &3)]
+        );
+        assert_eq!(
+            grid.ray((0, 0), (1, 1)).collect::<Vec<_>>(),
+            vec![((1, 1), &5)]
+        );
+        assert_eq!(grid.ray((0, 0), (-1, 0)).count(), 0);
+    }
+
+    #[test]
+    fn test_display() {
+        let grid = Grid::digits(SAMPLE).unwrap();
+
+        assert_eq!(grid.to_string(), "123\n456\n");
+        assert_eq!(format!("{:<2}", grid), "1 2 3 \n4 5 6 \n");
+        assert_eq!(
+            grid.map(|digit| if digit % 2 == 0 { '#' } else { '.' })
+                .to_string(),
+            ".#.\n#.#\n"
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! Errors in puzzle inputs, which point at the text that could not be parsed
+use crate::grid::ParseGridError;
+use std::fmt;
+use thiserror::Error;
+
+/// Where in a day's input parsing went wrong, and why
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+#[error("Day {day}, line {line}, column {column}: {message} at {text:?}")]
+pub struct InputError {
+    pub day: u8,
+    /// Counting from 1, or 0 if the text is not part of the input
+    pub line: usize,
+    /// Counting characters from 1, or 0 if the text is not part of the input
+    pub column: usize,
+    /// The offending text, cut off at the end of its first line
+    pub text: String,
+    pub message: String,
+}
+
+impl InputError {
+    /// An error at `fragment`, which should be a slice of `input`
+    pub fn at(day: u8, input: &str, fragment: &str, message: impl fmt::Display) -> Self {
+        let text = fragment.lines().next().unwrap_or_default().to_string();
+        let message = message.to_string();
+
+        let start = input.as_ptr() as usize;
+        let offset = (fragment.as_ptr() as usize).wrapping_sub(start);
+        if offset > input.len() {
+            return InputError {
+                day,
+                line: 0,
+                column: 0,
+                text,
+                message,
+            };
+        }
+
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+
+        InputError {
+            day,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            text,
+            message,
+        }
+    }
+
+    /// An error at the end of `input`, for when something is missing
+    pub fn end(day: u8, input: &str, message: impl fmt::Display) -> Self {
+        InputError::at(day, input, &input[input.len()..], message)
+    }
+
+    /// An error from a `nom` parser which was run on a slice of `input`
+    pub fn nom(day: u8, input: &str, err: nom::Err<nom::error::Error<&str>>) -> Self {
+        match err {
+            nom::Err::Incomplete(_) => InputError::end(day, input, "unexpected end of input"),
+            nom::Err::Error(err) | nom::Err::Failure(err) => InputError::at(
+                day,
+                input,
+                err.input,
+                format_args!("expected {}", err.code.description()),
+            ),
+        }
+    }
+
+    /// An error from parsing all of `input` as a [`Grid`](crate::grid::Grid)
+    pub fn grid(day: u8, input: &str, err: ParseGridError) -> Self {
+        let line = |number: usize| input.lines().nth(number - 1).unwrap_or_default();
+
+        match err {
+            ParseGridError::Ragged {
+                line: number,
+                expected,
+                found,
+            } => InputError::at(
+                day,
+                input,
+                line(number),
+                format_args!("expected {} cells, found {}", expected, found),
+            ),
+            ParseGridError::InvalidCell {
+                line: number,
+                column,
+                found,
+            } => {
+                let line = line(number);
+                let offset = line
+                    .char_indices()
+                    .nth(column - 1)
+                    .map_or(line.len(), |(offset, _)| offset);
+
+                InputError::at(
+                    day,
+                    input,
+                    &line[offset..],
+                    format_args!("unexpected {:?}", found),
+                )
+            }
+        }
+    }
+}
+
+/// Parses each of `pieces`, which should be slices of `input`, pointing any error at the piece
+/// which caused it
+pub fn parse_each<'a, T, E: fmt::Display, C: FromIterator<T>>(
+    day: u8,
+    input: &'a str,
+    pieces: impl IntoIterator<Item = &'a str>,
+    mut parse: impl FnMut(&'a str) -> Result<T, E>,
+) -> Result<C, InputError> {
+    pieces
+        .into_iter()
+        .map(|piece| parse(piece).map_err(|err| InputError::at(day, input, piece, err)))
+        .collect()
+}
+
+/// Parses each line of `input`, pointing any error at the line which caused it
+pub fn parse_lines<'a, T, E: fmt::Display, C: FromIterator<T>>(
+    day: u8,
+    input: &'a str,
+    parse: impl FnMut(&'a str) -> Result<T, E>,
+) -> Result<C, InputError> {
+    parse_each(day, input, input.lines(), parse)
+}
+
+/// Runs a `nom` parser over all of `text`, which should be a slice of `input`
+pub fn parse_all<'a, T>(
+    day: u8,
+    input: &'a str,
+    text: &'a str,
+    mut parser: impl nom::Parser<&'a str, T, nom::error::Error<&'a str>>,
+) -> Result<T, InputError> {
+    match parser.parse(text) {
+        Ok(("", parsed)) => Ok(parsed),
+        Ok((rest, _)) => Err(InputError::at(
+            day,
+            input,
+            rest,
+            "unexpected trailing input",
+        )),
+        Err(err) => Err(InputError::nom(day, input, err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::character::complete::{char, digit1};
+    use nom::sequence::separated_pair;
+
+    const INPUT: &str = "12\n3x4\n\n56";
+
+    #[test]
+    fn test_at() {
+        let fragment = &INPUT[4..];
+        assert_eq!(
+            InputError::at(2, INPUT, fragment, "not a number"),
+            InputError {
+                day: 2,
+                line: 2,
+                column: 2,
+                text: String::from("x4"),
+                message: String::from("not a number"),
+            }
+        );
+        assert_eq!(
+            InputError::at(2, INPUT, fragment, "not a number").to_string(),
+            "Day 2, line 2, column 2: not a number at \"x4\""
+        );
+
+        let end = InputError::end(2, INPUT, "missing a line");
+        assert_eq!((end.line, end.column, end.text.as_str()), (4, 3, ""));
+
+        let elsewhere = InputError::at(2, INPUT, &String::from("x4"), "not a number");
+        assert_eq!((elsewhere.line, elsewhere.column), (0, 0));
+    }
+
+    #[test]
+    fn test_grid() {
+        let input = "123\n4x6\n78";
+
+        let err = crate::grid::Grid::digits(input)
+            .map_err(|err| InputError::grid(9, input, err))
+            .unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, "x6"));
+        assert_eq!(err.message, "unexpected 'x'");
+
+        let input = "123\n456\n78";
+        let err = crate::grid::Grid::digits(input)
+            .map_err(|err| InputError::grid(9, input, err))
+            .unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (3, 1, "78"));
+        assert_eq!(err.message, "expected 3 cells, found 2");
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        assert_eq!(parse_lines(1, "1\n2", str::parse::<u8>), Ok(vec![1, 2]));
+
+        let err = parse_lines::<_, _, Vec<_>>(1, INPUT, str::parse::<u8>).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "3x4"));
+        assert_eq!(err.message, "invalid digit found in string");
+
+        let err = parse_each::<_, _, Vec<_>>(1, INPUT, INPUT.split("\n\n"), str::parse::<u8>)
+            .unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 1, "12"));
+    }
+
+    #[test]
+    fn test_parse_all() {
+        let pair = || separated_pair(digit1, char('x'), digit1);
+        let line = &INPUT[3..6];
+
+        assert_eq!(parse_all(5, INPUT, line, pair()), Ok(("3", "4")));
+
+        let err = parse_all(5, INPUT, &INPUT[3..], pair()).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+        assert_eq!(err.message, "unexpected trailing input");
+
+        let err = parse_all(5, INPUT, &INPUT[..2], pair()).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+        assert_eq!(err.message, "expected Char");
+    }
+}
@@ -0,0 +1,15 @@
+//! Building blocks shared between the solutions for each year
+pub mod answers;
+pub mod differential;
+pub mod fuzz;
+pub mod geometry;
+pub mod grid;
+pub mod input;
+pub mod ocr;
+pub mod render;
+pub mod runner;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+
+// Reexported for the code generated by `solutions!`
+pub use aoc_main;
@@ -0,0 +1,246 @@
+//! Reading the capital letters some puzzles draw as their answer, in the two sizes Advent of Code
+//! draws them: 4 cells wide and 6 tall, or 6 wide and 10 tall.
+//!
+//! Solutions return [`answer`], which reads the letters, unless the runner was asked for
+//! [`art`](Options::art), in which case it returns the drawing itself.
+use crate::grid::Grid;
+use crate::runner::Options;
+use thiserror::Error;
+
+/// The letters of one size, and how they are laid out
+struct Font {
+    width: usize,
+    height: usize,
+    /// Blank columns between one letter and the next
+    gap: usize,
+    glyphs: &'static [(char, &'static str)],
+}
+
+const SMALL: Font = Font {
+    width: 4,
+    height: 6,
+    gap: 1,
+    glyphs: &[
+        ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+        ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+        ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+        ('E', "####\n#...\n###.\n#...\n#...\n####"),
+        ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+        ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+        ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+        ('I', ".###\n..#.\n..#.\n..#.\n..#.\n.###"),
+        ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+        ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+        ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+        ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+        ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+        ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+        ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+        ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+        ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+    ],
+};
+
+const LARGE: Font = Font {
+    width: 6,
+    height: 10,
+    gap: 2,
+    glyphs: &[
+        (
+            'A',
+            "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+        ),
+        (
+            'B',
+            "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+        ),
+        (
+            'C',
+            ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+        ),
+        (
+            'E',
+            "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+        ),
+        (
+            'F',
+            "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+        ),
+        (
+            'G',
+            ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+        ),
+        (
+            'H',
+            "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+        ),
+        (
+            'J',
+            "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+        ),
+        (
+            'K',
+            "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+        ),
+        (
+            'L',
+            "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+        ),
+        (
+            'N',
+            "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+        ),
+        (
+            'P',
+            "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+        ),
+        (
+            'R',
+            "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+        ),
+        (
+            'X',
+            "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+        ),
+        (
+            'Z',
+            "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+        ),
+    ],
+};
+
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum OcrError {
+    #[error("Letters are 6 or 10 cells tall, but the drawing is {0} tall")]
+    Height(usize),
+    #[error("Letter {index} is not one that can be read:\n{art}")]
+    Unknown { index: usize, art: String },
+}
+
+/// Draws lit cells as `#` and the rest as `.`, a line for each row
+pub fn art(grid: &Grid<bool>) -> String {
+    grid.map(|lit| if *lit { '#' } else { '.' }).to_string()
+}
+
+/// Reads the letters drawn across `grid`, the first starting in its left-hand column
+pub fn recognise(grid: &Grid<bool>) -> Result<String, OcrError> {
+    let font = match grid.height() {
+        6 => SMALL,
+        10 => LARGE,
+        height => return Err(OcrError::Height(height)),
+    };
+    let pitch = font.width + font.gap;
+
+    (0..grid.width().div_ceil(pitch))
+        .map(|index| {
+            let art = (0..font.height)
+                .map(|y| {
+                    (index * pitch..index * pitch + font.width)
+                        .map(|x| match grid.get((x, y)) {
+                            Some(true) => '#',
+                            _ => '.',
+                        })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            font.glyphs
+                .iter()
+                .find(|(_, glyph)| *glyph == art)
+                .map(|(letter, _)| *letter)
+                .ok_or(OcrError::Unknown { index, art })
+        })
+        .collect()
+}
+
+/// What a solution which draws letters should answer: the letters, or the drawing itself, on a
+/// line of its own, when the current [`Options`] ask for art
+pub fn answer(grid: &Grid<bool>) -> Result<String, OcrError> {
+    if Options::current().art {
+        Ok(format!("\n{}", art(grid)))
+    } else {
+        recognise(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(art: &str) -> Grid<bool> {
+        Grid::parse(art, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_small() {
+        let drawing = "\
+###..####.#..#.###..
+#..#.#....#.#..#..#.
+#..#.###..##...#..#.
+###..#....#.#..###..
+#.#..#....#.#..#.#..
+#..#.####.#..#.#..#.";
+
+        assert_eq!(recognise(&grid(drawing)), Ok(String::from("REKR")));
+        assert_eq!(art(&grid(drawing)), format!("{}\n", drawing));
+
+        // The trailing gap is often left off
+        let trimmed = drawing.lines().map(|line| &line[..19]).collect::<Vec<_>>();
+        assert_eq!(
+            recognise(&grid(&trimmed.join("\n"))),
+            Ok(String::from("REKR"))
+        );
+    }
+
+    #[test]
+    fn test_large() {
+        let drawing = "\
+#....#..######
+##...#..#.....
+##...#..#.....
+#.#..#..#.....
+#.#..#..#####.
+#..#.#..#.....
+#..#.#..#.....
+#...##..#.....
+#...##..#.....
+#....#..######";
+
+        assert_eq!(recognise(&grid(drawing)), Ok(String::from("NE")));
+    }
+
+    #[test]
+    fn test_unknown() {
+        let square = "#####\n#...#\n#...#\n#...#\n#####\n.....";
+
+        assert_eq!(
+            recognise(&grid(square)),
+            Err(OcrError::Unknown {
+                index: 0,
+                art: String::from("####\n#...\n#...\n#...\n####\n....")
+            })
+        );
+        assert_eq!(recognise(&grid("#")), Err(OcrError::Height(1)));
+    }
+
+    #[test]
+    fn test_answer() {
+        let square = "#####\n#...#\n#...#\n#...#\n#####\n.....";
+        let art = Options {
+            art: true,
+            ..Options::default()
+        };
+
+        assert!(answer(&grid(square)).is_err());
+        assert_eq!(
+            art.scope(|| answer(&grid(square))),
+            Ok(format!("\n{}\n", square))
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! Pictures of grids and sets of points, as PPM or SVG images, and numbered frames of them for
+//! watching a simulation step by step.
+//!
+//! Days which simulate something write a frame for each step when the runner is given a
+//! [`frames`](Options::frames) directory, into `<dir>/<year>/day<day>/part<part>/`.
+use crate::geometry::{Bounds, Point, Scalar};
+use crate::grid::Grid;
+use crate::runner::Options;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+
+    pub fn grey(level: u8) -> Self {
+        Rgb(level, level, level)
+    }
+}
+
+/// A grid of colours. Grids of anything else become images with [`Grid::map`].
+pub type Image = Grid<Rgb>;
+
+/// An image of the points inside `bounds`, with the top row at `bounds.min.y`. Points outside
+/// the bounds are left out, and later points are drawn over earlier ones.
+pub fn plot<T>(
+    bounds: &Bounds<T>,
+    points: impl IntoIterator<Item = (Point<T>, Rgb)>,
+    background: Rgb,
+) -> Image
+where
+    T: Scalar + TryInto<usize>,
+{
+    let size = |n: T| n.try_into().unwrap_or_default();
+    let mut image = Grid::new(size(bounds.width()), size(bounds.height()), background);
+
+    for (point, colour) in points
+        .into_iter()
+        .filter(|(point, _)| bounds.contains(point))
+    {
+        let offset = point - bounds.min;
+        image[(size(offset.x), size(offset.y))] = colour;
+    }
+
+    image
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Ppm,
+    Svg,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Ppm => "ppm",
+            Format::Svg => "svg",
+        }
+    }
+
+    /// The format named by a path's extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "ppm" => Some(Format::Ppm),
+            "svg" => Some(Format::Svg),
+            _ => None,
+        }
+    }
+
+    pub fn write(self, image: &Image, out: impl Write) -> io::Result<()> {
+        match self {
+            Format::Ppm => write_ppm(image, out),
+            Format::Svg => write_svg(image, out),
+        }
+    }
+}
+
+/// Writes a binary PPM, which most image viewers and converters can read
+pub fn write_ppm(image: &Image, mut out: impl Write) -> io::Result<()> {
+    write!(out, "P6\n{} {}\n255\n", image.width(), image.height())?;
+
+    for Rgb(r, g, b) in image.iter() {
+        out.write_all(&[*r, *g, *b])?;
+    }
+
+    out.flush()
+}
+
+/// Writes an SVG with a square for each cell, scaled up so it can be seen in a browser
+pub fn write_svg(image: &Image, mut out: impl Write) -> io::Result<()> {
+    const SCALE: usize = 8;
+
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" shape-rendering="crispEdges">"#,
+        image.width() * SCALE,
+        image.height() * SCALE,
+        image.width(),
+        image.height()
+    )?;
+
+    for ((x, y), Rgb(r, g, b)) in image.enumerate() {
+        writeln!(
+            out,
+            r##"<rect x="{}" y="{}" width="1" height="1" fill="#{:02x}{:02x}{:02x}"/>"##,
+            x, y, r, g, b
+        )?;
+    }
+
+    writeln!(out, "</svg>")?;
+    out.flush()
+}
+
+/// Saves `image` in the format named by the path's extension
+pub fn save(image: &Image, path: &Path) -> io::Result<()> {
+    let format = Format::from_path(path).ok_or_else(|| {
+        io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} should end in .ppm or .svg", path.display()),
+        )
+    })?;
+
+    format.write(image, BufWriter::new(File::create(path)?))
+}
+
+/// Numbered images in a directory, one for each step of a simulation
+#[derive(Debug)]
+pub struct Frames {
+    dir: PathBuf,
+    format: Format,
+    count: usize,
+}
+
+impl Frames {
+    pub fn new(dir: impl Into<PathBuf>, format: Format) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+
+        Ok(Frames {
+            dir,
+            format,
+            count: 0,
+        })
+    }
+
+    /// PPM frames for one part of a day, if the current [`Options`] have somewhere to put them
+    pub fn for_part(year: u16, day: u8, part: u8) -> io::Result<Option<Self>> {
+        let Some(dir) = Options::current().frames else {
+            return Ok(None);
+        };
+
+        let dir = dir
+            .join(year.to_string())
+            .join(format!("day{}", day))
+            .join(format!("part{}", part));
+
+        Frames::new(dir, Format::Ppm).map(Some)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// How many frames have been written
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Writes the next frame, returning where to
+    pub fn push(&mut self, image: &Image) -> io::Result<PathBuf> {
+        let path = self
+            .dir
+            .join(format!("{:05}.{}", self.count, self.format.extension()));
+
+        self.format
+            .write(image, BufWriter::new(File::create(&path)?))?;
+        self.count += 1;
+
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkerboard() -> Image {
+        let mut image = Grid::new(2, 2, Rgb::BLACK);
+        image[(0, 0)] = Rgb::WHITE;
+        image[(1, 1)] = Rgb(255, 0, 16);
+        image
+    }
+
+    #[test]
+    fn test_plot() {
+        let bounds = Bounds::new(Point::new(-1, 3), Point::new(0, 4));
+        let points = [
+            (Point::new(-1, 3), Rgb::WHITE),
+            (Point::new(0, 4), Rgb(255, 0, 16)),
+            (Point::new(5, 5), Rgb::WHITE),
+        ];
+
+        assert_eq!(plot(&bounds, points, Rgb::BLACK), checkerboard());
+    }
+
+    #[test]
+    fn test_formats() -> io::Result<()> {
+        let mut ppm = Vec::new();
+        write_ppm(&checkerboard(), &mut ppm)?;
+        assert_eq!(
+            ppm,
+            b"P6\n2 2\n255\n\xff\xff\xff\0\0\0\0\0\0\xff\0\x10".to_vec()
+        );
+
+        let mut svg = Vec::new();
+        write_svg(&checkerboard(), &mut svg)?;
+        let svg = String::from_utf8(svg).unwrap();
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains(r##"<rect x="1" y="1" width="1" height="1" fill="#ff0010"/>"##));
+        assert_eq!(svg.matches("<rect").count(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_frames() -> io::Result<()> {
+        let dir = std::env::temp_dir().join(format!("aoc-frames-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut frames = Frames::new(&dir, Format::Svg)?;
+        frames.push(&checkerboard())?;
+        let second = frames.push(&checkerboard())?;
+
+        assert_eq!(frames.count(), 2);
+        assert_eq!(second, dir.join("00001.svg"));
+        assert!(fs::read_to_string(second)?.ends_with("</svg>\n"));
+
+        let err = save(&checkerboard(), &dir.join("frame.png")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+        assert!(Frames::for_part(2021, 11, 1)?.is_none());
+        let options = Options {
+            frames: Some(dir.clone()),
+            ..Options::default()
+        };
+        let mut frames = options.scope(|| Frames::for_part(2021, 11, 1))?.unwrap();
+        assert_eq!(
+            frames.push(&checkerboard())?,
+            dir.join("2021/day11/part1/00000.ppm")
+        );
+
+        fs::remove_dir_all(&dir)
+    }
+}
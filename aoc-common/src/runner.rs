@@ -0,0 +1,537 @@
+//! A registry of each year's solutions, so they can be run from outside of their own crate.
+//!
+//! Each year declares its solutions once, in its library, with [`solutions!`](crate::solutions),
+//! using the same table as `aoc_main::main!`. That provides both the year's own command line and
+//! a [`Year`] listing every [`Day`] for the workspace runner.
+use std::any::Any;
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// The outcome of running a generator or a solution
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outcome {
+    pub name: &'static str,
+    pub duration: Duration,
+    /// The answer, or the error message. Generators have an empty answer.
+    pub result: Result<String, String>,
+    /// Whether the error is a panic, rather than one the generator or solution returned
+    pub panicked: bool,
+}
+
+/// The message a panic was started with
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "Box<dyn Any>"
+    }
+}
+
+impl Outcome {
+    /// Times `f`, and names the outcome. A panic in `f` becomes the outcome's error, so that
+    /// one broken solution doesn't stop the rest from running.
+    pub fn time(name: &'static str, f: impl FnOnce() -> Result<String, String>) -> Self {
+        let start = Instant::now();
+        // Nothing `f` was in the middle of changing is looked at again after it panics
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+        let duration = start.elapsed();
+
+        match result {
+            Ok(result) => Outcome {
+                name,
+                duration,
+                result,
+                panicked: false,
+            },
+            Err(payload) => Outcome {
+                name,
+                duration,
+                result: Err(format!("Panicked: {}", panic_message(&*payload))),
+                panicked: true,
+            },
+        }
+    }
+}
+
+/// Everything that happened while running a day
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Report {
+    /// `None` when the day takes its input as it is
+    pub generator: Option<Outcome>,
+    pub solutions: Vec<Outcome>,
+}
+
+impl Report {
+    /// The first of the generator and solutions to panic, if any did
+    pub fn panic(&self) -> Option<&Outcome> {
+        self.generator
+            .iter()
+            .chain(&self.solutions)
+            .find(|outcome| outcome.panicked)
+    }
+}
+
+/// Picks solutions to run by their name
+pub type Filter<'a> = &'a dyn Fn(&str) -> bool;
+
+/// How the runner was asked to run a day, for the few solutions that answer differently.
+///
+/// [`Day::run`] makes these the [`current`](Options::current) options while the day runs. Anything
+/// else, such as the tests or a year's own command line, runs with the defaults.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Options {
+    /// Answer with the drawings some puzzles make, rather than the letters read from them
+    pub art: bool,
+    /// Where the simulations which draw each step write their [frames](crate::render::Frames)
+    pub frames: Option<PathBuf>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Options> = RefCell::new(Options::default());
+}
+
+/// Puts back the options replaced by [`Options::scope`], even if the day panics
+struct Restore(Option<Options>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        if let Some(previous) = self.0.take() {
+            CURRENT.with(|current| current.replace(previous));
+        }
+    }
+}
+
+impl Options {
+    /// The options of the day running on this thread
+    pub fn current() -> Options {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    /// Runs `f` with these as the current options
+    pub fn scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        let _restore = Restore(Some(CURRENT.with(|current| current.replace(self.clone()))));
+        f()
+    }
+}
+
+/// A day's generator and solutions
+#[derive(Clone, Copy, Debug)]
+pub struct Day {
+    pub year: u16,
+    pub day: u8,
+    /// The name of each solution, in the order they run
+    pub solutions: &'static [&'static str],
+    /// Parses the input, then runs each solution whose name is accepted by the filter, with the
+    /// options as the current ones
+    pub run: fn(&str, Filter<'_>, &Options) -> Report,
+}
+
+impl Day {
+    /// Runs every solution
+    pub fn run_all(&self, input: &str) -> Report {
+        self.run_with(input, None, &Options::default())
+    }
+
+    /// Runs the solutions for one part of the puzzle, or all of them, with the given options
+    pub fn run_with(&self, input: &str, part: Option<u8>, options: &Options) -> Report {
+        match part {
+            Some(part) => (self.run)(input, &|name| part_number(name) == Some(part), options),
+            None => (self.run)(input, &|_| true, options),
+        }
+    }
+
+    /// Whether any solution answers `part` of the puzzle
+    pub fn has_part(&self, part: u8) -> bool {
+        self.solutions
+            .iter()
+            .any(|name| part_number(name) == Some(part))
+    }
+
+    /// Runs only the generator, or returns `None` if the day takes its input as it is
+    pub fn parse(&self, input: &str) -> Option<Result<(), String>> {
+        let outcome = (self.run)(input, &|_| false, &Options::default()).generator?;
+
+        Some(outcome.result.map(drop))
+    }
+
+    /// Runs the solutions for one part of the puzzle
+    pub fn run_part(&self, input: &str, part: u8) -> Report {
+        self.run_with(input, Some(part), &Options::default())
+    }
+}
+
+/// Every day registered for a year
+#[derive(Clone, Debug)]
+pub struct Year {
+    pub year: u16,
+    pub days: Vec<Day>,
+}
+
+impl Year {
+    pub fn day(&self, day: u8) -> Option<&Day> {
+        self.days.iter().find(|registered| registered.day == day)
+    }
+}
+
+/// Which part of the puzzle a solution answers, from names like `part1`, `part_2` or
+/// `part2_hashset`
+pub fn part_number(name: &str) -> Option<u8> {
+    let digits = name.strip_prefix("part")?.trim_start_matches('_');
+    let end = digits
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(digits.len());
+
+    digits[..end].parse().ok()
+}
+
+/// Declares a year's solutions, with the same table as `aoc_main::main!`:
+///
+/// ```ignore
+/// aoc_common::solutions! {
+///     year 2022;
+///     day01             => part1, part2;
+///     day03 :parse      => part1, part2;
+///     day05 :parse?     => part_1?, part_2?;
+/// }
+/// ```
+///
+/// This expands to a `solutions()` function returning the [`Year`], a `cli` module whose `run()`
+/// is the year's own `aoc_main` command line, a test checking the solutions against any
+/// [recorded answers](crate::answers), and a test replaying the year's [fuzz corpus](crate::fuzz)
+/// through its generators and solutions. The day modules must be in scope.
+#[macro_export]
+macro_rules! solutions {
+    ( year $year: expr; $( $tail: tt )* ) => {
+        /// Every day solved for the year
+        pub fn solutions() -> $crate::runner::Year {
+            // `aoc_main::parse!` calls back into a macro by its bare name
+            #[allow(unused_imports)]
+            use $crate::register_day;
+
+            $crate::runner::Year {
+                year: $year,
+                days: $crate::aoc_main::parse! { register_day { $year }; $( $tail )* },
+            }
+        }
+
+        /// The year's own command line
+        #[allow(dead_code)]
+        pub mod cli {
+            use super::*;
+
+            $crate::aoc_main::main! { year $year; $( $tail )* }
+
+            pub fn run() {
+                main()
+            }
+        }
+
+        /// Checks every day with a recorded input against its accepted answers
+        #[cfg(test)]
+        mod recorded {
+            #[test]
+            fn test_recorded_answers() {
+                $crate::answers::assert_recorded(&super::solutions(), env!("CARGO_MANIFEST_DIR"));
+            }
+        }
+
+        /// Checks no generator or solution panics on the year's fuzz corpus
+        #[cfg(test)]
+        mod fuzz_corpus {
+            #[test]
+            fn test_fuzz_corpus() {
+                $crate::fuzz::assert_corpus(&super::solutions(), env!("CARGO_MANIFEST_DIR"));
+            }
+        }
+    };
+}
+
+/// Builds a [`Day`] from the token tree `aoc_main::parse!` produces for it
+#[doc(hidden)]
+#[macro_export]
+macro_rules! register_day {
+    ( { $year: expr }, { day $day: ident { $gen: tt { $( $sol: tt )* } } } ) => {
+        $crate::runner::Day {
+            year: $year,
+            day: stringify!($day)[3..].parse().expect("days must be integers"),
+            solutions: &[ $( $crate::solution_name!($sol) ),* ],
+            run: |data, selected, options| {
+                options.scope(|| {
+                    let mut report = $crate::runner::Report::default();
+
+                    if let Some(input) = $crate::run_generator!(report, $day, data, $gen) {
+                        $( $crate::run_solution!(report, selected, $day, &input, $sol); )*
+                    }
+
+                    report
+                })
+            },
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! solution_name {
+    ({ $kind: tt $solution: ident }) => {
+        stringify!($solution)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! run_generator {
+    ( $report: ident, $day: ident, $data: expr, { gen_default } ) => {{
+        Some($data)
+    }};
+    ( $report: ident, $day: ident, $data: expr, { gen $generator: ident } ) => {{
+        let mut input = None;
+        $report.generator = Some($crate::runner::Outcome::time("generator", || {
+            input = Some($day::$generator($data));
+            Ok(String::new())
+        }));
+        input
+    }};
+    ( $report: ident, $day: ident, $data: expr, { gen_fallible $generator: ident } ) => {{
+        use $crate::aoc_main::utils::TryUnwrap;
+
+        let mut input = None;
+        $report.generator = Some($crate::runner::Outcome::time("generator", || {
+            input = Some($day::$generator($data).try_unwrap()?);
+            Ok(String::new())
+        }));
+        input
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! run_solution {
+    ( $report: ident, $selected: ident, $day: ident, $input: expr, { sol $solution: ident } ) => {{
+        if $selected(stringify!($solution)) {
+            $report
+                .solutions
+                .push($crate::runner::Outcome::time(stringify!($solution), || {
+                    Ok($day::$solution($input).to_string())
+                }));
+        }
+    }};
+    (
+        $report: ident, $selected: ident, $day: ident, $input: expr,
+        { sol_fallible $solution: ident }
+    ) => {{
+        use $crate::aoc_main::utils::TryUnwrap;
+
+        if $selected(stringify!($solution)) {
+            $report
+                .solutions
+                .push($crate::runner::Outcome::time(stringify!($solution), || {
+                    $day::$solution($input)
+                        .try_unwrap()
+                        .map(|answer| answer.to_string())
+                }));
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod day01 {
+        pub fn part1(input: &str) -> usize {
+            input.len()
+        }
+
+        pub fn part2(input: &str) -> Result<usize, String> {
+            input.parse::<usize>().map_err(|err| err.to_string())
+        }
+    }
+
+    mod day03 {
+        pub fn parse(input: &str) -> Option<Vec<u32>> {
+            input.chars().map(|c| c.to_digit(10)).collect()
+        }
+
+        pub fn part_1(input: &[u32]) -> u32 {
+            input.iter().sum()
+        }
+
+        pub fn part_2_fast(input: &[u32]) -> Option<u32> {
+            input.iter().max().copied()
+        }
+
+        pub fn part_2_slow(input: &[u32]) -> Option<u32> {
+            input.iter().copied().reduce(u32::max)
+        }
+    }
+
+    mod day04 {
+        pub fn part1(_: &str) -> bool {
+            super::Options::current().art
+        }
+    }
+
+    mod day05 {
+        pub fn generator(input: &str) -> Vec<u8> {
+            match input {
+                "panic" => panic!("Unexpected {:?}", input),
+                _ => input.bytes().collect(),
+            }
+        }
+
+        pub fn part1(input: &[u8]) -> u8 {
+            input[0]
+        }
+
+        pub fn part2(input: &[u8]) -> usize {
+            input.len()
+        }
+    }
+
+    crate::solutions! {
+        year 2015;
+        day01         => part1, part2?;
+        day03 :parse? => part_1, part_2_fast?, part_2_slow?;
+        day04         => part1;
+        day05 :generator => part1, part2;
+    }
+
+    fn answers(report: &Report) -> Vec<(&str, Result<&str, &str>)> {
+        report
+            .solutions
+            .iter()
+            .map(|outcome| {
+                (
+                    outcome.name,
+                    outcome.result.as_deref().map_err(String::as_str),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_registry() {
+        let year = solutions();
+        assert_eq!(year.year, 2015);
+        assert_eq!(
+            year.days
+                .iter()
+                .map(|day| (day.day, day.solutions))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, &["part1", "part2"][..]),
+                (3, &["part_1", "part_2_fast", "part_2_slow"][..]),
+                (4, &["part1"][..]),
+                (5, &["part1", "part2"][..])
+            ]
+        );
+        assert!(year.day(2).is_none());
+        assert!(year.day(3).unwrap().has_part(2));
+        assert!(!year.day(3).unwrap().has_part(3));
+    }
+
+    #[test]
+    fn test_run() {
+        let year = solutions();
+
+        let report = year.day(1).unwrap().run_all("12");
+        assert_eq!(report.generator, None);
+        assert_eq!(
+            answers(&report),
+            vec![("part1", Ok("2")), ("part2", Ok("12"))]
+        );
+
+        let report = year.day(1).unwrap().run_part("x", 2);
+        assert_eq!(
+            answers(&report),
+            vec![("part2", Err("invalid digit found in string"))]
+        );
+
+        let report = year.day(3).unwrap().run_part("192", 2);
+        assert_eq!(
+            report.generator.as_ref().map(|outcome| &outcome.result),
+            Some(&Ok(String::new()))
+        );
+        assert_eq!(
+            answers(&report),
+            vec![("part_2_fast", Ok("9")), ("part_2_slow", Ok("9"))]
+        );
+
+        assert_eq!(year.day(1).unwrap().parse("x"), None);
+        assert_eq!(year.day(3).unwrap().parse("19"), Some(Ok(())));
+        assert_eq!(
+            year.day(3).unwrap().parse("1x"),
+            Some(Err(String::from("empty output")))
+        );
+
+        let report = year.day(3).unwrap().run_all("1x");
+        assert_eq!(
+            report.generator.map(|outcome| outcome.result),
+            Some(Err(String::from("empty output")))
+        );
+        assert!(report.solutions.is_empty());
+    }
+
+    #[test]
+    fn test_panics() {
+        let day = *solutions().day(5).unwrap();
+
+        let report = day.run_all("");
+        assert_eq!(
+            answers(&report),
+            vec![
+                (
+                    "part1",
+                    Err("Panicked: index out of bounds: the len is 0 but the index is 0")
+                ),
+                ("part2", Ok("0"))
+            ]
+        );
+        assert_eq!(report.panic().map(|outcome| outcome.name), Some("part1"));
+
+        let report = day.run_all("panic");
+        assert_eq!(
+            report
+                .generator
+                .as_ref()
+                .map(|outcome| outcome.result.clone()),
+            Some(Err(String::from("Panicked: Unexpected \"panic\"")))
+        );
+        assert!(report.solutions.is_empty());
+        assert_eq!(
+            report.panic().map(|outcome| outcome.name),
+            Some("generator")
+        );
+
+        assert_eq!(day.run_all("1").panic(), None);
+    }
+
+    #[test]
+    fn test_options() {
+        let day = *solutions().day(4).unwrap();
+        let art = Options {
+            art: true,
+            ..Options::default()
+        };
+
+        assert_eq!(answers(&day.run_all("")), vec![("part1", Ok("false"))]);
+        assert_eq!(
+            answers(&day.run_with("", Some(1), &art)),
+            vec![("part1", Ok("true"))]
+        );
+        assert_eq!(Options::current(), Options::default());
+    }
+
+    #[test]
+    fn test_part_number() {
+        assert_eq!(part_number("part1"), Some(1));
+        assert_eq!(part_number("part_2"), Some(2));
+        assert_eq!(part_number("part2_hashset"), Some(2));
+        assert_eq!(part_number("generator"), None);
+    }
+}
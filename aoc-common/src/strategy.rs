@@ -0,0 +1,88 @@
+//! Inputs for property testing parsers: puzzle inputs spoiled in small ways, and text which
+//! looks nothing like one. Parsers should reject them with an error rather than panic.
+//!
+//! Each day generates its own valid inputs, since only it knows their shape.
+use proptest::collection::{vec, SizeRange};
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+/// Printable ASCII and newlines, which most inputs are made of, or any text at all
+pub fn garbage() -> impl Strategy<Value = String> {
+    prop_oneof![3 => "[ -~\n]{0,64}", 1 => any::<String>()]
+}
+
+/// Lines joined by newlines, with no newline after the last
+pub fn lines(
+    line: impl Strategy<Value = String>,
+    size: impl Into<SizeRange>,
+) -> impl Strategy<Value = String> {
+    vec(line, size).prop_map(|lines| lines.join("\n"))
+}
+
+#[derive(Clone, Debug)]
+enum Edit {
+    Remove,
+    Replace(char),
+    Insert(char),
+    Truncate,
+}
+
+/// A character likely to be in an input, or any character
+fn character() -> impl Strategy<Value = char> {
+    prop_oneof![
+        3 => proptest::char::range(' ', '~'),
+        1 => Just('\n'),
+        1 => any::<char>(),
+    ]
+}
+
+fn edit() -> impl Strategy<Value = Edit> {
+    prop_oneof![
+        Just(Edit::Remove),
+        character().prop_map(Edit::Replace),
+        character().prop_map(Edit::Insert),
+        Just(Edit::Truncate),
+    ]
+}
+
+/// A valid input with one character removed, replaced or inserted, or cut short. The result is
+/// usually, but not always, invalid.
+pub fn malformed(valid: impl Strategy<Value = String>) -> impl Strategy<Value = String> {
+    (valid, edit(), any::<Index>()).prop_map(|(valid, edit, index)| {
+        let mut chars = valid.chars().collect::<Vec<_>>();
+        let at = index.index(chars.len() + 1);
+
+        match edit {
+            Edit::Remove if at < chars.len() => {
+                chars.remove(at);
+            }
+            Edit::Replace(c) if at < chars.len() => chars[at] = c,
+            Edit::Replace(c) | Edit::Insert(c) => chars.insert(at, c),
+            Edit::Remove | Edit::Truncate => chars.truncate(at),
+        }
+
+        chars.into_iter().collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_lines(text in lines("[a-z]{1,3}", 2..5)) {
+            prop_assert!((2..5).contains(&text.lines().count()));
+            prop_assert!(!text.ends_with('\n'));
+        }
+
+        #[test]
+        fn test_malformed(
+            (valid, spoiled) in "[a-z]{0,8}".prop_flat_map(|valid| {
+                (Just(valid.clone()), malformed(Just(valid)))
+            })
+        ) {
+            prop_assert!(spoiled.chars().count() <= valid.chars().count() + 1);
+        }
+    }
+}
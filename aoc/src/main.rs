@@ -0,0 +1,210 @@
+use anyhow::{anyhow, bail, Result};
+use aoc_common::answers::{self, Recorded};
+use aoc_common::aoc_main::colored::Colorize;
+use aoc_common::aoc_main::input;
+use aoc_common::aoc_main::utils::Line;
+use aoc_common::runner::{Day, Options, Outcome, Report, Year};
+use clap::Parser;
+use itertools::Itertools;
+use output::{Format, Row, Writer};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+mod output;
+
+/// Runs the solutions for any year, e.g. `aoc 2021 14 2` or `aoc 2022 all`
+#[derive(Parser)]
+#[command(name = "aoc")]
+struct Cli {
+    /// Year to run, or `all`
+    #[arg(required_unless_present = "list")]
+    year: Option<Selection<u16>>,
+    /// Day to run, or `all`
+    #[arg(default_value = "all")]
+    day: Selection<u8>,
+    /// Only run the solutions for this part
+    part: Option<u8>,
+    /// List the implemented days instead of running them
+    #[arg(short, long)]
+    list: bool,
+    /// Read the input from a file instead of the cache of downloaded inputs
+    #[arg(short, long)]
+    file: Option<PathBuf>,
+    /// Save the input and answers as the accepted ones, for the regression tests
+    #[arg(short, long, conflicts_with = "part")]
+    record: bool,
+    /// Answer with the drawings some puzzles make, rather than the letters read from them
+    #[arg(short, long)]
+    art: bool,
+    /// Write a frame for each step of the simulations which draw them, under this directory
+    #[arg(long, value_name = "DIR")]
+    frames: Option<PathBuf>,
+    /// How to print the results
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+/// Either every year or day, or one in particular
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Selection<T> {
+    All,
+    One(T),
+}
+
+impl<T: PartialEq> Selection<T> {
+    fn contains(&self, value: T) -> bool {
+        match self {
+            Selection::All => true,
+            Selection::One(selected) => *selected == value,
+        }
+    }
+}
+
+impl<T: FromStr> FromStr for Selection<T> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Selection::All),
+            _ => s
+                .parse()
+                .map(Selection::One)
+                .map_err(|_| format!("expected a number or `all`, found {:?}", s)),
+        }
+    }
+}
+
+fn years() -> Vec<Year> {
+    vec![
+        aoc_2019::solutions(),
+        aoc_2020::solutions(),
+        aoc_2021::solutions(),
+        aoc_2022::solutions(),
+        aoc_2023::solutions(),
+    ]
+}
+
+fn list(years: &[Year], selection: Selection<u16>) {
+    for year in years.iter().filter(|year| selection.contains(year.year)) {
+        println!("{}", year.year);
+
+        for day in &year.days {
+            println!("  day {:>2}: {}", day.day, day.solutions.join(", "));
+        }
+    }
+}
+
+fn print_outcome(outcome: &Outcome) {
+    let line = Line::new(outcome.name).with_duration(outcome.duration);
+
+    match &outcome.result {
+        Ok(answer) if answer.is_empty() => println!("  - {}", line),
+        Ok(answer) => println!("  - {}", line.with_state(answer.normal())),
+        Err(message) => println!("  - {}", line.with_state(message.red())),
+    }
+}
+
+fn print_report(report: &Report) {
+    report.generator.iter().for_each(print_outcome);
+    report.solutions.iter().for_each(print_outcome);
+}
+
+fn read_input(day: &Day, file: Option<&PathBuf>, dir: &Path) -> Result<String> {
+    if let Some(path) = file {
+        return Ok(fs::read_to_string(path)?);
+    }
+
+    match Recorded::load(dir, day)? {
+        Some(recorded) => Ok(recorded.input),
+        None => input::get_input(day.year, day.day).map_err(|err| anyhow!("{}", err)),
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let years = years();
+    let year_selection = cli.year.unwrap_or(Selection::All);
+    let dir = answers::input_dir(env!("CARGO_MANIFEST_DIR"));
+
+    let options = Options {
+        art: cli.art,
+        frames: cli.frames.clone(),
+    };
+
+    if cli.list {
+        list(&years, year_selection);
+        return Ok(());
+    }
+
+    let days = years
+        .iter()
+        .filter(|year| year_selection.contains(year.year))
+        .flat_map(|year| &year.days)
+        .filter(|day| cli.day.contains(day.day))
+        .collect_vec();
+
+    match (days.len(), year_selection, cli.day) {
+        (0, Selection::One(year), Selection::One(day)) => {
+            bail!("Day {} of {} is not implemented", day, year)
+        }
+        (0, _, _) => bail!("Nothing is implemented for that selection"),
+        (1, _, _) => {}
+        _ if cli.file.is_some() => bail!("An input file can only be given for a single day"),
+        _ => {}
+    }
+
+    let text = cli.format == Format::Text;
+    let mut writer = Writer::new(io::stdout(), cli.format);
+
+    for (i, day) in days.into_iter().enumerate() {
+        if text {
+            if i != 0 {
+                println!();
+            }
+            println!("{} Day {}", day.year, day.day);
+        }
+
+        let input = match read_input(day, cli.file.as_ref(), &dir) {
+            Ok(input) => input,
+            Err(err) if text => {
+                let line = Line::new("input").with_state(err.to_string().red());
+                println!("  - {}", line);
+                continue;
+            }
+            Err(err) => {
+                writer.write(&Row::failure(day, "input", &err.to_string()))?;
+                continue;
+            }
+        };
+
+        let report = match cli.part {
+            Some(part) if !day.has_part(part) => {
+                if text {
+                    println!("  - {}", "nothing to run for that part".bright_black());
+                }
+                continue;
+            }
+            part => day.run_with(&input, part, &options),
+        };
+
+        if text {
+            print_report(&report);
+        } else {
+            for row in Row::from_report(day, &report) {
+                writer.write(&row)?;
+            }
+        }
+
+        if cli.record {
+            let recorded = Recorded::save(&dir, day, &input, &report)?;
+            if text {
+                let parts = recorded.answers.keys().join(", ");
+                println!("  - {}", Line::new(format!("recorded parts {}", parts)));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,215 @@
+//! Results in a form other programs can read: a row for each solution run, with its answer and
+//! how long it took to parse the input and to solve
+use aoc_common::runner::{part_number, Day, Outcome, Report};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// For people, with coloured answers
+    Text,
+    /// One JSON object per line
+    Json,
+    /// Comma separated values, with a header
+    Csv,
+}
+
+/// The outcome of one solution, or of whatever stopped the solutions running
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Row<'a> {
+    pub year: u16,
+    pub day: u8,
+    /// `None` when the row is not for a solution
+    pub part: Option<u8>,
+    pub solution: &'a str,
+    pub answer: Option<&'a str>,
+    pub error: Option<&'a str>,
+    /// Nanoseconds spent in the generator, if the day has one
+    pub parse_ns: Option<u64>,
+    /// Nanoseconds spent in the solution
+    pub solve_ns: Option<u64>,
+}
+
+const CSV_HEADER: &str = "year,day,part,solution,answer,error,parse_ns,solve_ns";
+
+fn nanoseconds(outcome: &Outcome) -> u64 {
+    outcome.duration.as_nanos().try_into().unwrap_or(u64::MAX)
+}
+
+impl<'a> Row<'a> {
+    /// A row for something that went wrong before any solution could run, like reading the input
+    pub fn failure(day: &Day, name: &'a str, error: &'a str) -> Self {
+        Row {
+            year: day.year,
+            day: day.day,
+            part: None,
+            solution: name,
+            answer: None,
+            error: Some(error),
+            parse_ns: None,
+            solve_ns: None,
+        }
+    }
+
+    /// A row for each solution in the report, or one for the generator if it failed
+    pub fn from_report(day: &Day, report: &'a Report) -> Vec<Self> {
+        let parse_ns = report.generator.as_ref().map(nanoseconds);
+
+        if let Some(Outcome {
+            name,
+            result: Err(error),
+            ..
+        }) = &report.generator
+        {
+            return vec![Row {
+                parse_ns,
+                ..Row::failure(day, name, error)
+            }];
+        }
+
+        report
+            .solutions
+            .iter()
+            .map(|outcome| Row {
+                year: day.year,
+                day: day.day,
+                part: part_number(outcome.name),
+                solution: outcome.name,
+                answer: outcome.result.as_deref().ok(),
+                error: outcome.result.as_ref().err().map(String::as_str),
+                parse_ns,
+                solve_ns: Some(nanoseconds(outcome)),
+            })
+            .collect()
+    }
+}
+
+/// Quotes a CSV field if it needs to be
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains(['"', ',', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Writes rows in one of the machine readable formats
+pub struct Writer<W> {
+    out: W,
+    format: Format,
+    started: bool,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out: W, format: Format) -> Self {
+        Writer {
+            out,
+            format,
+            started: false,
+        }
+    }
+
+    pub fn write(&mut self, row: &Row) -> io::Result<()> {
+        match self.format {
+            Format::Text => {}
+            Format::Json => {
+                serde_json::to_writer(&mut self.out, row)?;
+                writeln!(self.out)?;
+            }
+            Format::Csv => {
+                if !self.started {
+                    writeln!(self.out, "{}", CSV_HEADER)?;
+                }
+
+                let optional = |value: Option<String>| value.unwrap_or_default();
+                let fields = [
+                    row.year.to_string(),
+                    row.day.to_string(),
+                    optional(row.part.map(|part| part.to_string())),
+                    row.solution.to_string(),
+                    row.answer.map(csv_field).unwrap_or_default().into_owned(),
+                    row.error.map(csv_field).unwrap_or_default().into_owned(),
+                    optional(row.parse_ns.map(|ns| ns.to_string())),
+                    optional(row.solve_ns.map(|ns| ns.to_string())),
+                ];
+                writeln!(self.out, "{}", fields.join(","))?;
+            }
+        }
+
+        self.started = true;
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const DAY: Day = Day {
+        year: 2021,
+        day: 13,
+        solutions: &["part1", "part2"],
+        run: |_, _, _| Report::default(),
+    };
+
+    fn outcome(name: &'static str, nanos: u64, result: Result<&str, &str>) -> Outcome {
+        Outcome {
+            name,
+            duration: Duration::from_nanos(nanos),
+            result: result.map(String::from).map_err(String::from),
+            panicked: false,
+        }
+    }
+
+    fn written(format: Format, rows: &[Row]) -> String {
+        let mut writer = Writer::new(Vec::new(), format);
+        for row in rows {
+            writer.write(row).unwrap();
+        }
+
+        String::from_utf8(writer.out).unwrap()
+    }
+
+    #[test]
+    fn test_rows() {
+        let report = Report {
+            generator: Some(outcome("generator", 5, Ok(""))),
+            solutions: vec![
+                outcome("part1", 7, Ok("17")),
+                outcome("part2", 9, Err("Letter 0 is not, \"known\"")),
+            ],
+        };
+        let rows = Row::from_report(&DAY, &report);
+
+        assert_eq!(
+            written(Format::Json, &rows),
+            r#"{"year":2021,"day":13,"part":1,"solution":"part1","answer":"17","error":null,"parse_ns":5,"solve_ns":7}
+{"year":2021,"day":13,"part":2,"solution":"part2","answer":null,"error":"Letter 0 is not, \"known\"","parse_ns":5,"solve_ns":9}
+"#
+        );
+        assert_eq!(
+            written(Format::Csv, &rows),
+            r#"year,day,part,solution,answer,error,parse_ns,solve_ns
+2021,13,1,part1,17,,5,7
+2021,13,2,part2,,"Letter 0 is not, ""known""",5,9
+"#
+        );
+    }
+
+    #[test]
+    fn test_failed_generator() {
+        let report = Report {
+            generator: Some(outcome("generator", 3, Err("Day 13, line 1"))),
+            solutions: Vec::new(),
+        };
+
+        assert_eq!(
+            written(Format::Csv, &Row::from_report(&DAY, &report)),
+            "year,day,part,solution,answer,error,parse_ns,solve_ns\n\
+             2021,13,,generator,,\"Day 13, line 1\",3,\n"
+        );
+    }
+}
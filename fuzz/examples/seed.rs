@@ -0,0 +1,132 @@
+//! Seeds each year's fuzz corpus with the samples from its days' tests: every string constant in
+//! a day's module, and every string literal given straight to its generator.
+//!
+//! `cargo run --example seed` from `fuzz/`. Files already in the corpus are left alone.
+use aoc_common::fuzz::{data, target};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+use syn::{Expr, ExprCall, ExprLit, ItemConst, ItemStatic, Lit, Macro, Token};
+
+const YEARS: [u16; 5] = [2019, 2020, 2021, 2022, 2023];
+
+/// The names generators go by
+const GENERATORS: [&str; 3] = ["generator", "generate", "parse"];
+
+#[derive(Default)]
+struct Samples {
+    /// By a name for the corpus file, so reseeding writes the same files
+    found: BTreeMap<String, String>,
+}
+
+impl Samples {
+    fn add(&mut self, name: &str, sample: String) {
+        let name = name.to_lowercase();
+        let mut unique = name.clone();
+        for n in 2.. {
+            if !self.found.contains_key(&unique) {
+                break;
+            }
+            unique = format!("{}-{}", name, n);
+        }
+
+        self.found.insert(unique, sample);
+    }
+}
+
+fn string(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) => Some(s.value()),
+        Expr::Reference(reference) => string(&reference.expr),
+        _ => None,
+    }
+}
+
+impl<'ast> Visit<'ast> for Samples {
+    fn visit_item_const(&mut self, item: &'ast ItemConst) {
+        if let Some(sample) = string(&item.expr) {
+            self.add(&item.ident.to_string(), sample);
+        }
+    }
+
+    fn visit_item_static(&mut self, item: &'ast ItemStatic) {
+        if let Some(sample) = string(&item.expr) {
+            self.add(&item.ident.to_string(), sample);
+        }
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+        if let Expr::Path(path) = &*call.func {
+            let name = path
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string());
+
+            if let (Some(name), Some(sample)) = (name, call.args.first().and_then(string)) {
+                if GENERATORS.contains(&name.as_str()) {
+                    self.add(&name, sample);
+                }
+            }
+        }
+
+        visit::visit_expr_call(self, call);
+    }
+
+    /// Most samples are given to generators inside assertions, which syn leaves as tokens
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        if let Ok(args) = Punctuated::<Expr, Token![,]>::parse_terminated.parse2(mac.tokens.clone())
+        {
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+    }
+}
+
+fn seed(root: &Path, year: u16) -> io::Result<usize> {
+    let corpus = root.join("corpus").join(target(year));
+    fs::create_dir_all(&corpus)?;
+
+    let mut written = 0;
+    for entry in fs::read_dir(root.join("..").join(format!("aoc-{}", year)).join("src"))? {
+        let path = entry?.path();
+        let Some(day) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str()?.strip_prefix("day")?.parse::<u8>().ok())
+        else {
+            continue;
+        };
+
+        let file = syn::parse_file(&fs::read_to_string(&path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let mut samples = Samples::default();
+        samples.visit_file(&file);
+
+        for (name, sample) in samples.found {
+            let path = corpus.join(format!("day{:02}-{}", day, name));
+            if !path.exists() {
+                fs::write(path, data(day, &sample))?;
+                written += 1;
+            }
+        }
+    }
+
+    Ok(written)
+}
+
+fn main() -> io::Result<()> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+    for year in YEARS {
+        println!("{}: {} new samples", target(year), seed(root, year)?);
+    }
+
+    Ok(())
+}